@@ -0,0 +1,120 @@
+//! Команда `compare`: сравнение и сверка двух файлов.
+use crate::formats::{self, FormatArgs, Formats};
+use clap::Args;
+use clap::builder::RangedU64ValueParser;
+use parser::{Amount, FuzzyOptions, MatchKey, Reconciliation, Report, ReportFormat, Transaction};
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    #[arg(long, value_name = "FILE", help = "First file (the ledger for --fuzzy)")]
+    file1: PathBuf,
+
+    #[arg(long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Format of the first file; detected when omitted")]
+    format1: Option<String>,
+
+    #[arg(long, value_name = "FILE", help = "Second file (the bank statement for --fuzzy)")]
+    file2: PathBuf,
+
+    #[arg(long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Format of the second file; detected when omitted")]
+    format2: Option<String>,
+
+    #[arg(long, value_name = "FIELDS", conflicts_with = "fuzzy", help = "Match records by key fields, e.g. id or account+amount+currency")]
+    key: Option<MatchKey>,
+
+    #[arg(long, value_name = "FORMAT", default_value = "text", value_parser = ["text", "json", "csv", "junit"], help = "Report format")]
+    report: String,
+
+    #[arg(long, help = "Fuzzy reconciliation with amount and date tolerances", help_heading = "Fuzzy reconciliation")]
+    fuzzy: bool,
+
+    #[arg(long, value_name = "AMOUNT", requires = "fuzzy", help = "Allowed amount difference, e.g. 0.01", help_heading = "Fuzzy reconciliation")]
+    tolerance: Option<Amount>,
+
+    #[arg(long, value_name = "DAYS", default_value_t = 0, requires = "fuzzy", help = "Allowed date difference in days", help_heading = "Fuzzy reconciliation")]
+    date_window: u32,
+
+    #[arg(long, value_name = "N", default_value_t = 1, requires = "fuzzy", value_parser = RangedU64ValueParser::<usize>::new().range(1..=FuzzyOptions::MAX_GROUP as u64), help = "Max ledger records summed into one statement line", help_heading = "Fuzzy reconciliation")]
+    max_group: usize,
+
+    #[arg(long, value_name = "X", default_value_t = 0.0, requires = "fuzzy", help = "Minimum match confidence from 0 to 1", help_heading = "Fuzzy reconciliation")]
+    min_confidence: f64,
+
+    #[command(flatten)]
+    formats: FormatArgs,
+}
+
+/// Сравнивает файлы и печатает отчёт; возвращает, совпали ли они.
+pub fn run(args: CompareArgs) -> Result<bool, Box<dyn Error>> {
+    let format: Option<ReportFormat> = match args.report.as_str() {
+        "text" => None,
+        name => Some(name.parse()?),
+    };
+
+    let formats = Formats::new(&args.formats)?;
+    let a: Vec<Transaction> = formats.open(&args.file1, args.format1.as_deref())?.collect::<Result<_, _>>()?;
+    let b: Vec<Transaction> = formats.open(&args.file2, args.format2.as_deref())?.collect::<Result<_, _>>()?;
+
+    // file1 — учёт, file2 — банковская выписка.
+    if args.fuzzy {
+        let mut options = FuzzyOptions::new()
+            .date_window(args.date_window)
+            .max_group(args.max_group)
+            .min_confidence(args.min_confidence);
+        if let Some(tolerance) = args.tolerance {
+            options = options.amount_tolerance(tolerance);
+        }
+
+        let reconciliation = parser::reconcile_fuzzy(&a, &b, &options);
+        let report = Report::from_fuzzy(&reconciliation);
+        match format {
+            Some(format) => report.write(format, io::stdout().lock())?,
+            None => print_text(&report, true),
+        }
+        return Ok(report.identical);
+    }
+
+    // Без ключа записи сравниваются по позиции, с ключом — сверяются в любом порядке.
+    let reconciliation = match &args.key {
+        Some(key) => parser::reconcile(&a, &b, key),
+        None => Reconciliation { diff: parser::diff(&a, &b), duplicates: Vec::new() },
+    };
+    let report = Report::from_reconciliation(&reconciliation);
+    match format {
+        Some(format) => report.write(format, io::stdout().lock())?,
+        None => print_text(&report, false),
+    }
+    Ok(report.identical)
+}
+
+/// Печатает отчёт текстом: соответствия, расхождения и итоговую сводку.
+fn print_text(report: &Report, fuzzy: bool) {
+    report.matches.iter().for_each(|found| println!("{}", found));
+    report.mismatches.iter().for_each(|mismatch| println!("{}", mismatch));
+
+    let summary = &report.summary;
+    if fuzzy {
+        let grouped = report.matches.iter().filter(|found| found.file1_records.len() > 1).count();
+        println!(
+            "{} matched ({} grouped, {} within tolerance), {} only in file1, {} only in file2",
+            report.matches.len(),
+            grouped,
+            summary.near_matches,
+            summary.only_in_file1,
+            summary.only_in_file2
+        );
+    } else if report.identical {
+        println!("Files are same! ({} records)", summary.matched);
+    } else {
+        println!(
+            "Files differ: {} same, {} changed, {} only in file1, {} only in file2, {} duplicate keys",
+            summary.matched,
+            summary.changed,
+            summary.only_in_file1,
+            summary.only_in_file2,
+            summary.duplicate_keys
+        );
+    }
+}
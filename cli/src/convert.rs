@@ -0,0 +1,115 @@
+//! Команда `convert`: конвертация между форматами.
+use crate::formats::{self, FormatArgs, Formats};
+use clap::Args;
+use parser::{Compression, Encoder, Lenient, ParserError, TransactionWriter};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    #[arg(short, long, value_name = "FILE", help = "Input file; gzip and zstd are decompressed transparently")]
+    input: PathBuf,
+
+    #[arg(long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Input format; detected from content and extension when omitted")]
+    input_format: Option<String>,
+
+    #[arg(short = 'f', long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Output format")]
+    output_format: String,
+
+    #[arg(short, long, value_name = "FILE", help = "Output file [default: stdout]")]
+    output: Option<PathBuf>,
+
+    #[arg(long, value_name = "ALGORITHM", help = "Compress the output: gzip or zstd [default: from the --output extension]")]
+    compress: Option<Compression>,
+
+    #[arg(long, help = "Skip invalid records and report them on stderr")]
+    lenient: bool,
+
+    #[arg(long, value_name = "N", help = "Abort after more than N invalid records (implies --lenient)")]
+    max_errors: Option<usize>,
+
+    #[command(flatten)]
+    formats: FormatArgs,
+}
+
+/// Конвертирует файл; возвращает `false`, если часть записей пропущена.
+pub fn run(args: ConvertArgs) -> Result<bool, Box<dyn Error>> {
+    let formats = Formats::new(&args.formats)?;
+    let transactions = formats.open(&args.input, args.input_format.as_deref())?;
+
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|err| {
+            format!("Failed to create output file '{}': {}", path.display(), err)
+        })?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    // Без --compress сжатие определяется по расширению: out.csv.gz — gzip.
+    let compression = args.compress.or_else(|| args.output.as_deref().and_then(Compression::from_extension));
+    let mut output = match compression {
+        Some(compression) => Output::Compressed(compression.encoder(output)?),
+        None => Output::Plain(output),
+    };
+    let mut writer: Box<dyn TransactionWriter> = formats.writer(&args.output_format, Box::new(&mut output))?;
+
+    let mut clean = true;
+    if args.lenient || args.max_errors.is_some() {
+        let lenient = match args.max_errors {
+            Some(max_errors) => Lenient::new().max_errors(max_errors),
+            None => Lenient::new(),
+        };
+        let mut transactions = lenient.reader(transactions);
+        for tx in transactions.by_ref() {
+            writer.write(&tx?)?;
+        }
+        writer.finish()?;
+
+        for rejected in transactions.rejected() {
+            eprintln!("Rejected record {}: {}", rejected.index, rejected.error);
+            clean = false;
+        }
+    } else {
+        for tx in transactions {
+            writer.write(&tx?)?;
+        }
+        writer.finish()?;
+    }
+
+    drop(writer);
+    output.finish()?;
+    Ok(clean)
+}
+
+/// Поток вывода, сжимаемый по флагу `--compress` или расширению файла.
+enum Output {
+    Plain(Box<dyn Write>),
+    Compressed(Encoder<Box<dyn Write>>),
+}
+
+impl Output {
+    /// Дописывает завершающие данные сжатия и сбрасывает буферы.
+    fn finish(self) -> Result<(), ParserError> {
+        let mut output = match self {
+            Output::Plain(output) => output,
+            Output::Compressed(encoder) => encoder.finish()?,
+        };
+        output.flush().map_err(ParserError::Io)
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(output) => output.write(buf),
+            Output::Compressed(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(output) => output.flush(),
+            Output::Compressed(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Общие для всех команд флаги форматов: выбор формата, диалект CSV
+//! и описание полей для формата фиксированной ширины.
+use clap::Args;
+use clap::builder::PossibleValuesParser;
+use parser::csv::{CsvOptions, HeaderMode};
+use parser::fixed::{FixedFormat, Layout};
+use parser::{Compression, DynFormat, ParserError, Registry, TransactionStream, TransactionWriter};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Флаги диалекта CSV и описания полей.
+#[derive(Debug, Default, Args)]
+pub struct FormatArgs {
+    #[arg(long, value_name = "FILE", help = "Field layout (JSON) for the fixed-width format")]
+    layout: Option<PathBuf>,
+
+    #[arg(long, value_name = "CHAR", value_parser = ascii_byte, help = "CSV field delimiter", help_heading = "CSV dialect")]
+    csv_delimiter: Option<u8>,
+
+    #[arg(long, value_name = "CHAR", value_parser = ascii_byte, help = "CSV quote character", help_heading = "CSV dialect")]
+    csv_quote: Option<u8>,
+
+    #[arg(long, value_name = "CHAR", value_parser = ascii_byte, help = "CSV escape character", help_heading = "CSV dialect")]
+    csv_escape: Option<u8>,
+
+    #[arg(long, value_name = "CHAR", value_parser = ascii_byte, help = "CSV comment line prefix", help_heading = "CSV dialect")]
+    csv_comment: Option<u8>,
+
+    #[arg(long, help = "Trim whitespace around CSV fields", help_heading = "CSV dialect")]
+    csv_trim: bool,
+
+    #[arg(long, value_name = "CHAR", value_parser = [".", ","], help = "CSV decimal separator", help_heading = "CSV dialect")]
+    csv_decimal: Option<String>,
+
+    #[arg(long, value_name = "LABEL", help = "CSV text encoding, e.g. windows-1251", help_heading = "CSV dialect")]
+    csv_encoding: Option<String>,
+
+    #[arg(long, value_name = "MODE", value_parser = header_mode, help = "CSV header row: required, absent or auto", help_heading = "CSV dialect")]
+    csv_header: Option<HeaderMode>,
+}
+
+/// Реестр форматов вместе с настройками CSV.
+///
+/// Формат фиксированной ширины попадает в реестр, только если задан `--layout`.
+pub struct Formats {
+    registry: Registry,
+    csv: Option<CsvOptions>,
+}
+
+impl Formats {
+    /// Собирает настройки из флагов команды.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает ошибку, если описание полей не читается или
+    /// диалект CSV задан неверно.
+    pub fn new(args: &FormatArgs) -> Result<Self, ParserError> {
+        let mut registry = Registry::default();
+        if let Some(path) = &args.layout {
+            registry.register(Box::new(FixedFormat::new(Layout::load(path)?)));
+        }
+
+        Ok(Self { registry, csv: args.csv_options()? })
+    }
+
+    /// Открывает файл с транзакциями.
+    ///
+    /// Сжатый файл распаковывается, а формат, если он не указан,
+    /// определяется по содержимому и по пути без `.gz`/`.zst`.
+    pub fn open(&self, path: &Path, format: Option<&str>) -> Result<TransactionStream<'static>, ParserError> {
+        let file = File::open(path).map_err(|err| {
+            ParserError::Invalid(format!("Failed to open input file '{}': {}", path.display(), err))
+        })?;
+        let (_, input) = parser::decompress(Some(path), file)?;
+
+        Ok(match format {
+            Some(name) => self.reader(self.format(name)?, Box::new(input)),
+            None => {
+                let (format, input) = self.registry.detect(Some(&Compression::strip_extension(path)), input)?;
+                self.reader(format, Box::new(input))
+            }
+        })
+    }
+
+    /// Создаёт писателя в формате `name`.
+    pub fn writer<'a>(&self, name: &str, output: Box<dyn Write + 'a>) -> Result<Box<dyn TransactionWriter + 'a>, ParserError> {
+        let format = self.format(name)?;
+        Ok(match &self.csv {
+            Some(options) if format.name() == "csv" => Box::new(options.writer(output)),
+            _ => format.writer(output),
+        })
+    }
+
+    // Настройки диалекта CSV применяются к той стороне, которая в формате CSV.
+    fn reader(&self, format: &dyn DynFormat, input: Box<dyn Read>) -> TransactionStream<'static> {
+        match &self.csv {
+            Some(options) if format.name() == "csv" => Box::new(options.reader(input)),
+            _ => format.reader(input),
+        }
+    }
+
+    /// Ищет формат в реестре; без `--layout` формата фиксированной ширины
+    /// в нём нет, и ошибка подсказывает, чего не хватает.
+    fn format(&self, name: &str) -> Result<&dyn DynFormat, ParserError> {
+        self.registry.get(name).map_err(|err| match name {
+            FixedFormat::NAME => ParserError::Invalid(format!("The {} format requires --layout <FILE>", name)),
+            _ => err,
+        })
+    }
+}
+
+impl FormatArgs {
+    /// Настройки CSV или `None`, если ни один флаг `--csv-*` не задан.
+    fn csv_options(&self) -> Result<Option<CsvOptions>, ParserError> {
+        let set = self.csv_delimiter.is_some()
+            || self.csv_quote.is_some()
+            || self.csv_escape.is_some()
+            || self.csv_comment.is_some()
+            || self.csv_trim
+            || self.csv_decimal.is_some()
+            || self.csv_encoding.is_some()
+            || self.csv_header.is_some();
+        if !set {
+            return Ok(None);
+        }
+
+        let mut options = CsvOptions::new().trim(self.csv_trim);
+        if let Some(delimiter) = self.csv_delimiter {
+            options = options.delimiter(delimiter);
+        }
+        if let Some(quote) = self.csv_quote {
+            options = options.quote(quote);
+        }
+        if let Some(escape) = self.csv_escape {
+            options = options.escape(escape);
+        }
+        if let Some(comment) = self.csv_comment {
+            options = options.comment(comment);
+        }
+        if let Some(decimal) = &self.csv_decimal {
+            options = options.decimal_separator(decimal.chars().next().unwrap_or('.'))?;
+        }
+        if let Some(encoding) = &self.csv_encoding {
+            options = options.encoding(encoding)?;
+        }
+        if let Some(header) = self.csv_header {
+            options = options.header(header);
+        }
+        Ok(Some(options))
+    }
+}
+
+/// Имена всех доступных форматов: встроенные и формат фиксированной
+/// ширины, который регистрируется при заданном `--layout`.
+pub fn names() -> Vec<&'static str> {
+    let mut names = Registry::default().names();
+    names.push(FixedFormat::NAME);
+    names
+}
+
+/// Разбор флагов формата: неизвестное имя отклоняется ещё при разборе
+/// аргументов, а в сообщении перечисляются все форматы.
+pub fn format_name() -> PossibleValuesParser {
+    PossibleValuesParser::new(names())
+}
+
+fn ascii_byte(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        [byte] if byte.is_ascii() => Ok(*byte),
+        _ => Err(format!("expected a single ASCII character, got {:?}", value)),
+    }
+}
+
+fn header_mode(value: &str) -> Result<HeaderMode, String> {
+    match value {
+        "required" => Ok(HeaderMode::Required),
+        "absent" => Ok(HeaderMode::Absent),
+        "auto" => Ok(HeaderMode::Auto),
+        _ => Err(format!("expected required, absent or auto, got {:?}", value)),
+    }
+}
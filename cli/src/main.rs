@@ -1,57 +1,65 @@
-use parser::{Format, ParserError, binary, csv, txt};
-use std::{env, fs::File, io::stdout};
+//! Утилита `parser`: конвертация, сравнение, проверка и статистика
+//! файлов с транзакциями.
+//!
+//! Коды завершения: `0` — успех (файлы совпадают, файл корректен),
+//! `1` — найдены различия или некорректные записи, `2` — ошибка
+//! аргументов, чтения или записи.
+mod compare;
+mod convert;
+mod formats;
+mod stats;
+mod validate;
 
-fn main() {
-    let mut args = env::args().skip(1);
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
 
-    let mut input_file: Option<String> = None;
-    let mut input_format: Option<String> = None;
-    let mut output_format: Option<String> = None;
+/// Код завершения: успех.
+const EXIT_OK: u8 = 0;
+/// Код завершения: различия или некорректные записи.
+const EXIT_FAILED: u8 = 1;
+/// Код завершения: ошибка; совпадает с кодом clap для неверных аргументов.
+const EXIT_ERROR: u8 = 2;
 
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--input" => input_file = args.next(),
-            "--input-format" => input_format = args.next(),
-            "--output-format" => output_format = args.next(),
-            _ => usage(),
-        }
-    }
-
-    let input_file = input_file.expect("Missing --input");
-    let input_format = input_format.expect("Missing --input-format");
-    let output_format = output_format.expect("Missing --output-format");
-
-    let input = File::open(input_file.clone())
-        .map_err(|err| {
-            ParserError::Invalid(format!(
-                "Failed to open input file '{}' (provided via --input): {}",
-                input_file, err
-            ))
-        })
-        .unwrap();
+#[derive(Debug, Parser)]
+#[command(name = "parser", version, about = "Convert, compare, validate and summarize financial transaction files")]
+#[command(after_help = "Exit codes: 0 success, 1 differences or invalid records, 2 error")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    let transactions = match input_format.as_str() {
-        "bin" => binary::BinParser::read(input),
-        "csv" => csv::Csv::read(input),
-        "txt" => txt::TxtParser::read(input),
-        _ => panic!("unsupported format"),
-    }
-    .unwrap();
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Convert a file to another format
+    Convert(convert::ConvertArgs),
+    /// Compare or reconcile two files
+    Compare(compare::CompareArgs),
+    /// Check that every record of a file is valid
+    Validate(validate::ValidateArgs),
+    /// Show record counts, accounts, period and totals per currency
+    Stats(stats::StatsArgs),
+    /// List supported formats
+    Formats,
+}
 
-    let out = stdout();
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Convert(args) => convert::run(args),
+        Command::Compare(args) => compare::run(args),
+        Command::Validate(args) => validate::run(args),
+        Command::Stats(args) => stats::run(args),
+        Command::Formats => {
+            formats::names().into_iter().for_each(|name| println!("{}", name));
+            Ok(true)
+        }
+    };
 
-    match output_format.as_str() {
-        "csv" => csv::Csv::write(out, &transactions),
-        "txt" => txt::TxtParser::write(out, &transactions),
-        "bin" => binary::BinParser::write(out, &transactions),
-        _ => panic!("unknown output format"),
+    match result {
+        Ok(true) => ExitCode::from(EXIT_OK),
+        Ok(false) => ExitCode::from(EXIT_FAILED),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(EXIT_ERROR)
+        }
     }
-    .unwrap();
-}
-
-fn usage() {
-    panic!(
-        "Usage:
-         converter --input <file> --input-format <csv|txt|bin> --output-format <csv|txt|bin>"
-    );
 }
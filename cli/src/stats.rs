@@ -0,0 +1,51 @@
+//! Команда `stats`: сводка по файлу.
+use crate::formats::{self, FormatArgs, Formats};
+use clap::Args;
+use parser::Stats;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    #[arg(short, long, value_name = "FILE", help = "Input file")]
+    input: PathBuf,
+
+    #[arg(long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Input format; detected from content and extension when omitted")]
+    input_format: Option<String>,
+
+    #[command(flatten)]
+    formats: FormatArgs,
+}
+
+/// Печатает количество записей, счета, период и обороты по валютам.
+pub fn run(args: StatsArgs) -> Result<bool, Box<dyn Error>> {
+    let formats = Formats::new(&args.formats)?;
+
+    let mut stats = Stats::new();
+    for tx in formats.open(&args.input, args.input_format.as_deref())? {
+        stats.add(&tx?)?;
+    }
+
+    println!("Records:  {}", stats.count);
+    println!("Accounts: {}", stats.accounts.len());
+    if let (Some(first), Some(last)) = (stats.first_date, stats.last_date) {
+        println!("Period:   {} .. {}", first, last);
+    }
+
+    if !stats.currencies.is_empty() {
+        println!();
+        println!("{:<8} {:>8} {:>20} {:>20} {:>20}", "Currency", "Records", "Credits", "Debits", "Net");
+    }
+    for (currency, totals) in &stats.currencies {
+        let net = totals.net().map_or_else(|| "overflow".to_string(), |net| net.to_string());
+        println!(
+            "{:<8} {:>8} {:>20} {:>20} {:>20}",
+            currency.code(),
+            totals.count,
+            totals.credits.to_string(),
+            totals.debits.to_string(),
+            net
+        );
+    }
+    Ok(true)
+}
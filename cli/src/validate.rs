@@ -0,0 +1,57 @@
+//! Команда `validate`: проверка файла без конвертации.
+use crate::formats::{self, FormatArgs, Formats};
+use clap::Args;
+use parser::ParserError;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    #[arg(short, long, value_name = "FILE", help = "File to validate")]
+    input: PathBuf,
+
+    #[arg(long, value_name = "FORMAT", value_parser = formats::format_name(), help = "Input format; detected from content and extension when omitted")]
+    input_format: Option<String>,
+
+    #[arg(long, value_name = "N", help = "Stop after N invalid records")]
+    max_errors: Option<usize>,
+
+    #[command(flatten)]
+    formats: FormatArgs,
+}
+
+/// Читает файл целиком и печатает каждую некорректную запись;
+/// возвращает, корректен ли файл.
+///
+/// Запись некорректна, если не разбирается или её сумму нельзя
+/// привести к точности валюты.
+pub fn run(args: ValidateArgs) -> Result<bool, Box<dyn Error>> {
+    let formats = Formats::new(&args.formats)?;
+    let transactions = formats.open(&args.input, args.input_format.as_deref())?;
+
+    let (mut valid, mut invalid) = (0u64, 0usize);
+    for (index, item) in transactions.enumerate() {
+        // Повреждённая запись не прерывает проверку, а ошибка чтения файла прерывает.
+        let error = match item {
+            Ok(tx) => match tx.money() {
+                Ok(_) => {
+                    valid += 1;
+                    continue;
+                }
+                Err(err) => err,
+            },
+            Err(err @ ParserError::Record(_)) => err,
+            Err(err) => return Err(err.into()),
+        };
+
+        invalid += 1;
+        println!("Record {}: {}", index + 1, error);
+        if args.max_errors.is_some_and(|max| invalid >= max) {
+            println!("Stopped after {} invalid records", invalid);
+            return Ok(false);
+        }
+    }
+
+    println!("{}: {} valid, {} invalid records", args.input.display(), valid, invalid);
+    Ok(invalid == 0)
+}
@@ -0,0 +1,219 @@
+//! Прозрачное сжатие входных и выходных потоков (gzip, zstd).
+//!
+//! Любой формат читается из сжатого потока, если обернуть поток в
+//! [`Decoder`], и пишется в сжатый поток через [`Encoder`]. Сжатие
+//! определяется по магическим байтам, а если поток пуст или байты
+//! не распознаны — по расширению файла (`.gz`, `.zst`):
+//!
+//! ```text
+//! let (_, input) = parser::decompress(Some(path), File::open(path)?)?;
+//! let transactions = Csv::read(input)?;
+//!
+//! let mut output = Compression::Gzip.encoder(File::create("out.bin.gz")?)?;
+//! BinParser::write(&mut output, &transactions)?;
+//! output.finish()?;
+//! ```
+use std::borrow::Cow;
+use std::fmt::Formatter;
+use std::io::{self, BufReader, Cursor, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use crate::detect::{self, Sniffed};
+use crate::ParserError;
+
+/// Магические байты gzip.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Магические байты кадра zstd.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Алгоритм сжатия.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// gzip (RFC 1952), расширения `.gz`, `.gzip`.
+    Gzip,
+    /// Zstandard, расширения `.zst`, `.zstd`.
+    Zstd,
+}
+
+impl Compression {
+    /// Все поддерживаемые алгоритмы.
+    pub const ALL: [Compression; 2] = [Compression::Gzip, Compression::Zstd];
+
+    /// Короткое имя алгоритма, используемое в CLI (`gzip`, `zstd`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Основное расширение сжатого файла без точки (`gz`, `zst`).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+        }
+    }
+
+    /// Определяет сжатие по расширению файла (без учёта регистра).
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Определяет сжатие по первым байтам потока.
+    pub fn sniff(prefix: &[u8]) -> Option<Self> {
+        if prefix.starts_with(&GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if prefix.starts_with(&ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Отбрасывает расширение сжатия: `a.csv.gz` → `a.csv`.
+    ///
+    /// По оставшемуся пути можно определить формат содержимого.
+    pub fn strip_extension(path: &Path) -> Cow<'_, Path> {
+        match Self::from_extension(path) {
+            Some(_) => Cow::Owned(path.with_extension("")),
+            None => Cow::Borrowed(path),
+        }
+    }
+
+    /// Оборачивает поток в распаковщик.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если распаковщик не удалось создать.
+    pub fn decoder<R: Read>(&self, reader: R) -> Result<Decoder<R>, ParserError> {
+        Ok(match self {
+            Compression::Gzip => Decoder::Gzip(MultiGzDecoder::new(reader)),
+            Compression::Zstd => Decoder::Zstd(zstd::Decoder::new(reader).map_err(ParserError::Io)?),
+        })
+    }
+
+    /// Оборачивает поток в упаковщик с уровнем сжатия по умолчанию.
+    ///
+    /// После записи необходимо вызвать [`Encoder::finish`].
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если упаковщик не удалось создать.
+    pub fn encoder<W: Write>(&self, writer: W) -> Result<Encoder<W>, ParserError> {
+        Ok(match self {
+            Compression::Gzip => Encoder::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+            Compression::Zstd => Encoder::Zstd(zstd::Encoder::new(writer, 0).map_err(ParserError::Io)?),
+        })
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(ParserError::Invalid(format!("Unknown compression: {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Поток, распаковываемый при чтении.
+pub enum Decoder<R: Read> {
+    /// Несжатый поток.
+    Plain(R),
+    /// gzip, в том числе из нескольких последовательных блоков.
+    Gzip(MultiGzDecoder<R>),
+    /// Zstandard.
+    Zstd(zstd::Decoder<'static, BufReader<R>>),
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decoder::Plain(reader) => reader.read(buf),
+            Decoder::Gzip(reader) => reader.read(buf),
+            Decoder::Zstd(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Поток, сжимаемый при записи.
+///
+/// Форматы пишут в `&mut Encoder`, после чего [`Encoder::finish`]
+/// дописывает завершающие данные сжатия.
+pub enum Encoder<W: Write> {
+    /// gzip.
+    Gzip(GzEncoder<W>),
+    /// Zstandard.
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    /// Завершает сжатие и возвращает исходный поток.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`] при ошибке записи.
+    pub fn finish(self) -> Result<W, ParserError> {
+        match self {
+            Encoder::Gzip(writer) => writer.finish(),
+            Encoder::Zstd(writer) => writer.finish(),
+        }
+        .map_err(ParserError::Io)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Gzip(writer) => writer.write(buf),
+            Encoder::Zstd(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Gzip(writer) => writer.flush(),
+            Encoder::Zstd(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Распаковывает поток, если он сжат.
+///
+/// Сжатие определяется по магическим байтам, а если они не распознаны —
+/// по расширению пути. Возвращает найденный алгоритм (или `None`
+/// для несжатого потока) и поток с содержимым.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParserError::Io`] при ошибке чтения потока.
+pub fn decompress<R: Read>(path: Option<&Path>, mut reader: R) -> Result<(Option<Compression>, Decoder<Sniffed<R>>), ParserError> {
+    let prefix = detect::read_prefix(&mut reader)?;
+
+    let compression = Compression::sniff(&prefix).or_else(|| path.and_then(Compression::from_extension));
+    let reader = Cursor::new(prefix).chain(reader);
+
+    let decoder = match compression {
+        Some(compression) => compression.decoder(reader)?,
+        None => Decoder::Plain(reader),
+    };
+
+    Ok((compression, decoder))
+}
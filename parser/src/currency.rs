@@ -0,0 +1,219 @@
+//! Коды валют ISO 4217.
+//!
+//! Модуль содержит тип [`Currency`], который допускает только коды из
+//! таблицы ISO 4217 и знает для каждого из них числовой код и количество
+//! знаков после запятой (экспоненту).
+use std::cmp::Ordering;
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::ParserError;
+
+/// Запись таблицы ISO 4217.
+#[derive(Debug)]
+struct CurrencyInfo {
+    code: &'static str,
+    numeric: u16,
+    exponent: u8,
+}
+
+const fn info(code: &'static str, numeric: u16, exponent: u8) -> CurrencyInfo {
+    CurrencyInfo { code, numeric, exponent }
+}
+
+/// Действующие валюты ISO 4217, упорядоченные по буквенному коду.
+static TABLE: &[CurrencyInfo] = &[
+    info("AED", 784, 2), info("AFN", 971, 2), info("ALL", 8, 2), info("AMD", 51, 2),
+    info("ANG", 532, 2), info("AOA", 973, 2), info("ARS", 32, 2), info("AUD", 36, 2),
+    info("AWG", 533, 2), info("AZN", 944, 2), info("BAM", 977, 2), info("BBD", 52, 2),
+    info("BDT", 50, 2), info("BGN", 975, 2), info("BHD", 48, 3), info("BIF", 108, 0),
+    info("BMD", 60, 2), info("BND", 96, 2), info("BOB", 68, 2), info("BOV", 984, 2),
+    info("BRL", 986, 2), info("BSD", 44, 2), info("BTN", 64, 2), info("BWP", 72, 2),
+    info("BYN", 933, 2), info("BZD", 84, 2), info("CAD", 124, 2), info("CDF", 976, 2),
+    info("CHE", 947, 2), info("CHF", 756, 2), info("CHW", 948, 2), info("CLF", 990, 4),
+    info("CLP", 152, 0), info("CNY", 156, 2), info("COP", 170, 2), info("COU", 970, 2),
+    info("CRC", 188, 2), info("CUP", 192, 2), info("CVE", 132, 2), info("CZK", 203, 2),
+    info("DJF", 262, 0), info("DKK", 208, 2), info("DOP", 214, 2), info("DZD", 12, 2),
+    info("EGP", 818, 2), info("ERN", 232, 2), info("ETB", 230, 2), info("EUR", 978, 2),
+    info("FJD", 242, 2), info("FKP", 238, 2), info("GBP", 826, 2), info("GEL", 981, 2),
+    info("GHS", 936, 2), info("GIP", 292, 2), info("GMD", 270, 2), info("GNF", 324, 0),
+    info("GTQ", 320, 2), info("GYD", 328, 2), info("HKD", 344, 2), info("HNL", 340, 2),
+    info("HTG", 332, 2), info("HUF", 348, 2), info("IDR", 360, 2), info("ILS", 376, 2),
+    info("INR", 356, 2), info("IQD", 368, 3), info("IRR", 364, 2), info("ISK", 352, 0),
+    info("JMD", 388, 2), info("JOD", 400, 3), info("JPY", 392, 0), info("KES", 404, 2),
+    info("KGS", 417, 2), info("KHR", 116, 2), info("KMF", 174, 0), info("KPW", 408, 2),
+    info("KRW", 410, 0), info("KWD", 414, 3), info("KYD", 136, 2), info("KZT", 398, 2),
+    info("LAK", 418, 2), info("LBP", 422, 2), info("LKR", 144, 2), info("LRD", 430, 2),
+    info("LSL", 426, 2), info("LYD", 434, 3), info("MAD", 504, 2), info("MDL", 498, 2),
+    info("MGA", 969, 2), info("MKD", 807, 2), info("MMK", 104, 2), info("MNT", 496, 2),
+    info("MOP", 446, 2), info("MRU", 929, 2), info("MUR", 480, 2), info("MVR", 462, 2),
+    info("MWK", 454, 2), info("MXN", 484, 2), info("MXV", 979, 2), info("MYR", 458, 2),
+    info("MZN", 943, 2), info("NAD", 516, 2), info("NGN", 566, 2), info("NIO", 558, 2),
+    info("NOK", 578, 2), info("NPR", 524, 2), info("NZD", 554, 2), info("OMR", 512, 3),
+    info("PAB", 590, 2), info("PEN", 604, 2), info("PGK", 598, 2), info("PHP", 608, 2),
+    info("PKR", 586, 2), info("PLN", 985, 2), info("PYG", 600, 0), info("QAR", 634, 2),
+    info("RON", 946, 2), info("RSD", 941, 2), info("RUB", 643, 2), info("RWF", 646, 0),
+    info("SAR", 682, 2), info("SBD", 90, 2), info("SCR", 690, 2), info("SDG", 938, 2),
+    info("SEK", 752, 2), info("SGD", 702, 2), info("SHP", 654, 2), info("SLE", 925, 2),
+    info("SOS", 706, 2), info("SRD", 968, 2), info("SSP", 728, 2), info("STN", 930, 2),
+    info("SVC", 222, 2), info("SYP", 760, 2), info("SZL", 748, 2), info("THB", 764, 2),
+    info("TJS", 972, 2), info("TMT", 934, 2), info("TND", 788, 3), info("TOP", 776, 2),
+    info("TRY", 949, 2), info("TTD", 780, 2), info("TWD", 901, 2), info("TZS", 834, 2),
+    info("UAH", 980, 2), info("UGX", 800, 0), info("USD", 840, 2), info("USN", 997, 2),
+    info("UYI", 940, 0), info("UYU", 858, 2), info("UYW", 927, 4), info("UZS", 860, 2),
+    info("VED", 926, 2), info("VES", 928, 2), info("VND", 704, 0), info("VUV", 548, 0),
+    info("WST", 882, 2), info("XAF", 950, 0), info("XCD", 951, 2), info("XOF", 952, 0),
+    info("XPF", 953, 0), info("YER", 886, 2), info("ZAR", 710, 2), info("ZMW", 967, 2),
+    info("ZWG", 924, 2),
+];
+
+// Поиск по коду двоичный: неупорядоченная таблица давала бы неверные
+// ответы без ошибок, поэтому порядок проверяется при сборке.
+const _: () = {
+    let mut index = 1;
+    while index < TABLE.len() {
+        assert!(code_less(TABLE[index - 1].code, TABLE[index].code), "ISO 4217 table must be sorted by code");
+        index += 1;
+    }
+};
+
+/// Сравнивает коды побайтно: `true`, если `a` строго меньше `b`.
+const fn code_less(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut index = 0;
+    while index < a.len() && index < b.len() {
+        if a[index] != b[index] {
+            return a[index] < b[index];
+        }
+        index += 1;
+    }
+    a.len() < b.len()
+}
+
+/// Режим разбора кода валюты.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Только буквенный код в верхнем регистре без пробелов (`USD`).
+    #[default]
+    Strict,
+    /// Допускает пробелы по краям, нижний регистр и числовой код (`usd`, ` 840 `).
+    Lenient,
+}
+
+/// Валюта из таблицы ISO 4217.
+///
+/// Сравнивается, хешируется и упорядочивается по буквенному коду.
+#[derive(Clone, Copy)]
+pub struct Currency {
+    info: &'static CurrencyInfo,
+}
+
+impl Currency {
+    /// Разбирает код валюты в заданном режиме.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если код отсутствует в таблице ISO 4217.
+    pub fn parse(s: &str, mode: ParseMode) -> Result<Self, ParserError> {
+        let found = match mode {
+            ParseMode::Strict => Self::find(s),
+            ParseMode::Lenient => {
+                let s = s.trim();
+                match s.parse::<u16>() {
+                    Ok(numeric) => Self::from_numeric(numeric),
+                    Err(_) => Self::find(&s.to_ascii_uppercase()),
+                }
+            }
+        };
+
+        found.ok_or_else(|| ParserError::Invalid(format!("Unknown currency: {:?}", s)))
+    }
+
+    /// Ищет валюту по числовому коду ISO 4217 (например, `840` для USD).
+    pub fn from_numeric(numeric: u16) -> Option<Self> {
+        TABLE.iter().find(|info| info.numeric == numeric).map(|info| Self { info })
+    }
+
+    fn find(code: &str) -> Option<Self> {
+        TABLE
+            .binary_search_by(|info| info.code.cmp(code))
+            .ok()
+            .map(|index| Self { info: &TABLE[index] })
+    }
+
+    /// Буквенный код (например, `USD`).
+    pub fn code(&self) -> &'static str {
+        self.info.code
+    }
+
+    /// Числовой код ISO 4217.
+    pub fn numeric(&self) -> u16 {
+        self.info.numeric
+    }
+
+    /// Количество знаков после запятой (экспонента ISO 4217).
+    pub fn exponent(&self) -> u8 {
+        self.info.exponent
+    }
+}
+
+impl PartialEq for Currency {
+    fn eq(&self, other: &Self) -> bool {
+        self.code() == other.code()
+    }
+}
+
+impl Eq for Currency {}
+
+impl Hash for Currency {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.code().hash(state);
+    }
+}
+
+impl PartialOrd for Currency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Currency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.code().cmp(other.code())
+    }
+}
+
+impl std::fmt::Debug for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Currency({})", self.code())
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = ParserError;
+
+    /// Разбирает код валюты в строгом режиме.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, ParseMode::Strict)
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
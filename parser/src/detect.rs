@@ -0,0 +1,225 @@
+//! Определение формата входных данных.
+//!
+//! Формат определяется по содержимому потока (заголовку BIN, строке
+//! заголовка CSV, ключам KV, первому символу JSON, пространству имён
+//! camt, тегам MT940, заголовку OFX или полям TXT), а при неоднозначности — по
+//! расширению файла.
+use std::fmt::Formatter;
+use std::io::{Chain, Cursor, Read};
+use std::path::Path;
+use std::str::FromStr;
+use crate::ParserError;
+use crate::registry::{Descriptor, FORMATS};
+
+/// Количество байт, анализируемых при определении формата.
+const SNIFF_LEN: u64 = 4096;
+
+/// Общий префикс пространств имён camt.053 и camt.054.
+const CAMT_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.05";
+
+/// Обязательные колонки заголовка, который пишет CSV-формат.
+const CSV_HEADER: [&str; 4] = ["id", "account", "amount", "currency"];
+
+/// Поддерживаемый формат файла с транзакциями.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatKind {
+    /// Бинарный формат ([`crate::binary::BinParser`]).
+    Bin,
+    /// CSV ([`crate::csv::Csv`]).
+    Csv,
+    /// Текстовый формат ([`crate::txt::TxtParser`]).
+    Txt,
+    /// Блоки «ключ: значение» ([`crate::kv::KvParser`]).
+    Kv,
+    /// JSON-массив ([`crate::json::JsonParser`]).
+    Json,
+    /// JSON по объекту на строку ([`crate::json::NdjsonParser`]).
+    Ndjson,
+    /// Выписки ISO 20022 camt.053/camt.054 ([`crate::camt::CamtParser`]).
+    Camt,
+    /// Выписки SWIFT MT940 ([`crate::mt940::Mt940Parser`]).
+    Mt940,
+    /// Выгрузки OFX/QFX ([`crate::ofx::OfxParser`]).
+    Ofx,
+}
+
+impl FormatKind {
+    /// Все встроенные форматы в порядке приоритета при определении по содержимому.
+    ///
+    /// Порядок задаётся таблицей форматов реестра; при сборке проверяется,
+    /// что номер варианта совпадает с его позицией в таблице.
+    pub const ALL: [FormatKind; 9] = {
+        let mut all = [FormatKind::Bin; 9];
+        let mut index = 0;
+        while index < FORMATS.len() {
+            assert!(FORMATS[index].kind as usize == index, "FORMATS must follow FormatKind declaration order");
+            all[index] = FORMATS[index].kind;
+            index += 1;
+        }
+        all
+    };
+
+    /// Описание формата из таблицы реестра.
+    pub(crate) fn descriptor(&self) -> &'static Descriptor {
+        &FORMATS[*self as usize]
+    }
+
+    /// Короткое имя формата, используемое в CLI (`bin`, `csv`, `txt`, `kv`, `json`, `ndjson`, `camt`, `mt940`, `ofx`).
+    pub fn as_str(&self) -> &'static str {
+        self.descriptor().name
+    }
+
+    /// Определяет формат по расширению файла (без учёта регистра).
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        FORMATS
+            .iter()
+            .find(|format| format.extensions.contains(&ext.as_str()))
+            .map(|format| format.kind)
+    }
+
+    /// Определяет формат по первым байтам содержимого.
+    ///
+    /// Возвращает `None`, если содержимое не позволяет однозначно
+    /// выбрать формат (например, поток пуст).
+    pub fn sniff(prefix: &[u8]) -> Option<Self> {
+        if prefix.is_empty() {
+            return None;
+        }
+
+        if prefix.starts_with(&crate::binary::MAGIC) || looks_binary(prefix) {
+            return Some(FormatKind::Bin);
+        }
+
+        let text = match std::str::from_utf8(prefix) {
+            Ok(text) => text,
+            // Префикс мог оборваться посреди многобайтового символа.
+            Err(err) => std::str::from_utf8(&prefix[..err.valid_up_to()]).ok()?,
+        };
+        // Комментарии допустимы в TXT и в CSV с настроенным символом комментария.
+        let first_line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+        if first_line.starts_with("OFXHEADER:") || (first_line.starts_with('<') && text.contains("OFXHEADER=")) {
+            return Some(FormatKind::Ofx);
+        }
+
+        if first_line.starts_with('<') && text.contains(CAMT_NAMESPACE) {
+            return Some(FormatKind::Camt);
+        }
+
+        if first_line.starts_with(":20:") || first_line.starts_with("{1:") {
+            return Some(FormatKind::Mt940);
+        }
+
+        if first_line.starts_with('[') {
+            return Some(FormatKind::Json);
+        }
+
+        if first_line.starts_with('{') {
+            return Some(FormatKind::Ndjson);
+        }
+
+        if crate::kv::is_known_field(first_line) {
+            return Some(FormatKind::Kv);
+        }
+
+        if is_csv_header(first_line) || is_csv_record(first_line) {
+            return Some(FormatKind::Csv);
+        }
+
+        let mut fields = first_line.split_whitespace();
+        let id_is_numeric = fields.next().is_some_and(|id| id.parse::<u64>().is_ok());
+        if id_is_numeric && fields.count() >= CSV_HEADER.len() - 1 {
+            return Some(FormatKind::Txt);
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Display for FormatKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FormatKind {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FORMATS
+            .iter()
+            .find(|format| format.name == s)
+            .map(|format| format.kind)
+            .ok_or_else(|| ParserError::Invalid(format!("Unknown format: {}", s)))
+    }
+}
+
+/// Поток, из которого уже прочитан анализируемый префикс.
+///
+/// Отдаёт сначала прочитанные байты, затем остаток исходного потока,
+/// поэтому его можно передавать парсеру как исходный.
+pub type Sniffed<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// Определяет формат потока по содержимому и, при необходимости, по пути к файлу.
+///
+/// Содержимое имеет приоритет: расширение используется, только если
+/// по первым байтам формат определить не удалось.
+///
+/// # Ошибки
+///
+/// Возвращает:
+/// - [`ParserError::Io`] — при ошибке чтения потока;
+/// - [`ParserError::Invalid`] — если формат определить не удалось.
+pub fn detect<R: Read>(path: Option<&Path>, mut reader: R) -> Result<(FormatKind, Sniffed<R>), ParserError> {
+    let prefix = read_prefix(&mut reader)?;
+
+    let kind = FormatKind::sniff(&prefix)
+        .or_else(|| path.and_then(FormatKind::from_extension))
+        .ok_or_else(|| undetected(path))?;
+
+    Ok((kind, Cursor::new(prefix).chain(reader)))
+}
+
+/// Читает анализируемый префикс потока.
+pub(crate) fn read_prefix<R: Read>(reader: &mut R) -> Result<Vec<u8>, ParserError> {
+    let mut prefix = Vec::new();
+    reader.take(SNIFF_LEN).read_to_end(&mut prefix).map_err(ParserError::Io)?;
+
+    Ok(prefix)
+}
+
+/// Ошибка «формат не определён» с указанием файла, если он известен.
+pub(crate) fn undetected(path: Option<&Path>) -> ParserError {
+    ParserError::Invalid(match path {
+        Some(path) => format!("Unable to detect format of '{}'", path.display()),
+        None => "Unable to detect input format".to_string(),
+    })
+}
+
+/// Содержит ли префикс байты, недопустимые в текстовых форматах.
+fn looks_binary(prefix: &[u8]) -> bool {
+    prefix
+        .iter()
+        .any(|&b| b.is_ascii_control() && !matches!(b, b'\t' | b'\n' | b'\r'))
+}
+
+/// Разбирается ли строка как запись CSV без заголовка: числовой `id`
+/// и известная валюта на своих местах. Одних запятых мало — они бывают
+/// и в описании TXT.
+fn is_csv_record(line: &str) -> bool {
+    let mut reader = csv::ReaderBuilder::new().has_headers(false).flexible(true).from_reader(line.as_bytes());
+    let Some(Ok(record)) = reader.records().next() else { return false };
+
+    record.len() >= CSV_HEADER.len()
+        && record[0].trim().parse::<u64>().is_ok()
+        && record[3].trim().parse::<crate::Currency>().is_ok()
+}
+
+fn is_csv_header(line: &str) -> bool {
+    let fields: Vec<_> = line.split(',').map(|field| field.trim().to_ascii_lowercase()).collect();
+    fields.starts_with(&CSV_HEADER.map(str::to_string))
+}
@@ -0,0 +1,127 @@
+//! Сравнение двух списков транзакций.
+//!
+//! [`diff`] сопоставляет транзакции по позиции: запись `i` первого списка
+//! сравнивается с записью `i` второго. Различающиеся пары попадают
+//! в [`Diff::changed`] вместе с отличиями по каждому полю, а записи,
+//! для которых нет пары, — в [`Diff::removed`] (только в первом списке)
+//! или [`Diff::added`] (только во втором). Для списков в разном порядке
+//! используйте сверку по ключу, [`crate::reconcile`].
+use crate::csv::Column;
+use crate::Transaction;
+
+/// Различие значения одного поля.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// Поле транзакции.
+    pub field: Column,
+    /// Значение в первом списке, `None` — если поле не заполнено.
+    pub left: Option<String>,
+    /// Значение во втором списке, `None` — если поле не заполнено.
+    pub right: Option<String>,
+}
+
+/// Транзакция вместе с её номером в списке, начиная с 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry<'a> {
+    /// Номер записи в списке, начиная с 1.
+    pub index: usize,
+    /// Транзакция.
+    pub transaction: &'a Transaction,
+}
+
+/// Пара различающихся транзакций.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<'a> {
+    /// Запись первого списка.
+    pub left: Entry<'a>,
+    /// Запись второго списка.
+    pub right: Entry<'a>,
+    /// Отличия по полям в порядке [`Column::ALL`].
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Результат сравнения двух списков транзакций.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff<'a> {
+    /// Записи, которые есть только в первом списке.
+    pub removed: Vec<Entry<'a>>,
+    /// Записи, которые есть только во втором списке.
+    pub added: Vec<Entry<'a>>,
+    /// Пары записей с различающимися полями.
+    pub changed: Vec<Change<'a>>,
+    /// Количество совпавших пар.
+    pub unchanged: usize,
+}
+
+impl Diff<'_> {
+    /// Совпадают ли списки полностью.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Сравнивает два списка транзакций по позиции.
+pub fn diff<'a>(left: &'a [Transaction], right: &'a [Transaction]) -> Diff<'a> {
+    let mut result = Diff::default();
+    let entry = |index: usize, transaction: &'a Transaction| Entry { index: index + 1, transaction };
+
+    for (index, (a, b)) in left.iter().zip(right).enumerate() {
+        let fields = field_diffs(a, b);
+        if fields.is_empty() {
+            result.unchanged += 1;
+        } else {
+            result.changed.push(Change { left: entry(index, a), right: entry(index, b), fields });
+        }
+    }
+
+    let common = left.len().min(right.len());
+    result.removed = left[common..].iter().enumerate().map(|(index, tx)| entry(common + index, tx)).collect();
+    result.added = right[common..].iter().enumerate().map(|(index, tx)| entry(common + index, tx)).collect();
+
+    result
+}
+
+/// Сравнивает две транзакции по всем полям.
+///
+/// Возвращает отличия в порядке [`Column::ALL`]; пустой список означает,
+/// что транзакции равны.
+pub fn field_diffs(left: &Transaction, right: &Transaction) -> Vec<FieldDiff> {
+    Column::ALL
+        .into_iter()
+        .filter(|column| !same(*column, left, right))
+        .map(|field| FieldDiff { field, left: value(field, left), right: value(field, right) })
+        .collect()
+}
+
+/// Совпадает ли значение поля у двух транзакций.
+fn same(column: Column, left: &Transaction, right: &Transaction) -> bool {
+    match column {
+        Column::Id => left.id == right.id,
+        Column::Account => left.account == right.account,
+        Column::Amount => left.amount == right.amount,
+        Column::Currency => left.currency == right.currency,
+        Column::BookingDate => left.booking_date == right.booking_date,
+        Column::ValueDate => left.value_date == right.value_date,
+        Column::Description => left.description == right.description,
+        Column::Counterparty => left.counterparty == right.counterparty,
+        Column::Direction => left.direction == right.direction,
+        Column::Status => left.status == right.status,
+    }
+}
+
+/// Значение поля транзакции в текстовом виде.
+pub(crate) fn value(column: Column, tx: &Transaction) -> Option<String> {
+    match column {
+        Column::Id => Some(tx.id.to_string()),
+        Column::Account => Some(tx.account.clone()),
+        // Сумма в точности валюты, чтобы `1.0` и `1.00` давали один ключ.
+        Column::Amount => Some(tx.money().map_or(tx.amount, |money| money.amount()).to_string()),
+        Column::Currency => Some(tx.currency.to_string()),
+        Column::BookingDate => tx.booking_date.map(|date| date.to_string()),
+        Column::ValueDate => tx.value_date.map(|date| date.to_string()),
+        Column::Description => tx.description.clone(),
+        Column::Counterparty => tx.counterparty.clone(),
+        Column::Direction => tx.direction.map(|direction| direction.to_string()),
+        Column::Status => tx.status.map(|status| status.to_string()),
+    }
+}
@@ -3,7 +3,13 @@
 //! Модуль содержит тип [`ParserError`], который используется во всех парсерах
 //! (CSV, TXT, BIN) для унифицированной обработки ошибок ввода-вывода,
 //! формата и валидации данных.
+//!
+//! Ошибки в конкретной записи входных данных описываются типом
+//! [`RecordError`]: он хранит формат, место ошибки ([`Position`]),
+//! имя поля и исходное значение, чтобы можно было точно указать,
+//! где повреждены данные.
 use std::fmt::{Debug, Formatter};
+use crate::Currency;
 
 
 /// Унифицированная ошибка парсера.
@@ -21,19 +27,177 @@ pub enum ParserError {
     /// Ошибка валидации входных данных с поясняющим сообщением.
     Invalid(String),
 
-    /// Некорректный CSV без детализированной причины.
-    InvalidCsv,
+    /// Ошибка в конкретной записи входных данных с указанием места.
+    Record(Box<RecordError>),
+
+    /// Арифметика над суммами в разных валютах.
+    CurrencyMismatch(Currency, Currency),
+}
+
+impl ParserError {
+    /// Сведения о месте ошибки, если она относится к конкретной записи.
+    pub fn record(&self) -> Option<&RecordError> {
+        match self {
+            ParserError::Record(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Причина ошибки без префикса варианта.
+    pub(crate) fn into_reason(self) -> String {
+        match self {
+            ParserError::Invalid(reason) => reason,
+            err => err.to_string(),
+        }
+    }
 }
 
 impl std::fmt::Display for ParserError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParserError::Io(err) => write!(f, "IO error: {}", err.to_string()),
-            ParserError::Csv(err) => write!(f, "CSV error: {}", err.to_string()),
+            ParserError::Io(err) => write!(f, "IO error: {}", err),
+            ParserError::Csv(err) => write!(f, "CSV error: {}", err),
             ParserError::Invalid(reason) => write!(f, "Invalid: {}", reason),
-            ParserError::InvalidCsv => write!(f, "Invalid csv"),
+            ParserError::Record(err) => write!(f, "{}", err),
+            ParserError::CurrencyMismatch(left, right) => {
+                write!(f, "Currency mismatch: {} vs {}", left, right)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParserError {}
+
+impl From<RecordError> for ParserError {
+    fn from(err: RecordError) -> Self {
+        ParserError::Record(Box::new(err))
+    }
+}
+
+/// Место во входных данных.
+///
+/// Заполняются только те координаты, которые имеют смысл для формата:
+/// строки и колонки — для текстовых форматов, смещение в байтах — для всех.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    /// Порядковый номер записи (транзакции), начиная с 1.
+    pub record: Option<u64>,
+
+    /// Номер строки, начиная с 1.
+    pub line: Option<u64>,
+
+    /// Номер колонки, начиная с 1: символа в строке для TXT, поля для CSV.
+    pub column: Option<u64>,
+
+    /// Смещение в байтах от начала потока.
+    pub byte: Option<u64>,
+}
+
+impl Position {
+    /// Возвращает `true`, если не известна ни одна координата.
+    pub fn is_empty(&self) -> bool {
+        *self == Position::default()
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            ("record", self.record),
+            ("line", self.line),
+            ("column", self.column),
+            ("byte", self.byte),
+        ];
+
+        let mut first = true;
+        for (name, value) in parts {
+            let Some(value) = value else { continue };
+            if !first {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {}", name, value)?;
+            first = false;
         }
+
+        Ok(())
+    }
+}
+
+/// Ошибка в конкретной записи входных данных.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    /// Имя формата (`csv`, `txt`, `bin`).
+    pub format: &'static str,
+
+    /// Место ошибки.
+    pub position: Position,
+
+    /// Имя поля транзакции, если ошибка относится к полю.
+    pub field: Option<&'static str>,
+
+    /// Исходное значение, которое не удалось разобрать.
+    pub value: Option<String>,
+
+    /// Исходный текст всей записи, если формат текстовый.
+    pub raw: Option<String>,
+
+    /// Описание причины.
+    pub reason: String,
+}
+
+impl RecordError {
+    /// Создаёт ошибку без указания места и поля.
+    pub fn new(format: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            format,
+            position: Position::default(),
+            field: None,
+            value: None,
+            raw: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Указывает место ошибки.
+    pub fn at(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Указывает поле, в котором обнаружена ошибка.
+    pub fn field(mut self, field: &'static str) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// Указывает исходное значение поля.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Указывает исходный текст записи.
+    pub fn raw(mut self, raw: impl Into<String>) -> Self {
+        self.raw = Some(raw.into());
+        self
+    }
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} error", self.format.to_ascii_uppercase())?;
+        if !self.position.is_empty() {
+            write!(f, " at {}", self.position)?;
+        }
+        if let Some(field) = self.field {
+            write!(f, ", field {}", field)?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, ", value {:?}", value)?;
+        }
+
+        write!(f, ": {}", self.reason)
     }
 }
 
-impl std::error::Error for ParserError {}
\ No newline at end of file
+impl std::error::Error for RecordError {}
@@ -0,0 +1,343 @@
+//! Нечёткая сверка учёта с банковской выпиской.
+//!
+//! Банк может округлять суммы иначе, чем учётная система, проводить
+//! операцию другим днём или объединять несколько записей учёта в одну
+//! строку выписки. [`reconcile_fuzzy`] сопоставляет записи учёта (`ledger`)
+//! со строками выписки (`statement`) с учётом этих расхождений:
+//!
+//! 1. пары «запись — строка» с тем же счётом и валютой, суммой в пределах
+//!    [`FuzzyOptions::amount_tolerance`] и датой в пределах
+//!    [`FuzzyOptions::date_window`] сопоставляются по убыванию уверенности;
+//! 2. для оставшихся строк выписки ищется группа из 2..=[`FuzzyOptions::max_group`]
+//!    оставшихся записей того же счёта, валюты и знака, сумма которых
+//!    совпадает со строкой в пределах допуска.
+//!
+//! Уверенность ([`FuzzyMatch::confidence`]) — число от 0 до 1:
+//! половину дают суммы (1 при точном совпадении, 0.5 на границе допуска),
+//! 0.3 — даты (1 при совпадении или отсутствии даты, 0.5 на границе окна),
+//! 0.2 — совпадение `id` или описания. Совпадения с уверенностью ниже
+//! [`FuzzyOptions::min_confidence`] отбрасываются.
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use crate::diff::Entry;
+use crate::{Amount, Currency, Transaction};
+
+/// Максимальное число записей-кандидатов при поиске группы для одной
+/// строки выписки; ближайшие по дате записи проверяются первыми.
+const MAX_GROUP_CANDIDATES: usize = 24;
+
+/// Настройки нечёткой сверки.
+///
+/// По умолчанию суммы и даты должны совпадать точно, группировка
+/// отключена, а порог уверенности не задан: принимается любое
+/// совпадение в пределах допусков.
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzyOptions {
+    amount_tolerance: Amount,
+    date_window: u32,
+    max_group: usize,
+    min_confidence: f64,
+}
+
+impl Default for FuzzyOptions {
+    fn default() -> Self {
+        Self {
+            amount_tolerance: Amount::from_minor(0, 0),
+            date_window: 0,
+            max_group: 1,
+            min_confidence: 0.0,
+        }
+    }
+}
+
+impl FuzzyOptions {
+    /// Наибольшее значение [`FuzzyOptions::max_group`]: время поиска
+    /// группы растёт с ним комбинаторно.
+    pub const MAX_GROUP: usize = 8;
+
+    /// Создаёт настройки по умолчанию.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Задаёт допустимое расхождение сумм, например `0.01`.
+    ///
+    /// Отрицательный допуск считается положительным.
+    pub fn amount_tolerance(mut self, tolerance: Amount) -> Self {
+        self.amount_tolerance = Amount::from_minor(tolerance.minor_units().saturating_abs(), tolerance.exponent());
+        self
+    }
+
+    /// Задаёт допустимое расхождение дат в днях.
+    ///
+    /// Сравнивается дата проводки, а если её нет — дата валютирования;
+    /// запись без дат подходит к любой дате.
+    pub fn date_window(mut self, days: u32) -> Self {
+        self.date_window = days;
+        self
+    }
+
+    /// Задаёт наибольшее число записей учёта, объединяемых в одну строку
+    /// выписки; `1` отключает группировку, значения больше
+    /// [`FuzzyOptions::MAX_GROUP`] ограничиваются им.
+    pub fn max_group(mut self, max_group: usize) -> Self {
+        self.max_group = max_group.clamp(1, Self::MAX_GROUP);
+        self
+    }
+
+    /// Задаёт минимальную уверенность совпадения, от 0 до 1.
+    pub fn min_confidence(mut self, confidence: f64) -> Self {
+        self.min_confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Найденное соответствие записей учёта строке выписки.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch<'a> {
+    /// Записи учёта: одна или несколько при группировке.
+    pub ledger: Vec<Entry<'a>>,
+    /// Строка выписки.
+    pub statement: Entry<'a>,
+    /// Уверенность совпадения, от 0 до 1.
+    pub confidence: f64,
+    /// Сумма записей учёта минус сумма строки выписки.
+    pub difference: Amount,
+}
+
+/// Результат нечёткой сверки.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuzzyReconciliation<'a> {
+    /// Найденные соответствия в порядке строк выписки.
+    pub matches: Vec<FuzzyMatch<'a>>,
+    /// Записи учёта без пары.
+    pub unmatched_ledger: Vec<Entry<'a>>,
+    /// Строки выписки без пары.
+    pub unmatched_statement: Vec<Entry<'a>>,
+}
+
+impl FuzzyReconciliation<'_> {
+    /// Все ли записи с обеих сторон нашли пару.
+    pub fn is_clean(&self) -> bool {
+        self.unmatched_ledger.is_empty() && self.unmatched_statement.is_empty()
+    }
+}
+
+/// Сверяет записи учёта со строками выписки с допусками и группировкой.
+pub fn reconcile_fuzzy<'a>(
+    ledger: &'a [Transaction],
+    statement: &'a [Transaction],
+    options: &FuzzyOptions,
+) -> FuzzyReconciliation<'a> {
+    let mut ledger_used = vec![false; ledger.len()];
+    let mut statement_used = vec![false; statement.len()];
+    let mut matches = Vec::new();
+
+    // Записи сопоставляются только внутри одного счёта и валюты.
+    let mut buckets: HashMap<(&str, Currency), Vec<usize>> = HashMap::new();
+    for (index, tx) in ledger.iter().enumerate() {
+        buckets.entry((tx.account.as_str(), tx.currency)).or_default().push(index);
+    }
+
+    let mut pairs = Vec::new();
+    for (s, line) in statement.iter().enumerate() {
+        let Some(candidates) = buckets.get(&(line.account.as_str(), line.currency)) else { continue };
+        for &l in candidates {
+            if let Some((confidence, difference)) = score(&[&ledger[l]], line, options) {
+                pairs.push((confidence, l, s, difference));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    for (confidence, l, s, difference) in pairs {
+        if ledger_used[l] || statement_used[s] || confidence < options.min_confidence {
+            continue;
+        }
+        ledger_used[l] = true;
+        statement_used[s] = true;
+        matches.push(FuzzyMatch { ledger: vec![entry(ledger, l)], statement: entry(statement, s), confidence, difference });
+    }
+
+    if options.max_group > 1 {
+        for (s, line) in statement.iter().enumerate() {
+            if statement_used[s] {
+                continue;
+            }
+            let Some(candidates) = buckets.get(&(line.account.as_str(), line.currency)) else { continue };
+
+            // Банк объединяет записи одного направления: знаки должны совпадать.
+            let mut candidates: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&l| !ledger_used[l] && ledger[l].amount.minor_units().signum() == line.amount.minor_units().signum())
+                .filter(|&l| date_distance(&ledger[l], line).is_none_or(|days| days <= i64::from(options.date_window)))
+                .collect();
+            candidates.sort_by_key(|&l| (date_distance(&ledger[l], line).unwrap_or(0), l));
+            candidates.truncate(MAX_GROUP_CANDIDATES);
+
+            let mut search = GroupSearch::new(ledger, &candidates, line, options);
+            search.search(0, &mut Vec::new(), Amount::from_minor(0, 0));
+
+            if let Some((confidence, group, difference)) = search.best {
+                for &l in &group {
+                    ledger_used[l] = true;
+                }
+                statement_used[s] = true;
+                let ledger = group.iter().map(|&l| entry(ledger, l)).collect();
+                matches.push(FuzzyMatch { ledger, statement: entry(statement, s), confidence, difference });
+            }
+        }
+    }
+
+    matches.sort_by_key(|m| m.statement.index);
+
+    FuzzyReconciliation {
+        matches,
+        unmatched_ledger: (0..ledger.len()).filter(|&l| !ledger_used[l]).map(|l| entry(ledger, l)).collect(),
+        unmatched_statement: (0..statement.len()).filter(|&s| !statement_used[s]).map(|s| entry(statement, s)).collect(),
+    }
+}
+
+/// Лучшая найденная группа: уверенность, номера записей и расхождение.
+type Best = Option<(f64, Vec<usize>, Amount)>;
+
+/// Поиск группы записей учёта для одной строки выписки.
+///
+/// Все кандидаты одного знака со строкой, поэтому модуль суммы группы
+/// при добавлении записей только растёт: ветвь отбрасывается, как только
+/// он превысил модуль суммы строки с допуском или уже не может до него
+/// дотянуться даже со всеми оставшимися кандидатами.
+struct GroupSearch<'a> {
+    ledger: &'a [Transaction],
+    candidates: &'a [usize],
+    line: &'a Transaction,
+    options: &'a FuzzyOptions,
+    /// Модуль суммы строки минус допуск.
+    low: Option<Amount>,
+    /// Модуль суммы строки плюс допуск.
+    high: Option<Amount>,
+    /// Сумма модулей кандидатов, начиная с данного; `None` при переполнении.
+    remaining: Vec<Option<Amount>>,
+    best: Best,
+}
+
+impl<'a> GroupSearch<'a> {
+    fn new(ledger: &'a [Transaction], candidates: &'a [usize], line: &'a Transaction, options: &'a FuzzyOptions) -> Self {
+        let target = magnitude(line.amount);
+        let mut remaining = vec![Some(Amount::from_minor(0, 0)); candidates.len() + 1];
+        for index in (0..candidates.len()).rev() {
+            remaining[index] = remaining[index + 1].and_then(|rest| rest.checked_add(&magnitude(ledger[candidates[index]].amount)));
+        }
+
+        Self {
+            ledger,
+            candidates,
+            line,
+            options,
+            low: target.checked_sub(&options.amount_tolerance),
+            high: target.checked_add(&options.amount_tolerance),
+            remaining,
+            best: None,
+        }
+    }
+
+    /// Перебирает группы из 2..=`max_group` кандидатов, начиная с `start`,
+    /// и запоминает группу с наибольшей уверенностью (при равенстве — меньшую).
+    /// `total` — модуль суммы текущей группы.
+    fn search(&mut self, start: usize, group: &mut Vec<usize>, total: Amount) {
+        let in_range = self.low.is_none_or(|low| !greater(low, total));
+        if group.len() >= 2 && in_range {
+            let rows: Vec<&Transaction> = group.iter().map(|&l| &self.ledger[l]).collect();
+            if let Some((confidence, difference)) = score(&rows, self.line, self.options) {
+                let better = self.best.as_ref().is_none_or(|(best, best_group, _)| {
+                    confidence > *best || (confidence == *best && group.len() < best_group.len())
+                });
+                if better && confidence >= self.options.min_confidence {
+                    self.best = Some((confidence, group.clone(), difference));
+                }
+            }
+        }
+        if group.len() == self.options.max_group {
+            return;
+        }
+
+        for index in start..self.candidates.len() {
+            let candidate = self.candidates[index];
+            let Some(next) = total.checked_add(&magnitude(self.ledger[candidate].amount)) else { continue };
+            if self.high.is_some_and(|high| greater(next, high)) {
+                continue;
+            }
+            // Дальше кандидатов только меньше: если не хватает сейчас, не хватит и потом.
+            let reachable = self.remaining[index + 1].and_then(|rest| next.checked_add(&rest));
+            if let (Some(low), Some(reachable)) = (self.low, reachable) && greater(low, reachable) {
+                break;
+            }
+
+            group.push(candidate);
+            self.search(index + 1, group, next);
+            group.pop();
+        }
+    }
+}
+
+/// Модуль суммы.
+fn magnitude(amount: Amount) -> Amount {
+    Amount::from_minor(amount.minor_units().saturating_abs(), amount.exponent())
+}
+
+/// Больше ли `a`, чем `b`.
+fn greater(a: Amount, b: Amount) -> bool {
+    a.checked_sub(&b).is_some_and(|difference| difference.minor_units() > 0)
+}
+
+/// Оценивает соответствие записей учёта строке выписки.
+///
+/// Возвращает уверенность и расхождение сумм или `None`, если сумма
+/// или даты выходят за допуски.
+fn score(rows: &[&Transaction], line: &Transaction, options: &FuzzyOptions) -> Option<(f64, Amount)> {
+    let total = rows.iter().try_fold(Amount::from_minor(0, 0), |total, tx| total.checked_add(&tx.amount))?;
+    let difference = total.checked_sub(&line.amount)?;
+    let excess = Amount::from_minor(difference.minor_units().checked_abs()?, difference.exponent())
+        .checked_sub(&options.amount_tolerance)?;
+    if excess.minor_units() > 0 {
+        return None;
+    }
+
+    let amount_score = match difference.minor_units() {
+        0 => 1.0,
+        _ => 1.0 - 0.5 * ratio(difference, options.amount_tolerance),
+    };
+
+    let mut date_score = 0.0;
+    for tx in rows {
+        date_score += match date_distance(tx, line) {
+            None | Some(0) => 1.0,
+            Some(days) if days <= i64::from(options.date_window) => 1.0 - 0.5 * days as f64 / f64::from(options.date_window),
+            Some(_) => return None,
+        };
+    }
+    date_score /= rows.len() as f64;
+
+    let reference = rows.len() == 1
+        && (rows[0].id == line.id || (rows[0].description.is_some() && rows[0].description == line.description));
+    let reference_score = if reference { 1.0 } else { 0.0 };
+
+    Some((0.5 * amount_score + 0.3 * date_score + 0.2 * reference_score, difference))
+}
+
+/// Отношение модулей двух сумм.
+fn ratio(value: Amount, limit: Amount) -> f64 {
+    let decimal = |amount: Amount| amount.minor_units().unsigned_abs() as f64 / 10f64.powi(i32::from(amount.exponent()));
+    decimal(value) / decimal(limit)
+}
+
+/// Расстояние между датами записей в днях, если даты есть у обеих.
+fn date_distance(a: &Transaction, b: &Transaction) -> Option<i64> {
+    let date = |tx: &Transaction| -> Option<NaiveDate> { tx.booking_date.or(tx.value_date) };
+    Some((date(a)? - date(b)?).num_days().abs())
+}
+
+fn entry(transactions: &[Transaction], index: usize) -> Entry<'_> {
+    Entry { index: index + 1, transaction: &transactions[index] }
+}
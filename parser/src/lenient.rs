@@ -0,0 +1,140 @@
+//! Нестрогое чтение: пропуск повреждённых записей с отчётом об ошибках.
+//!
+//! Обычное чтение ([`Format::read`]) прерывается на первой ошибке и теряет
+//! все корректные записи. [`Lenient`] оборачивает любой поток транзакций,
+//! пропускает записи с ошибками ([`ParserError::Record`]) и запоминает их
+//! вместе с номером, исходным текстом и причиной. Ошибки ввода-вывода
+//! и превышение порога ошибок по-прежнему прерывают чтение.
+//!
+//! [`Format::read`]: crate::Format::read
+use crate::{ParserError, Transaction};
+
+/// Настройки нестрогого чтения.
+///
+/// Порог ошибок по умолчанию не задан: отклоняются все повреждённые записи.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lenient {
+    max_errors: Option<usize>,
+}
+
+impl Lenient {
+    /// Создаёт настройки без ограничения количества ошибок.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Задаёт максимальное количество отклонённых записей.
+    ///
+    /// Если ошибок больше, чтение прерывается.
+    pub fn max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
+    /// Оборачивает поток транзакций, пропуская записи с ошибками.
+    pub fn reader<I>(self, inner: I) -> LenientReader<I>
+    where
+        I: Iterator<Item = Result<Transaction, ParserError>>,
+    {
+        LenientReader {
+            inner,
+            options: self,
+            rejected: Vec::new(),
+            seen: 0,
+        }
+    }
+
+    /// Читает весь поток, собирая корректные транзакции и отклонённые записи.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает ошибку ввода-вывода или [`ParserError::Invalid`],
+    /// если отклонено больше записей, чем допускает [`Lenient::max_errors`].
+    pub fn collect<I>(self, inner: I) -> Result<LenientReport, ParserError>
+    where
+        I: Iterator<Item = Result<Transaction, ParserError>>,
+    {
+        let mut reader = self.reader(inner);
+        let transactions = reader.by_ref().collect::<Result<_, _>>()?;
+
+        Ok(LenientReport { transactions, rejected: reader.rejected })
+    }
+}
+
+/// Запись, отклонённая при нестрогом чтении.
+#[derive(Debug)]
+pub struct Rejected {
+    /// Порядковый номер записи в потоке, начиная с 1.
+    pub index: u64,
+
+    /// Исходный текст записи, если формат текстовый.
+    pub raw: Option<String>,
+
+    /// Причина отклонения.
+    pub error: ParserError,
+}
+
+/// Результат нестрогого чтения.
+#[derive(Debug)]
+pub struct LenientReport {
+    /// Корректно прочитанные транзакции.
+    pub transactions: Vec<Transaction>,
+
+    /// Отклонённые записи в порядке чтения.
+    pub rejected: Vec<Rejected>,
+}
+
+/// Поток транзакций, пропускающий записи с ошибками.
+///
+/// Отклонённые записи доступны через [`LenientReader::rejected`].
+pub struct LenientReader<I> {
+    inner: I,
+    options: Lenient,
+    rejected: Vec<Rejected>,
+    seen: u64,
+}
+
+impl<I> LenientReader<I> {
+    /// Записи, отклонённые на текущий момент.
+    pub fn rejected(&self) -> &[Rejected] {
+        &self.rejected
+    }
+
+    /// Завершает чтение и возвращает отклонённые записи.
+    pub fn into_rejected(self) -> Vec<Rejected> {
+        self.rejected
+    }
+}
+
+impl<I> Iterator for LenientReader<I>
+where
+    I: Iterator<Item = Result<Transaction, ParserError>>,
+{
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.inner.next()?;
+            self.seen += 1;
+
+            let error = match item {
+                Ok(tx) => return Some(Ok(tx)),
+                Err(ParserError::Record(error)) => error,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if self.options.max_errors.is_some_and(|max| self.rejected.len() >= max) {
+                return Some(Err(ParserError::Invalid(format!(
+                    "Too many invalid records: more than {}, last: {}",
+                    self.rejected.len(), error
+                ))));
+            }
+
+            self.rejected.push(Rejected {
+                index: error.position.record.unwrap_or(self.seen),
+                raw: error.raw.clone(),
+                error: ParserError::Record(error),
+            });
+        }
+    }
+}
@@ -1,32 +1,122 @@
+mod compression;
+mod currency;
+mod detect;
+mod diff;
 mod errors;
+mod fuzzy;
+mod lenient;
 mod models;
+mod money;
 mod parsers;
+mod reconcile;
+mod registry;
+mod report;
+mod stats;
 mod tests;
 
 use std::io::{Read, Write};
-pub use crate::errors::ParserError;
-pub use crate::models::Transaction;
+pub use crate::compression::{Compression, Decoder, Encoder, decompress};
+pub use crate::currency::{Currency, ParseMode};
+pub use crate::detect::{FormatKind, Sniffed, detect};
+pub use crate::diff::{Change, Diff, Entry, FieldDiff, diff, field_diffs};
+pub use crate::errors::{ParserError, Position, RecordError};
+pub use crate::fuzzy::{FuzzyMatch, FuzzyOptions, FuzzyReconciliation, reconcile_fuzzy};
+pub use crate::lenient::{Lenient, LenientReader, LenientReport, Rejected};
+pub use crate::models::{Direction, Status, Transaction};
+pub use crate::money::{Amount, Money};
+pub use crate::reconcile::{Duplicate, MatchKey, Reconciliation, Side, reconcile};
+pub use crate::registry::{DynFormat, Registry, TransactionStream};
+pub use crate::report::{FieldMismatch, Match, Mismatch, MismatchKind, Report, ReportFormat, Summary};
+pub use crate::stats::{CurrencyStats, Stats};
 
 pub use parsers::{
-    binary, csv, txt
+    binary, camt, csv, fixed, json, kv, mt940, ofx, txt
 };
+#[cfg(feature = "arrow")]
+pub use parsers::arrow;
+#[cfg(feature = "parquet")]
+pub use parsers::parquet;
 
 /// Общий интерфейс для чтения/записи транзакций в файл.
 ///
 /// Реализуется для CSV, TXT и BIN форматов.
+///
+/// Основой интерфейса служат потоковые [`Format::reader`] и [`Format::writer`],
+/// которые обрабатывают транзакции по одной и не требуют держать весь файл
+/// в памяти. Методы [`Format::read`] и [`Format::write`] построены поверх них.
 pub trait Format {
+    /// Потоковый итератор транзакций, читаемых из `R`.
+    type Reader<R: Read>: Iterator<Item = Result<Transaction, ParserError>>;
+
+    /// Инкрементальный писатель транзакций в `W`.
+    type Writer<W: Write>: TransactionWriter;
+
+    /// Создаёт потоковый итератор транзакций поверх входного потока.
+    ///
+    /// Ошибки заголовка и отдельных записей возвращаются элементами итератора.
+    fn reader<R: Read>(reader: R) -> Self::Reader<R>;
+
+    /// Создаёт писателя, принимающего транзакции по одной.
+    ///
+    /// После записи всех транзакций необходимо вызвать
+    /// [`TransactionWriter::finish`].
+    fn writer<W: Write>(writer: W) -> Self::Writer<W>;
+
     /// Читает транзакции из входного потока.
     ///
     /// # Ошибки
     ///
     /// Возвращает ошибку, если данные повреждены
     /// или имеют неверный формат.
-    fn read<R: Read>(reader: R) -> Result<Vec<Transaction>, ParserError>;
+    fn read<R: Read>(reader: R) -> Result<Vec<Transaction>, ParserError> {
+        Self::reader(reader).collect()
+    }
+
+    /// Читает транзакции, пропуская повреждённые записи.
+    ///
+    /// Возвращает корректные транзакции и список отклонённых записей,
+    /// см. [`Lenient`].
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает ошибку ввода-вывода или ошибку превышения порога
+    /// [`Lenient::max_errors`].
+    fn read_lenient<R: Read>(reader: R, lenient: Lenient) -> Result<LenientReport, ParserError> {
+        lenient.collect(Self::reader(reader))
+    }
 
     /// Записывает транзакции в выходной поток.
     ///
     /// # Ошибки
     ///
     /// Возвращает ошибку записи.
-    fn write<W: Write>(writer: W, transactions: &[Transaction]) -> Result<(), ParserError>;
+    fn write<W: Write>(writer: W, transactions: &[Transaction]) -> Result<(), ParserError> {
+        let mut writer = Self::writer(writer);
+        for tx in transactions {
+            writer.write(tx)?;
+        }
+
+        writer.finish()
+    }
+}
+
+/// Инкрементальная запись транзакций.
+///
+/// Транзакции передаются по одной через [`TransactionWriter::write`],
+/// а [`TransactionWriter::finish`] дописывает завершающие данные формата
+/// и сбрасывает буферы.
+pub trait TransactionWriter {
+    /// Записывает одну транзакцию.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает ошибку записи.
+    fn write(&mut self, transaction: &Transaction) -> Result<(), ParserError>;
+
+    /// Завершает запись.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает ошибку записи.
+    fn finish(&mut self) -> Result<(), ParserError>;
 }
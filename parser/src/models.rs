@@ -3,13 +3,18 @@
 //! Модуль содержит структуру [`Transaction`], используемую всеми форматами
 //! парсеров (CSV, TXT, BIN) для сериализации и десериализации данных.
 use std::fmt::Formatter;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use crate::ParserError;
+use crate::{Amount, Currency, Money, ParserError};
 
 /// Банковская транзакция.
 ///
-/// Представляет одну запись о транзакции.
-#[derive(Debug, Serialize, Deserialize)]
+/// Представляет одну запись о транзакции. Поля после `currency`
+/// необязательны: они заполняются, если их передаёт источник данных,
+/// и не сериализуются, если отсутствуют.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     /// Уникальный идентификатор транзакции.
     pub id: u64,
@@ -17,42 +22,181 @@ pub struct Transaction {
     /// Идентификатор или номер счёта.
     pub account: String,
 
-    /// Сумма транзакции.
+    /// Сумма транзакции с точностью валюты.
     ///
     /// Может быть отрицательной (например, списание средств).
-    pub amount: i64,
+    pub amount: Amount,
 
-    /// Код валюты (например, `USD`, `EUR`).
-    pub currency: String,
+    /// Валюта ISO 4217 (например, `USD`, `EUR`).
+    pub currency: Currency,
+
+    /// Дата проводки (бронирования) по счёту.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub booking_date: Option<NaiveDate>,
+
+    /// Дата валютирования.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<NaiveDate>,
+
+    /// Назначение платежа или описание операции.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Счёт контрагента.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<String>,
+
+    /// Направление операции (зачисление или списание).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<Direction>,
+
+    /// Статус операции в банке.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+/// Направление операции по счёту.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Зачисление.
+    Credit,
+    /// Списание.
+    Debit,
+}
+
+impl Direction {
+    /// Имя в нижнем регистре (`credit`, `debit`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Credit => "credit",
+            Direction::Debit => "debit",
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParserError;
+
+    /// Разбирает направление без учёта регистра: `credit`/`CRDT`/`C`, `debit`/`DBIT`/`D`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "credit" | "crdt" | "c" => Ok(Direction::Credit),
+            "debit" | "dbit" | "d" => Ok(Direction::Debit),
+            _ => Err(ParserError::Invalid(format!("Invalid direction: {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Статус операции в банке.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Операция проведена.
+    Booked,
+    /// Операция ожидает проведения.
+    Pending,
+    /// Информационная запись без влияния на баланс.
+    Info,
+}
+
+impl Status {
+    /// Имя в нижнем регистре (`booked`, `pending`, `info`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Booked => "booked",
+            Status::Pending => "pending",
+            Status::Info => "info",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = ParserError;
+
+    /// Разбирает статус без учёта регистра: `booked`/`BOOK`, `pending`/`PDNG`, `info`/`INFO`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "booked" | "book" => Ok(Status::Booked),
+            "pending" | "pdng" => Ok(Status::Pending),
+            "info" => Ok(Status::Info),
+            _ => Err(ParserError::Invalid(format!("Invalid status: {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl Transaction {
-    /// Создаёт транзакцию из CSV-записи.
+    /// Создаёт транзакцию с обязательными полями; необязательные поля пусты.
+    pub fn new(id: u64, account: impl Into<String>, amount: Amount, currency: Currency) -> Self {
+        Self {
+            id,
+            account: account.into(),
+            amount,
+            currency,
+            booking_date: None,
+            value_date: None,
+            description: None,
+            counterparty: None,
+            direction: None,
+            status: None,
+        }
+    }
+
+    /// Создаёт транзакцию из CSV-записи без заголовка.
     ///
-    /// Ожидаемый формат CSV:
+    /// Ожидаемый порядок колонок:
     /// ```text
-    /// id,account,amount,currency
+    /// id,account,amount,currency[,booking_date,value_date,description,counterparty,direction,status]
     /// ```
     ///
+    /// Необязательные колонки могут отсутствовать или быть пустыми.
+    /// Для сопоставления колонок по заголовку используйте
+    /// [`crate::csv::CsvOptions`].
+    ///
     /// # Ошибки
     ///
-    /// Возвращает [`ParserError::InvalidCsv`], если:
+    /// Возвращает [`ParserError::Record`] с номером строки, поля
+    /// и исходным значением, если:
     /// - отсутствует одно из обязательных полей;
-    /// - не удалось преобразовать поле в ожидаемый тип.
+    /// - не удалось преобразовать поле в ожидаемый тип;
+    /// - код валюты отсутствует в таблице ISO 4217;
+    /// - сумма содержит больше знаков после запятой, чем допускает валюта.
     pub fn from_csv_record(record: &csv::StringRecord) -> Result<Self, ParserError> {
-        Ok(Self {
-            id: record.get(0)
-                .ok_or(ParserError::InvalidCsv)?
-                .parse()
-                .map_err(|_| ParserError::InvalidCsv)?,
-            account: record.get(1).ok_or(ParserError::InvalidCsv)?.to_string(),
-            amount: record.get(2)
-                .ok_or(ParserError::InvalidCsv)?
-                .parse()
-                .map_err(|_| ParserError::InvalidCsv)?,
-            currency: record.get(3).ok_or(ParserError::InvalidCsv)?.to_string(),
-        })
+        crate::csv::parse_record(record, &crate::csv::ColumnMap::positional(), &crate::csv::CsvOptions::default(), None)
     }
+
+    /// Сумма транзакции вместе с валютой, приведённая к точности валюты.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если сумму нельзя без потерь
+    /// привести к количеству знаков после запятой, принятому для валюты.
+    pub fn money(&self) -> Result<Money, ParserError> {
+        Money::new(self.amount, self.currency)
+    }
+}
+
+/// Текущее время UTC с точностью до секунды.
+pub(crate) fn now_utc() -> NaiveDateTime {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    DateTime::from_timestamp(secs as i64, 0).unwrap_or_default().naive_utc()
+}
+
+/// Разбирает дату в формате `ГГГГ-ММ-ДД`.
+pub(crate) fn parse_date(s: &str) -> Result<NaiveDate, ParserError> {
+    s.parse().map_err(|_| ParserError::Invalid(format!("Invalid date: {}", s)))
 }
 
 impl PartialEq for Transaction {
@@ -60,13 +204,40 @@ impl PartialEq for Transaction {
         self.id == other.id &&
             self.account == other.account &&
             self.currency == other.currency &&
-            self.amount == other.amount
+            self.amount == other.amount &&
+            self.booking_date == other.booking_date &&
+            self.value_date == other.value_date &&
+            self.description == other.description &&
+            self.counterparty == other.counterparty &&
+            self.direction == other.direction &&
+            self.status == other.status
     }
 }
 
 impl std::fmt::Display for Transaction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "id: [{}], account: [{}], currency: [{}], amount: [{}]",
-               self.id, self.account, self.currency, self.amount)
+               self.id, self.account, self.currency, self.amount)?;
+
+        if let Some(date) = &self.booking_date {
+            write!(f, ", booking_date: [{}]", date)?;
+        }
+        if let Some(date) = &self.value_date {
+            write!(f, ", value_date: [{}]", date)?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, ", description: [{}]", description)?;
+        }
+        if let Some(counterparty) = &self.counterparty {
+            write!(f, ", counterparty: [{}]", counterparty)?;
+        }
+        if let Some(direction) = &self.direction {
+            write!(f, ", direction: [{}]", direction)?;
+        }
+        if let Some(status) = &self.status {
+            write!(f, ", status: [{}]", status)?;
+        }
+
+        Ok(())
     }
-}
\ No newline at end of file
+}
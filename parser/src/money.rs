@@ -0,0 +1,299 @@
+//! Денежные суммы с фиксированной точностью.
+//!
+//! Сумма хранится как целое число минимальных единиц валюты (центов, копеек)
+//! вместе с количеством знаков после запятой (экспонентой ISO 4217,
+//! см. [`Currency::exponent`]).
+//! Это позволяет хранить значения без потерь, а в текстовых форматах
+//! читать и печатать их в привычном виде `12.34`.
+use std::fmt::Formatter;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::{Currency, ParserError};
+
+/// Максимальная поддерживаемая экспонента: `10^18` ещё помещается в `i64`.
+const MAX_EXPONENT: u8 = 18;
+
+/// Десятичная сумма: целое число минимальных единиц и экспонента.
+///
+/// Значение равно `minor_units / 10^exponent`. Например, `12.34 USD`
+/// хранится как `Amount { minor_units: 1234, exponent: 2 }`.
+///
+/// Сравнение и хеширование числовые: `1.0` равно `1.00`, хотя печатаются
+/// они по-разному.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Amount {
+    minor_units: i64,
+    exponent: u8,
+}
+
+impl Amount {
+    /// Создаёт сумму из минимальных единиц и экспоненты.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если экспонента больше 18.
+    pub fn from_minor(minor_units: i64, exponent: u8) -> Self {
+        assert!(exponent <= MAX_EXPONENT, "exponent {} is too large", exponent);
+        Self { minor_units, exponent }
+    }
+
+    /// Количество минимальных единиц валюты.
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    /// Количество знаков после запятой.
+    pub fn exponent(&self) -> u8 {
+        self.exponent
+    }
+
+    /// Разбирает десятичную запись (`12.34`, `-0.5`, `+100`) с заданной экспонентой.
+    ///
+    /// Дробных знаков может быть меньше экспоненты; лишние знаки допускаются,
+    /// только если это нули.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если запись некорректна, значение
+    /// не помещается в `i64` или его нельзя представить без потери точности.
+    pub fn parse(s: &str, exponent: u8) -> Result<Self, ParserError> {
+        let invalid = || ParserError::Invalid(format!("Invalid amount: {}", s));
+
+        if exponent > MAX_EXPONENT {
+            return Err(invalid());
+        }
+
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty()) || !all_digits(int_part) || !all_digits(frac_part) {
+            return Err(invalid());
+        }
+
+        let (kept, dropped) = frac_part.split_at(frac_part.len().min(exponent as usize));
+        if dropped.bytes().any(|b| b != b'0') {
+            return Err(ParserError::Invalid(format!(
+                "Amount {} has more than {} decimal places", s, exponent
+            )));
+        }
+
+        let mut minor_units: i64 = 0;
+        let padding = std::iter::repeat_n(b'0', exponent as usize - kept.len());
+        for digit in int_part.bytes().chain(kept.bytes()).chain(padding) {
+            minor_units = minor_units
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(i64::from(digit - b'0')))
+                .ok_or_else(invalid)?;
+        }
+
+        Ok(Self::from_minor(if negative { -minor_units } else { minor_units }, exponent))
+    }
+
+    /// Переводит сумму в другую экспоненту.
+    ///
+    /// Возвращает `None`, если перевод приводит к потере точности или переполнению.
+    pub fn rescale(&self, exponent: u8) -> Option<Self> {
+        if exponent > MAX_EXPONENT {
+            return None;
+        }
+
+        let minor_units = if exponent >= self.exponent {
+            self.minor_units.checked_mul(10i64.pow(u32::from(exponent - self.exponent)))?
+        } else {
+            let divisor = 10i64.pow(u32::from(self.exponent - exponent));
+            if self.minor_units % divisor != 0 {
+                return None;
+            }
+            self.minor_units / divisor
+        };
+
+        Some(Self::from_minor(minor_units, exponent))
+    }
+
+    /// Сумма с наименьшей экспонентой, при которой значение не меняется.
+    fn normalized(&self) -> Self {
+        let mut amount = *self;
+        while amount.exponent > 0 && amount.minor_units % 10 == 0 {
+            amount.minor_units /= 10;
+            amount.exponent -= 1;
+        }
+
+        amount
+    }
+
+    /// Складывает суммы, приводя их к большей экспоненте.
+    ///
+    /// Возвращает `None` при переполнении.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let exponent = self.exponent.max(other.exponent);
+        let (a, b) = (self.rescale(exponent)?, other.rescale(exponent)?);
+
+        Some(Self::from_minor(a.minor_units.checked_add(b.minor_units)?, exponent))
+    }
+
+    /// Вычитает сумму, приводя обе к большей экспоненте.
+    ///
+    /// Возвращает `None` при переполнении.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&Self::from_minor(other.minor_units.checked_neg()?, other.exponent))
+    }
+}
+
+impl PartialEq for Amount {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.minor_units == b.minor_units && a.exponent == b.exponent
+    }
+}
+
+impl Eq for Amount {}
+
+impl std::hash::Hash for Amount {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let amount = self.normalized();
+        amount.minor_units.hash(state);
+        amount.exponent.hash(state);
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.minor_units < 0 { "-" } else { "" };
+        let abs = self.minor_units.unsigned_abs();
+
+        if self.exponent == 0 {
+            return write!(f, "{}{}", sign, abs);
+        }
+
+        let divisor = 10u64.pow(u32::from(self.exponent));
+        write!(f, "{}{}.{:0width$}", sign, abs / divisor, abs % divisor, width = self.exponent as usize)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = ParserError;
+
+    /// Разбирает сумму, определяя экспоненту по количеству дробных знаков.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let exponent = s.split_once('.').map_or(0, |(_, frac)| frac.len());
+        let exponent = u8::try_from(exponent).map_err(|_| ParserError::Invalid(format!("Invalid amount: {}", s)))?;
+
+        Self::parse(s, exponent)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Сумма в конкретной валюте.
+///
+/// Сумма всегда приведена к экспоненте валюты.
+/// Арифметика разрешена только между суммами в одной валюте.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Amount,
+    currency: Currency,
+}
+
+impl Money {
+    /// Создаёт сумму из минимальных единиц валюты.
+    pub fn from_minor(minor_units: i64, currency: Currency) -> Self {
+        Self {
+            amount: Amount::from_minor(minor_units, currency.exponent()),
+            currency,
+        }
+    }
+
+    /// Создаёт сумму в валюте, приводя её к экспоненте валюты.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если сумма содержит больше
+    /// знаков после запятой, чем допускает валюта.
+    pub fn new(amount: Amount, currency: Currency) -> Result<Self, ParserError> {
+        let amount = amount.rescale(currency.exponent()).ok_or_else(|| ParserError::Invalid(format!(
+            "Amount {} cannot be represented in {} with {} decimal places", amount, currency, currency.exponent()
+        )))?;
+
+        Ok(Self { amount, currency })
+    }
+
+    /// Разбирает десятичную запись суммы в указанной валюте.
+    ///
+    /// # Ошибки
+    ///
+    /// См. [`Amount::parse`].
+    pub fn parse(s: &str, currency: Currency) -> Result<Self, ParserError> {
+        Ok(Self {
+            amount: Amount::parse(s, currency.exponent())?,
+            currency,
+        })
+    }
+
+    /// Десятичная сумма.
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    /// Валюта суммы.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Складывает суммы в одной валюте.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::CurrencyMismatch`], если валюты различаются,
+    /// и [`ParserError::Invalid`] при переполнении.
+    pub fn checked_add(&self, other: &Money) -> Result<Money, ParserError> {
+        self.ensure_same_currency(other)?;
+        let amount = self.amount.checked_add(&other.amount)
+            .ok_or_else(|| ParserError::Invalid(format!("Overflow in {} + {}", self, other)))?;
+
+        Ok(Money { amount, currency: self.currency })
+    }
+
+    /// Вычитает сумму в той же валюте.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::CurrencyMismatch`], если валюты различаются,
+    /// и [`ParserError::Invalid`] при переполнении.
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, ParserError> {
+        self.ensure_same_currency(other)?;
+        let amount = self.amount.checked_sub(&other.amount)
+            .ok_or_else(|| ParserError::Invalid(format!("Overflow in {} - {}", self, other)))?;
+
+        Ok(Money { amount, currency: self.currency })
+    }
+
+    fn ensure_same_currency(&self, other: &Money) -> Result<(), ParserError> {
+        if self.currency != other.currency {
+            return Err(ParserError::CurrencyMismatch(self.currency, other.currency));
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
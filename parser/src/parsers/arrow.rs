@@ -0,0 +1,279 @@
+//! Преобразование транзакций в колоночный формат Apache Arrow и обратно.
+//!
+//! Доступно с feature `arrow`. Каждое поле транзакции становится колонкой
+//! [`RecordBatch`] со строгим типом, см. [`schema`]:
+//!
+//! ```text
+//! id            UInt64             обязательное
+//! account       Utf8               обязательное
+//! amount        Decimal128(38, 4)  обязательное
+//! currency      Utf8               обязательное
+//! booking_date  Date32
+//! value_date    Date32
+//! description   Utf8
+//! counterparty  Utf8
+//! direction     Utf8               (`credit`/`debit`)
+//! status        Utf8               (`booked`/`pending`/`info`)
+//! ```
+//!
+//! Сумма хранится десятичным числом с [`AMOUNT_SCALE`] знаками после
+//! запятой, чтобы в одной колонке помещались валюты с разной точностью.
+//! При чтении колонки сопоставляются по именам, лишние колонки
+//! пропускаются, а колонки другого совместимого типа (`Int64` вместо
+//! `UInt64`, `LargeUtf8` вместо `Utf8`, `Decimal128` с другим масштабом
+//! и т. п.) приводятся к нужному.
+
+use std::sync::{Arc, LazyLock};
+use arrow::array::{
+    Array, ArrayRef, AsArray, Date32Builder, Decimal128Builder, RecordBatch, StringBuilder, UInt64Builder,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Date32Type, Decimal128Type, DecimalType, Field, Schema, SchemaRef, UInt64Type};
+use chrono::{Datelike, NaiveDate};
+use crate::csv::Column;
+use crate::errors::{ParserError, Position, RecordError};
+use crate::models::Transaction;
+use crate::{Amount, Currency};
+
+/// Число знаков после запятой в колонке `amount`.
+///
+/// Достаточно для любой валюты ISO 4217.
+pub const AMOUNT_SCALE: i8 = 4;
+
+/// Точность колонки `amount`.
+const AMOUNT_PRECISION: u8 = 38;
+
+/// Номер дня 01.01.1970 от начала нашей эры: начало отсчёта `Date32`.
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+static SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(
+        Column::ALL
+            .into_iter()
+            .map(|column| Field::new(column.name(), data_type(column), !column.is_required()))
+            .collect::<Vec<_>>(),
+    ))
+});
+
+/// Тип колонки для поля транзакции.
+fn data_type(column: Column) -> DataType {
+    match column {
+        Column::Id => DataType::UInt64,
+        Column::Amount => DataType::Decimal128(AMOUNT_PRECISION, AMOUNT_SCALE),
+        Column::BookingDate | Column::ValueDate => DataType::Date32,
+        _ => DataType::Utf8,
+    }
+}
+
+/// Схема Arrow для транзакций.
+pub fn schema() -> SchemaRef {
+    SCHEMA.clone()
+}
+
+/// Преобразует транзакции в [`RecordBatch`] со схемой [`schema`].
+///
+/// # Ошибки
+///
+/// Возвращает [`ParserError::Invalid`], если сумму нельзя представить
+/// с [`AMOUNT_SCALE`] знаками после запятой.
+pub fn to_record_batch(transactions: &[Transaction]) -> Result<RecordBatch, ParserError> {
+    let capacity = transactions.len();
+    let mut id = UInt64Builder::with_capacity(capacity);
+    let mut account = StringBuilder::with_capacity(capacity, 0);
+    let mut amount = Decimal128Builder::with_capacity(capacity)
+        .with_precision_and_scale(AMOUNT_PRECISION, AMOUNT_SCALE)
+        .map_err(arrow_error)?;
+    let mut currency = StringBuilder::with_capacity(capacity, 0);
+    let mut booking_date = Date32Builder::with_capacity(capacity);
+    let mut value_date = Date32Builder::with_capacity(capacity);
+    let mut description = StringBuilder::new();
+    let mut counterparty = StringBuilder::new();
+    let mut direction = StringBuilder::new();
+    let mut status = StringBuilder::new();
+
+    let days = |date: Option<NaiveDate>| date.map(|date| date.num_days_from_ce() - EPOCH_DAYS_FROM_CE);
+
+    for tx in transactions {
+        let scaled = scaled_amount(tx)?;
+
+        id.append_value(tx.id);
+        account.append_value(&tx.account);
+        amount.append_value(i128::from(scaled.minor_units()));
+        currency.append_value(tx.currency.code());
+        booking_date.append_option(days(tx.booking_date));
+        value_date.append_option(days(tx.value_date));
+        description.append_option(tx.description.as_deref());
+        counterparty.append_option(tx.counterparty.as_deref());
+        direction.append_option(tx.direction.map(|direction| direction.as_str()));
+        status.append_option(tx.status.map(|status| status.as_str()));
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(id.finish()),
+        Arc::new(account.finish()),
+        Arc::new(amount.finish()),
+        Arc::new(currency.finish()),
+        Arc::new(booking_date.finish()),
+        Arc::new(value_date.finish()),
+        Arc::new(description.finish()),
+        Arc::new(counterparty.finish()),
+        Arc::new(direction.finish()),
+        Arc::new(status.finish()),
+    ];
+
+    RecordBatch::try_new(schema(), columns).map_err(arrow_error)
+}
+
+/// Сумма транзакции с [`AMOUNT_SCALE`] знаками после запятой.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParserError::Invalid`], если сумму нельзя так представить.
+pub(crate) fn scaled_amount(tx: &Transaction) -> Result<Amount, ParserError> {
+    tx.amount.rescale(AMOUNT_SCALE as u8).ok_or_else(|| ParserError::Invalid(format!(
+        "Amount {} cannot be stored with {} decimal places", tx.amount, AMOUNT_SCALE
+    )))
+}
+
+/// Преобразует [`RecordBatch`] в транзакции.
+///
+/// # Ошибки
+///
+/// Возвращает:
+/// - [`ParserError::Invalid`] — если нет обязательной колонки или колонку
+///   нельзя привести к нужному типу;
+/// - [`ParserError::Record`] — если значение в строке некорректно; ошибка
+///   содержит номер строки и имя колонки.
+pub fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Transaction>, ParserError> {
+    rows(batch, "arrow", 0)?.collect()
+}
+
+/// Колонки пакета, приведённые к типам [`schema`].
+struct Columns {
+    arrays: Vec<Option<ArrayRef>>,
+}
+
+impl Columns {
+    fn get(&self, column: Column) -> Option<&ArrayRef> {
+        self.arrays[column as usize].as_ref()
+    }
+
+    /// Значение строковой колонки, если оно есть.
+    fn text(&self, column: Column, row: usize) -> Option<&str> {
+        let array = self.get(column)?.as_string::<i32>();
+        array.is_valid(row).then(|| array.value(row))
+    }
+
+    /// Значение колонки дат, если оно есть.
+    fn date(&self, column: Column, row: usize) -> Option<i32> {
+        let array = self.get(column)?.as_primitive::<Date32Type>();
+        array.is_valid(row).then(|| array.value(row))
+    }
+}
+
+/// Построчный итератор транзакций пакета.
+///
+/// Ошибки колонок возвращаются сразу, ошибки значений — элементами
+/// итератора; номера записей отсчитываются от `first + 1`.
+pub(crate) fn rows(
+    batch: &RecordBatch,
+    format: &'static str,
+    first: u64,
+) -> Result<impl Iterator<Item = Result<Transaction, ParserError>> + use<>, ParserError> {
+    let mut arrays = Vec::with_capacity(Column::ALL.len());
+    for column in Column::ALL {
+        let Some(array) = batch.column_by_name(column.name()) else {
+            if column.is_required() {
+                return Err(ParserError::Invalid(format!("Missing column: {}", column.name())));
+            }
+            arrays.push(None);
+            continue;
+        };
+
+        // Десятичная сумма читается в собственном масштабе, чтобы приведение не округляло её.
+        let array = match (column, array.data_type()) {
+            (Column::Amount, DataType::Decimal128(..)) => array.clone(),
+            (_, data_type) => cast(array, &self::data_type(column)).map_err(|err| ParserError::Invalid(format!(
+                "Column {} of type {} cannot be read: {}", column.name(), data_type, err
+            )))?,
+        };
+        arrays.push(Some(array));
+    }
+
+    let columns = Columns { arrays };
+    Ok((0..batch.num_rows()).map(move |row| row_transaction(&columns, row, format, first).map_err(ParserError::Record)))
+}
+
+fn row_transaction(columns: &Columns, row: usize, format: &'static str, first: u64) -> Result<Transaction, Box<RecordError>> {
+    let position = Position { record: Some(first + row as u64 + 1), ..Position::default() };
+    let error = |column: Column, reason: String| {
+        Box::new(RecordError::new(format, reason).at(position).field(column.name()))
+    };
+    let missing = |column: Column| error(column, "missing field".to_string());
+
+    let ids = columns.get(Column::Id).expect("required column").as_primitive::<UInt64Type>();
+    let id = ids.is_valid(row).then(|| ids.value(row)).ok_or_else(|| missing(Column::Id))?;
+
+    let account = columns.text(Column::Account, row).ok_or_else(|| missing(Column::Account))?;
+
+    let code = columns.text(Column::Currency, row).ok_or_else(|| missing(Column::Currency))?;
+    let currency: Currency = code.parse().map_err(|err: ParserError| error(Column::Currency, err.into_reason()).value(code))?;
+
+    let amounts = columns.get(Column::Amount).expect("required column").as_primitive::<Decimal128Type>();
+    if !amounts.is_valid(row) {
+        return Err(missing(Column::Amount));
+    }
+    let value = amounts.value(row);
+    let scale = amounts.scale();
+    let amount = minor_units(value, scale, currency.exponent())
+        .map(|minor_units| Amount::from_minor(minor_units, currency.exponent()))
+        .ok_or_else(|| {
+            let value = Decimal128Type::format_decimal(value, amounts.precision(), scale);
+            error(Column::Amount, format!("Amount {} cannot be represented in {}", value, currency)).value(value)
+        })?;
+
+    let mut tx = Transaction::new(id, account, amount, currency);
+
+    let date = |column: Column| -> Result<Option<NaiveDate>, Box<RecordError>> {
+        let Some(days) = columns.date(column, row) else { return Ok(None) };
+        days.checked_add(EPOCH_DAYS_FROM_CE)
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+            .map(Some)
+            .ok_or_else(|| error(column, format!("Invalid date: {} days since 1970-01-01", days)))
+    };
+    tx.booking_date = date(Column::BookingDate)?;
+    tx.value_date = date(Column::ValueDate)?;
+    tx.description = columns.text(Column::Description, row).map(str::to_string);
+    tx.counterparty = columns.text(Column::Counterparty, row).map(str::to_string);
+    if let Some(value) = columns.text(Column::Direction, row) {
+        tx.direction = Some(value.parse().map_err(|err: ParserError| error(Column::Direction, err.into_reason()).value(value))?);
+    }
+    if let Some(value) = columns.text(Column::Status, row) {
+        tx.status = Some(value.parse().map_err(|err: ParserError| error(Column::Status, err.into_reason()).value(value))?);
+    }
+
+    Ok(tx)
+}
+
+/// Переводит десятичное значение с масштабом `scale` в минимальные единицы
+/// валюты с экспонентой `exponent`.
+///
+/// Возвращает `None` при потере точности или переполнении.
+fn minor_units(value: i128, scale: i8, exponent: u8) -> Option<i64> {
+    let shift = i32::from(exponent) - i32::from(scale);
+    let factor = 10i128.checked_pow(shift.unsigned_abs())?;
+
+    let value = if shift >= 0 {
+        value.checked_mul(factor)?
+    } else if value % factor == 0 {
+        value / factor
+    } else {
+        return None;
+    };
+
+    i64::try_from(value).ok()
+}
+
+fn arrow_error(err: arrow::error::ArrowError) -> ParserError {
+    ParserError::Invalid(format!("Arrow error: {}", err))
+}
@@ -1,22 +1,84 @@
 //! Бинарный формат парсинга транзакций.
 //!
-//! Формат использует little-endian кодирование чисел и следующую структуру:
+//! Формат использует little-endian кодирование чисел. Текущая версия
+//! контейнера (v2) начинается с заголовка:
 //!
 //! ```text
-//! u32  — количество транзакций
+//! [u8; 4] — магические байты `YPBN`
+//! u16     — версия раскладки (2)
+//! u16     — флаги
+//! u32     — количество транзакций (`0xFFFFFFFF`, если неизвестно при записи)
+//! ```
+//!
+//! Если установлен флаг [`FLAG_BLOCKS`], записи сгруппированы в блоки
+//! с контрольной суммой, а поток завершается пустым блоком:
+//!
+//! ```text
+//! для каждого блока:
+//! u32  — количество записей в блоке (0 — конец потока)
+//! u32  — длина данных блока в байтах
+//! [u8] — записи блока
+//! u32  — CRC32 данных блока
+//! ```
+//!
+//! Без флагов записи следуют сразу за заголовком. Каждая запись:
 //!
-//! для каждой транзакции:
+//! ```text
 //! u64  — id
-//! i64  — amount
+//! i64  — amount в минимальных единицах валюты (центах, копейках)
 //! u32  — длина account
 //! [u8] — account (UTF-8)
-//! u32  — длина currency
-//! [u8] — currency (UTF-8)
+//! u16  — числовой код валюты ISO 4217 (например, 840 для USD)
+//! u8   — маска присутствующих необязательных полей
+//!
+//! далее — только поля, чей бит установлен в маске, в порядке битов:
+//! бит 0: i32        — booking_date (дней от 01.01.0001)
+//! бит 1: i32        — value_date (дней от 01.01.0001)
+//! бит 2: u32 + [u8] — description (UTF-8)
+//! бит 3: u32 + [u8] — counterparty (UTF-8)
+//! бит 4: u8         — direction (0 — credit, 1 — debit)
+//! бит 5: u8         — status (0 — booked, 1 — pending, 2 — info)
 //! ```
+//!
+//! Раскладка v1 не имеет заголовка: файл начинается сразу с `u32` количества
+//! транзакций, а записи не содержат маски и необязательных полей.
+//! Такие файлы по-прежнему читаются.
+//!
+//! Файлы, записанные до появления числового кода валюты, хранят её строкой
+//! (`u32` длина `3` и три байта буквенного кода). Такие записи по-прежнему
+//! читаются: числовой код `3` в ISO 4217 не используется, поэтому первые два
+//! байта `03 00` однозначно указывают на старое представление.
 
-use std::io::{Read, Write};
+use std::io::{Cursor, ErrorKind, Read, Write};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use crate::{Format, models::Transaction, errors::ParserError};
+use chrono::{Datelike, NaiveDate};
+use crate::{Amount, Currency, Direction, Format, Position, RecordError, Status, TransactionWriter};
+use crate::{models::Transaction, errors::ParserError};
+
+/// Магические байты заголовка раскладки v2.
+pub const MAGIC: [u8; 4] = *b"YPBN";
+
+/// Версия раскладки, которую пишет [`BinWriter`].
+pub const VERSION: u16 = 2;
+
+/// Флаг заголовка: записи сгруппированы в блоки с CRC32.
+pub const FLAG_BLOCKS: u16 = 1 << 0;
+
+/// Количество транзакций в заголовке, если оно неизвестно при записи.
+pub const UNKNOWN_COUNT: u32 = u32::MAX;
+
+/// Максимальное количество записей в блоке, которое пишет [`BinWriter`].
+const BLOCK_RECORDS: u32 = 1024;
+
+/// Размер данных блока, после которого [`BinWriter`] начинает новый блок.
+const BLOCK_BYTES: usize = 64 * 1024;
+
+const HAS_BOOKING_DATE: u8 = 1 << 0;
+const HAS_VALUE_DATE: u8 = 1 << 1;
+const HAS_DESCRIPTION: u8 = 1 << 2;
+const HAS_COUNTERPARTY: u8 = 1 << 3;
+const HAS_DIRECTION: u8 = 1 << 4;
+const HAS_STATUS: u8 = 1 << 5;
 
 /// Парсер бинарного формата транзакций.
 ///
@@ -27,71 +89,584 @@ use crate::{Format, models::Transaction, errors::ParserError};
 pub struct BinParser;
 
 impl Format for BinParser {
-    /// Считывает список транзакций из бинарного потока.
+    type Reader<R: Read> = BinReader<R>;
+    type Writer<W: Write> = BinWriter<W>;
+
+    /// Создаёт потоковый итератор транзакций из бинарного потока.
+    ///
+    /// Версия раскладки определяется по заголовку.
     ///
     /// # Формат входных данных
     /// См. документацию модуля.
     ///
     /// # Ошибки
     ///
-    /// Возвращает [`ParserError::Io`], если:
-    /// - поток содержит недостаточно данных;
-    /// - произошла ошибка чтения;
-    /// - данные имеют некорректную структуру.
-    fn read<R: Read>(mut reader: R) -> Result<Vec<Transaction>, ParserError> {
-        let count = reader.read_u32::<LittleEndian>().map_err(|err| ParserError::Io(err))?;
-        let mut transactions = Vec::with_capacity(count as usize);
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения;
+    /// - [`ParserError::Record`] — если поток оборван, контрольная сумма
+    ///   блока не совпала, версия или флаги не поддерживаются
+    ///   либо поле содержит недопустимое значение; ошибка содержит смещение
+    ///   в байтах, номер записи и имя поля.
+    ///
+    /// Блок с неверной контрольной суммой даёт одну ошибку на весь диапазон
+    /// его записей, после чего чтение продолжается со следующего блока.
+    /// После остальных ошибок итератор завершается.
+    fn reader<R: Read>(reader: R) -> BinReader<R> {
+        BinReader {
+            inner: Counted { inner: reader, offset: 0 },
+            header: None,
+            read: 0,
+            block: Counted { inner: Cursor::new(Vec::new()), offset: 0 },
+            block_remaining: 0,
+            blocks: 0,
+            skipped: false,
+            done: false,
+        }
+    }
+
+    /// Создаёт писателя бинарного формата.
+    ///
+    /// Записи пишутся блоками с контрольной суммой, поэтому писателю
+    /// не нужно заранее знать количество транзакций: в памяти хранится
+    /// не больше одного блока.
+    fn writer<W: Write>(writer: W) -> BinWriter<W> {
+        BinWriter {
+            inner: writer,
+            expected: None,
+            header_written: false,
+            written: 0,
+            block: Vec::new(),
+            block_records: 0,
+        }
+    }
+
+    /// Записывает список транзакций, указывая их количество в заголовке.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
+    fn write<W: Write>(writer: W, transactions: &[Transaction]) -> Result<(), ParserError> {
+        let mut writer = BinWriter::with_count(writer, transactions.len() as u32)?;
+        for tx in transactions {
+            writer.write(tx)?;
+        }
+
+        writer.finish()
+    }
+}
+
+/// Заголовок бинарного потока.
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    version: u16,
+    flags: u16,
+    count: u32,
+}
+
+/// Читатель, считающий прочитанные байты.
+struct Counted<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.offset += n as u64;
+
+        Ok(n)
+    }
+}
+
+/// Потоковый итератор транзакций из бинарного потока.
+pub struct BinReader<R: Read> {
+    inner: Counted<R>,
+    header: Option<Header>,
+    read: u32,
+    block: Counted<Cursor<Vec<u8>>>,
+    block_remaining: u32,
+    blocks: u32,
+    skipped: bool,
+    done: bool,
+}
+
+impl<R: Read> BinReader<R> {
+    fn read_header(&mut self) -> Result<Header, ParserError> {
+        let missing = |err| truncated(err, 0, "header");
 
-        for _ in 0..count {
-            let id = reader.read_u64::<LittleEndian>().map_err(|err| ParserError::Io(err))?;
-            let amount = reader.read_i64::<LittleEndian>().map_err(|err| ParserError::Io(err))?;
-            let account = read_string(&mut reader)?;
-            let currency = read_string(&mut reader)?;
+        let mut head = [0u8; 4];
+        self.inner.read_exact(&mut head).map_err(missing)?;
 
-            transactions.push(Transaction{
-                id,
-                account,
-                amount,
-                currency,
-            })
+        if head != MAGIC {
+            // Раскладка v1: первые четыре байта — количество транзакций.
+            return Ok(Header { version: 1, flags: 0, count: u32::from_le_bytes(head) });
         }
 
-        Ok(transactions)
+        let version = self.inner.read_u16::<LittleEndian>().map_err(missing)?;
+        if version != VERSION {
+            return Err(invalid(4, format!("Unsupported BIN version: {}", version)));
+        }
+
+        let flags = self.inner.read_u16::<LittleEndian>().map_err(missing)?;
+        if flags & !FLAG_BLOCKS != 0 {
+            return Err(invalid(6, format!("Unsupported BIN flags: {:#06x}", flags)));
+        }
+
+        let count = self.inner.read_u32::<LittleEndian>().map_err(missing)?;
+
+        Ok(Header { version, flags, count })
+    }
+
+    /// Читает следующую запись; `None` означает корректный конец потока.
+    fn next_transaction(&mut self, header: Header) -> Result<Option<Transaction>, ParserError> {
+        let record = u64::from(self.read) + 1;
+
+        if header.flags & FLAG_BLOCKS == 0 {
+            if self.read == header.count {
+                return Ok(None);
+            }
+
+            let tx = read_transaction(&mut self.inner, header.version).map_err(|err| in_record(err, record))?;
+            self.read += 1;
+
+            return Ok(Some(tx));
+        }
+
+        if self.block_remaining == 0 && !self.read_block()? {
+            if header.count != UNKNOWN_COUNT && header.count != self.read {
+                return Err(invalid(self.inner.offset, format!(
+                    "BIN header declares {} transactions, found {}", header.count, self.read
+                )));
+            }
+            return Ok(None);
+        }
+
+        let tx = read_transaction(&mut self.block, header.version).map_err(|err| in_record(err, record))?;
+        self.block_remaining -= 1;
+        self.read += 1;
+
+        let block_len = self.block.inner.get_ref().len() as u64;
+        let position = self.block.inner.position();
+        if self.block_remaining == 0 && position != block_len {
+            return Err(invalid(self.block.offset, format!(
+                "BIN block {} has {} trailing bytes", self.blocks, block_len - position
+            )));
+        }
+
+        Ok(Some(tx))
     }
 
-    /// Записывает список транзакций в бинарный поток.
+    /// Читает и проверяет следующий блок; `false` означает маркер конца потока.
+    fn read_block(&mut self) -> Result<bool, ParserError> {
+        let start = self.inner.offset;
+        let missing = |err| truncated(err, start, "block");
+
+        let records = self.inner.read_u32::<LittleEndian>().map_err(missing)?;
+        if records == 0 {
+            return Ok(false);
+        }
+
+        let len = self.inner.read_u32::<LittleEndian>().map_err(missing)?;
+        let payload_start = self.inner.offset;
+        let payload = read_bytes(&mut self.inner, len).map_err(missing)?;
+        let checksum = self.inner.read_u32::<LittleEndian>().map_err(missing)?;
+
+        self.blocks += 1;
+        if crc32fast::hash(&payload) != checksum {
+            // Блок прочитан целиком, поэтому поток остаётся выровненным:
+            // его записи пропускаются, а чтение продолжается со следующего блока.
+            let first = u64::from(self.read) + 1;
+            self.read = self.read.saturating_add(records);
+            self.skipped = true;
+            return Err(in_record(invalid(start, format!(
+                "Checksum mismatch in BIN block {} (records {}-{})",
+                self.blocks, first, first + u64::from(records) - 1
+            )), first));
+        }
+
+        self.block = Counted { inner: Cursor::new(payload), offset: payload_start };
+        self.block_remaining = records;
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for BinReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let header = match self.header {
+            Some(header) => header,
+            None => match self.read_header() {
+                Ok(header) => *self.header.insert(header),
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            },
+        };
+
+        // После ошибки выравнивание потока потеряно, продолжать чтение бессмысленно;
+        // исключение — пропущенный блок с неверной контрольной суммой.
+        let result = self.next_transaction(header);
+        self.done = match result {
+            Ok(Some(_)) => false,
+            Ok(None) => true,
+            Err(_) => !std::mem::take(&mut self.skipped),
+        };
+
+        result.transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.header {
+            Some(header) if header.count != UNKNOWN_COUNT => {
+                (0, Some(header.count.saturating_sub(self.read) as usize))
+            }
+            _ => (0, None),
+        }
+    }
+}
+
+/// Инкрементальный писатель транзакций в бинарный поток.
+///
+/// Всегда пишет текущую версию раскладки ([`VERSION`]) с блоками
+/// и контрольными суммами ([`FLAG_BLOCKS`]).
+pub struct BinWriter<W: Write> {
+    inner: W,
+    expected: Option<u32>,
+    header_written: bool,
+    written: u32,
+    block: Vec<u8>,
+    block_records: u32,
+}
+
+impl<W: Write> BinWriter<W> {
+    /// Создаёт писателя, заранее знающего количество транзакций.
     ///
-    /// # А
-    /// См. документацию модуля.
+    /// Количество записывается в заголовок и проверяется читателем.
     ///
     /// # Ошибки
     ///
     /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
-    fn write<W: Write>(mut writer: W, transactions: &[Transaction]) -> Result<(), ParserError> {
-        writer.write_u32::<LittleEndian>(transactions.len() as u32).map_err(|err| ParserError::Io(err))?;
+    pub fn with_count(mut writer: W, count: u32) -> Result<Self, ParserError> {
+        write_header(&mut writer, count)?;
 
-        for tx in transactions {
-            writer.write_u64::<LittleEndian>(tx.id).map_err(|err| ParserError::Io(err))?;
-            writer.write_i64::<LittleEndian>(tx.amount).map_err(|err| ParserError::Io(err))?;
-            write_string(&mut writer, &tx.account)?;
-            write_string(&mut writer, &tx.currency)?
+        Ok(Self {
+            inner: writer,
+            expected: Some(count),
+            header_written: true,
+            written: 0,
+            block: Vec::new(),
+            block_records: 0,
+        })
+    }
+
+    fn ensure_header(&mut self) -> Result<(), ParserError> {
+        if !self.header_written {
+            write_header(&mut self.inner, UNKNOWN_COUNT)?;
+            self.header_written = true;
         }
 
         Ok(())
     }
+
+    fn flush_block(&mut self) -> Result<(), ParserError> {
+        if self.block_records == 0 {
+            return Ok(());
+        }
+
+        self.ensure_header()?;
+        self.inner.write_u32::<LittleEndian>(self.block_records).map_err(ParserError::Io)?;
+        write_bytes(&mut self.inner, &self.block)?;
+        self.inner.write_u32::<LittleEndian>(crc32fast::hash(&self.block)).map_err(ParserError::Io)?;
+
+        self.block.clear();
+        self.block_records = 0;
+
+        Ok(())
+    }
+}
+
+impl<W: Write> TransactionWriter for BinWriter<W> {
+    /// Записывает одну транзакцию.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи,
+    /// и [`ParserError::Invalid`], если записей больше, чем заявлено
+    /// в [`BinWriter::with_count`].
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        if self.expected == Some(self.written) {
+            return Err(ParserError::Invalid(format!(
+                "Too many transactions: expected {}", self.written
+            )));
+        }
+
+        write_transaction(&mut self.block, tx)?;
+        self.block_records += 1;
+        self.written += 1;
+
+        if self.block_records == BLOCK_RECORDS || self.block.len() >= BLOCK_BYTES {
+            self.flush_block()?;
+        }
+
+        Ok(())
+    }
+
+    /// Дописывает последний блок, маркер конца потока и сбрасывает буферы.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи,
+    /// и [`ParserError::Invalid`], если записано меньше транзакций,
+    /// чем заявлено в [`BinWriter::with_count`].
+    fn finish(&mut self) -> Result<(), ParserError> {
+        if let Some(expected) = self.expected.filter(|expected| *expected != self.written) {
+            return Err(ParserError::Invalid(format!(
+                "Too few transactions: expected {}, written {}", expected, self.written
+            )));
+        }
+
+        self.flush_block()?;
+        self.ensure_header()?;
+        self.inner.write_u32::<LittleEndian>(0).map_err(ParserError::Io)?;
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
+
+fn write_header<W: Write>(w: &mut W, count: u32) -> Result<(), ParserError> {
+    w.write_all(&MAGIC).map_err(ParserError::Io)?;
+    w.write_u16::<LittleEndian>(VERSION).map_err(ParserError::Io)?;
+    w.write_u16::<LittleEndian>(FLAG_BLOCKS).map_err(ParserError::Io)?;
+    w.write_u32::<LittleEndian>(count).map_err(ParserError::Io)
+}
+
+/// Ошибка структуры потока по смещению `byte`.
+fn invalid(byte: u64, reason: impl Into<String>) -> ParserError {
+    RecordError::new("bin", reason)
+        .at(Position { byte: Some(byte), ..Position::default() })
+        .into()
+}
+
+/// Превращает преждевременный конец потока в понятную ошибку.
+fn truncated(err: std::io::Error, byte: u64, what: &str) -> ParserError {
+    match err.kind() {
+        ErrorKind::UnexpectedEof => invalid(byte, format!("Truncated BIN data: incomplete {}", what)),
+        _ => ParserError::Io(err),
+    }
+}
+
+/// Дополняет ошибку порядковым номером записи.
+fn in_record(err: ParserError, record: u64) -> ParserError {
+    match err {
+        ParserError::Record(mut err) => {
+            err.position.record = Some(record);
+            ParserError::Record(err)
+        }
+        err => err,
+    }
+}
+
+/// Недопустимое значение поля.
+fn invalid_value(reason: impl Into<String>, value: impl ToString) -> ParserError {
+    RecordError::new("bin", reason).value(value.to_string()).into()
+}
+
+/// Читает поле записи; ошибка указывает имя поля и смещение его начала.
+fn read_field<R: Read, T>(
+    r: &mut Counted<R>,
+    name: &'static str,
+    read: impl FnOnce(&mut Counted<R>) -> Result<T, ParserError>,
+) -> Result<T, ParserError> {
+    let byte = r.offset;
+
+    read(r).map_err(|err| {
+        let mut err = match err {
+            ParserError::Io(err) if err.kind() == ErrorKind::UnexpectedEof => {
+                RecordError::new("bin", "Truncated BIN data: incomplete record")
+            }
+            ParserError::Invalid(reason) => RecordError::new("bin", reason),
+            ParserError::Record(err) => *err,
+            err => return err,
+        };
+        err.position.byte = Some(byte);
+        err.field = Some(name);
+
+        err.into()
+    })
+}
+
+fn read_transaction<R: Read>(r: &mut Counted<R>, version: u16) -> Result<Transaction, ParserError> {
+    let id = read_field(r, "id", |r| r.read_u64::<LittleEndian>().map_err(ParserError::Io))?;
+    let minor_units = read_field(r, "amount", |r| r.read_i64::<LittleEndian>().map_err(ParserError::Io))?;
+    let account = read_field(r, "account", read_string)?;
+    let currency = read_field(r, "currency", read_currency)?;
+    let amount = Amount::from_minor(minor_units, currency.exponent());
+
+    let mut tx = Transaction::new(id, account, amount, currency);
+    if version >= 2 {
+        read_optional_fields(r, &mut tx)?;
+    }
+
+    Ok(tx)
+}
+
+fn write_transaction<W: Write>(w: &mut W, tx: &Transaction) -> Result<(), ParserError> {
+    w.write_u64::<LittleEndian>(tx.id).map_err(ParserError::Io)?;
+    w.write_i64::<LittleEndian>(tx.money()?.amount().minor_units()).map_err(ParserError::Io)?;
+    write_string(w, &tx.account)?;
+    w.write_u16::<LittleEndian>(tx.currency.numeric()).map_err(ParserError::Io)?;
+    write_optional_fields(w, tx)
+}
+
+fn write_optional_fields<W: Write>(w: &mut W, tx: &Transaction) -> Result<(), ParserError> {
+    let mask = [
+        (tx.booking_date.is_some(), HAS_BOOKING_DATE),
+        (tx.value_date.is_some(), HAS_VALUE_DATE),
+        (tx.description.is_some(), HAS_DESCRIPTION),
+        (tx.counterparty.is_some(), HAS_COUNTERPARTY),
+        (tx.direction.is_some(), HAS_DIRECTION),
+        (tx.status.is_some(), HAS_STATUS),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .fold(0, |mask, (_, bit)| mask | bit);
+    w.write_u8(mask).map_err(ParserError::Io)?;
+
+    if let Some(date) = tx.booking_date {
+        w.write_i32::<LittleEndian>(date.num_days_from_ce()).map_err(ParserError::Io)?;
+    }
+    if let Some(date) = tx.value_date {
+        w.write_i32::<LittleEndian>(date.num_days_from_ce()).map_err(ParserError::Io)?;
+    }
+    if let Some(description) = &tx.description {
+        write_string(w, description)?;
+    }
+    if let Some(counterparty) = &tx.counterparty {
+        write_string(w, counterparty)?;
+    }
+    if let Some(direction) = tx.direction {
+        let code = match direction {
+            Direction::Credit => 0,
+            Direction::Debit => 1,
+        };
+        w.write_u8(code).map_err(ParserError::Io)?;
+    }
+    if let Some(status) = tx.status {
+        let code = match status {
+            Status::Booked => 0,
+            Status::Pending => 1,
+            Status::Info => 2,
+        };
+        w.write_u8(code).map_err(ParserError::Io)?;
+    }
+
+    Ok(())
+}
+
+fn read_optional_fields<R: Read>(r: &mut Counted<R>, tx: &mut Transaction) -> Result<(), ParserError> {
+    let mask = read_field(r, "mask", |r| {
+        let mask = r.read_u8().map_err(ParserError::Io)?;
+        let known = HAS_BOOKING_DATE | HAS_VALUE_DATE | HAS_DESCRIPTION | HAS_COUNTERPARTY | HAS_DIRECTION | HAS_STATUS;
+        if mask & !known != 0 {
+            return Err(invalid_value("Unknown optional field mask", format!("{:#04x}", mask)));
+        }
+
+        Ok(mask)
+    })?;
+
+    if mask & HAS_BOOKING_DATE != 0 {
+        tx.booking_date = Some(read_field(r, "booking_date", read_date)?);
+    }
+    if mask & HAS_VALUE_DATE != 0 {
+        tx.value_date = Some(read_field(r, "value_date", read_date)?);
+    }
+    if mask & HAS_DESCRIPTION != 0 {
+        tx.description = Some(read_field(r, "description", read_string)?);
+    }
+    if mask & HAS_COUNTERPARTY != 0 {
+        tx.counterparty = Some(read_field(r, "counterparty", read_string)?);
+    }
+    if mask & HAS_DIRECTION != 0 {
+        tx.direction = Some(read_field(r, "direction", |r| match r.read_u8().map_err(ParserError::Io)? {
+            0 => Ok(Direction::Credit),
+            1 => Ok(Direction::Debit),
+            code => Err(invalid_value("Invalid direction code", code)),
+        })?);
+    }
+    if mask & HAS_STATUS != 0 {
+        tx.status = Some(read_field(r, "status", |r| match r.read_u8().map_err(ParserError::Io)? {
+            0 => Ok(Status::Booked),
+            1 => Ok(Status::Pending),
+            2 => Ok(Status::Info),
+            code => Err(invalid_value("Invalid status code", code)),
+        })?);
+    }
+
+    Ok(())
+}
+
+fn read_date<R: Read>(r: &mut R) -> Result<NaiveDate, ParserError> {
+    let days = r.read_i32::<LittleEndian>().map_err(ParserError::Io)?;
+
+    NaiveDate::from_num_days_from_ce_opt(days)
+        .ok_or_else(|| invalid_value("Invalid date: days from CE out of range", days))
+}
+
+/// Числовой код, которым начинается строковое представление валюты
+/// (младшие байты длины `3`).
+const LEGACY_CURRENCY_MARKER: u16 = 3;
+
+fn read_currency<R: Read>(r: &mut R) -> Result<Currency, ParserError> {
+    let numeric = r.read_u16::<LittleEndian>().map_err(ParserError::Io)?;
+
+    if numeric == LEGACY_CURRENCY_MARKER {
+        let high = r.read_u16::<LittleEndian>().map_err(ParserError::Io)?;
+        let mut code = [0u8; 3];
+        r.read_exact(&mut code).map_err(ParserError::Io)?;
+
+        let text = std::str::from_utf8(&code).ok().filter(|_| high == 0);
+        return match text {
+            Some(text) => text.parse().map_err(|err: ParserError| invalid_value(err.into_reason(), text)),
+            None => Err(invalid_value("Invalid currency code", format!("{:?}", code))),
+        };
+    }
+
+    Currency::from_numeric(numeric)
+        .ok_or_else(|| invalid_value("Unknown numeric currency code", numeric))
+}
+
+/// Читает ровно `len` байт, не выделяя память заранее: длина из повреждённого
+/// файла может быть сколь угодно большой.
+fn read_bytes<R: Read>(r: &mut R, len: u32) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    r.take(u64::from(len)).read_to_end(&mut buf)?;
+
+    if buf.len() != len as usize {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(buf)
+}
+
+fn write_bytes<W: Write>(w: &mut W, bytes: &[u8]) -> Result<(), ParserError> {
+    w.write_u32::<LittleEndian>(bytes.len() as u32).map_err(ParserError::Io)?;
+    w.write_all(bytes).map_err(ParserError::Io)
 }
 
 fn read_string<R: Read>(r: &mut R) -> Result<String, ParserError> {
-    let n = r.read_u32::<LittleEndian>().map_err(|err| ParserError::Io(err))?;
-    let mut buf = vec![0u8; n as usize];
+    let n = r.read_u32::<LittleEndian>().map_err(ParserError::Io)?;
+    let buf = read_bytes(r, n).map_err(ParserError::Io)?;
 
-    r.read_exact(&mut buf).map_err(|err| ParserError::Io(err))?;
     Ok(String::from_utf8_lossy(&buf).to_string())
 }
 
 fn write_string<W: Write>(w: &mut W, s: &str) -> Result<(), ParserError> {
-    w.write_u32::<LittleEndian>(s.len() as u32).map_err(|err| ParserError::Io(err))?;
-    w.write_all(s.as_bytes()).map_err(|err| ParserError::Io(err))?;
-
-    Ok(())
-}
\ No newline at end of file
+    write_bytes(w, s.as_bytes())
+}
@@ -0,0 +1,500 @@
+//! Выписки ISO 20022 camt.053 и уведомления camt.054 (XML).
+//!
+//! Читатель принимает оба вида сообщений и превращает каждую запись
+//! (`Ntry`) в [`Transaction`]:
+//!
+//! - `NtryRef` — идентификатор транзакции; нечисловая ссылка, как и в MT940,
+//!   даёт идентификатор [`NONREF_ID`] плюс порядковый номер записи;
+//! - `Amt` и его атрибут `Ccy` — сумма и валюта;
+//! - `CdtDbtInd` — знак суммы и направление (`DBIT` — списание);
+//! - `Acct/Id/IBAN` (или `Acct/Id/Othr/Id`) выписки — счёт;
+//! - `Sts`, `BookgDt`, `ValDt` — статус и даты;
+//! - `RmtInf/Ustrd` (или `AddtlNtryInf`) — описание;
+//! - счёт плательщика или получателя из `RltdPties` — контрагент.
+//!
+//! Писатель формирует минимальную выписку camt.053.001.02: по одному
+//! `Stmt` на каждую последовательность транзакций с одинаковыми счётом
+//! и валютой. Входящий остаток (`OPBD`) неизвестен и пишется нулевым,
+//! исходящий (`CLBD`) равен сумме записей выписки.
+
+use std::borrow::Cow;
+use std::io::{BufReader, Read, Write};
+use chrono::{NaiveDate, NaiveDateTime};
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::{BytesStart, Event};
+use crate::{Amount, Currency, Direction, Format, Position, RecordError, Status, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, now_utc, parse_date};
+use crate::mt940::NONREF_ID;
+
+/// Пространство имён, которое пишет [`CamtParser`].
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02";
+
+/// Парсер выписок camt.053/camt.054.
+pub struct CamtParser;
+
+impl Format for CamtParser {
+    type Reader<R: Read> = CamtReader<R>;
+    type Writer<W: Write> = CamtWriter<W>;
+
+    /// Создаёт потоковый итератор по записям выписки.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если XML повреждён или запись
+    ///   не соответствует модели [`Transaction`]; ошибка содержит номер
+    ///   записи, смещение её начала и имя элемента.
+    ///
+    /// После ошибки разбора XML итератор завершается; после ошибки
+    /// в значении элемента чтение продолжается со следующей записи.
+    fn reader<R: Read>(reader: R) -> CamtReader<R> {
+        let mut xml = quick_xml::Reader::from_reader(BufReader::new(reader));
+        xml.config_mut().expand_empty_elements = true;
+
+        CamtReader {
+            xml,
+            buf: Vec::new(),
+            path: Vec::new(),
+            text: String::new(),
+            account: None,
+            entry: None,
+            entries: 0,
+            done: false,
+        }
+    }
+
+    /// Создаёт писателя выписки camt.053.
+    ///
+    /// Транзакции одной выписки накапливаются в памяти, пока не сменятся
+    /// счёт или валюта: остатки пишутся перед записями.
+    ///
+    /// Время создания сообщения — текущее время UTC; другое время задаёт
+    /// [`CamtWriter::with_time`].
+    fn writer<W: Write>(writer: W) -> CamtWriter<W> {
+        CamtWriter::with_time(writer, now_utc())
+    }
+}
+
+/// Значения элементов записи `Ntry`, собранные до её закрытия.
+#[derive(Default)]
+struct Entry {
+    byte: u64,
+    reference: Option<String>,
+    amount: Option<String>,
+    currency: Option<String>,
+    indicator: Option<String>,
+    status: Option<String>,
+    booking_date: Option<String>,
+    value_date: Option<String>,
+    remittance: Option<String>,
+    info: Option<String>,
+    debtor: Option<String>,
+    creditor: Option<String>,
+}
+
+/// Потоковый итератор транзакций из camt.053/camt.054.
+pub struct CamtReader<R: Read> {
+    xml: quick_xml::Reader<BufReader<R>>,
+    buf: Vec<u8>,
+    /// Локальные имена открытых элементов.
+    path: Vec<String>,
+    /// Текст текущего элемента.
+    text: String,
+    /// Счёт текущей выписки.
+    account: Option<String>,
+    entry: Option<Entry>,
+    entries: u64,
+    done: bool,
+}
+
+impl<R: Read> CamtReader<R> {
+    fn syntax_error(&mut self, reason: impl Into<String>, byte: u64) -> ParserError {
+        self.done = true;
+        RecordError::new("camt", reason)
+            .at(Position { record: Some(self.entries + 1), byte: Some(byte), ..Position::default() })
+            .into()
+    }
+
+    fn start(&mut self, element: &BytesStart, byte: u64) -> Result<(), ParserError> {
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+        self.text.clear();
+
+        if self.path.is_empty() && name != "Document" {
+            return Err(self.syntax_error(format!("expected camt Document, found <{}>", name), byte));
+        }
+
+        match name.as_str() {
+            "Stmt" | "Ntfctn" | "Rpt" => self.account = None,
+            "Ntry" => self.entry = Some(Entry { byte, ..Entry::default() }),
+            "Amt" if self.path.last().is_some_and(|parent| parent == "Ntry") => {
+                let currency = element.try_get_attribute("Ccy")
+                    .ok()
+                    .flatten()
+                    .and_then(|attr| attr.unescape_value().ok().map(Cow::into_owned));
+                if let Some(entry) = self.entry.as_mut() {
+                    entry.currency = currency;
+                }
+            }
+            _ => {}
+        }
+
+        self.path.push(name);
+
+        Ok(())
+    }
+
+    /// Закрывает элемент; возвращает запись, если закрыт `Ntry`.
+    fn end(&mut self) -> Option<Entry> {
+        let text = self.text.trim().to_string();
+        self.text.clear();
+
+        let path: Vec<&str> = self.path.iter().map(String::as_str).collect();
+        match self.entry.as_mut() {
+            Some(entry) => {
+                let start = path.iter().rposition(|&name| name == "Ntry").map_or(path.len(), |i| i + 1);
+                let slot = match &path[start..] {
+                    ["NtryRef"] => &mut entry.reference,
+                    ["Amt"] => &mut entry.amount,
+                    ["CdtDbtInd"] => &mut entry.indicator,
+                    ["Sts"] | ["Sts", "Cd"] => &mut entry.status,
+                    ["BookgDt", "Dt" | "DtTm"] => &mut entry.booking_date,
+                    ["ValDt", "Dt" | "DtTm"] => &mut entry.value_date,
+                    ["AddtlNtryInf"] => &mut entry.info,
+                    ["NtryDtls", "TxDtls", "RmtInf", "Ustrd"] => &mut entry.remittance,
+                    ["NtryDtls", "TxDtls", "RltdPties", "DbtrAcct", "Id", "IBAN"]
+                    | ["NtryDtls", "TxDtls", "RltdPties", "DbtrAcct", "Id", "Othr", "Id"] => &mut entry.debtor,
+                    ["NtryDtls", "TxDtls", "RltdPties", "CdtrAcct", "Id", "IBAN"]
+                    | ["NtryDtls", "TxDtls", "RltdPties", "CdtrAcct", "Id", "Othr", "Id"] => &mut entry.creditor,
+                    _ => &mut None,
+                };
+                // Значение берётся из первого встреченного элемента.
+                if slot.is_none() && !text.is_empty() {
+                    *slot = Some(text);
+                }
+            }
+            None => {
+                if let [.., "Stmt" | "Ntfctn" | "Rpt", "Acct", "Id", "IBAN"]
+                | [.., "Stmt" | "Ntfctn" | "Rpt", "Acct", "Id", "Othr", "Id"] = path.as_slice() {
+                    self.account.get_or_insert(text);
+                }
+            }
+        }
+
+        if self.path.pop().as_deref() == Some("Ntry") {
+            return self.entry.take();
+        }
+
+        None
+    }
+
+    /// Собирает транзакцию из записи выписки с порядковым номером `index`.
+    fn transaction(&self, entry: Entry, index: u64) -> Result<Transaction, Box<RecordError>> {
+        let error = |field: &'static str, value: &Option<String>, reason: String| {
+            let error = RecordError::new("camt", reason).field(field);
+            Box::new(match value {
+                Some(value) => error.value(value.clone()),
+                None => error,
+            })
+        };
+        let required = |field: &'static str, value: &Option<String>| {
+            value.clone().ok_or_else(|| error(field, value, "missing element".to_string()))
+        };
+
+        let account = required("Acct", &self.account)?;
+        let id = required("NtryRef", &entry.reference)?.parse().unwrap_or(NONREF_ID + index);
+        let currency: Currency = required("Ccy", &entry.currency)?
+            .parse()
+            .map_err(|err: ParserError| error("Ccy", &entry.currency, err.into_reason()))?;
+        let direction: Direction = required("CdtDbtInd", &entry.indicator)?
+            .parse()
+            .map_err(|err: ParserError| error("CdtDbtInd", &entry.indicator, err.into_reason()))?;
+        let amount = Amount::parse(&required("Amt", &entry.amount)?, currency.exponent())
+            .ok()
+            .filter(|amount| amount.minor_units() >= 0)
+            .ok_or_else(|| error("Amt", &entry.amount, "Invalid amount".to_string()))?;
+        let amount = match direction {
+            Direction::Credit => amount,
+            Direction::Debit => Amount::from_minor(-amount.minor_units(), amount.exponent()),
+        };
+
+        let date = |field: &'static str, value: &Option<String>| {
+            value.as_deref()
+                .map(date)
+                .transpose()
+                .map_err(|err| error(field, value, err.into_reason()))
+        };
+
+        let mut tx = Transaction::new(id, account, amount, currency);
+        tx.booking_date = date("BookgDt", &entry.booking_date)?;
+        tx.value_date = date("ValDt", &entry.value_date)?;
+        tx.description = entry.remittance.or(entry.info);
+        tx.counterparty = match direction {
+            Direction::Credit => entry.debtor.or(entry.creditor),
+            Direction::Debit => entry.creditor.or(entry.debtor),
+        };
+        tx.direction = Some(direction);
+        tx.status = entry.status
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err: ParserError| error("Sts", &entry.status, err.into_reason()))?;
+
+        Ok(tx)
+    }
+}
+
+impl<R: Read> Iterator for CamtReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            let byte = self.xml.buffer_position();
+
+            let event = match self.xml.read_event_into(&mut self.buf) {
+                Ok(event) => event.into_owned(),
+                Err(quick_xml::Error::Io(err)) => {
+                    self.done = true;
+                    let err = std::sync::Arc::try_unwrap(err)
+                        .unwrap_or_else(|err| std::io::Error::new(err.kind(), err.to_string()));
+                    return Some(Err(ParserError::Io(err)));
+                }
+                Err(err) => {
+                    let byte = self.xml.error_position();
+                    return Some(Err(self.syntax_error(err.to_string(), byte)));
+                }
+            };
+
+            let entry = match event {
+                Event::Start(element) => match self.start(&element, byte) {
+                    Ok(()) => continue,
+                    Err(err) => return Some(Err(err)),
+                },
+                Event::End(_) => self.end(),
+                Event::Text(text) => {
+                    self.text.push_str(&text.xml_content().unwrap_or_default());
+                    continue;
+                }
+                Event::CData(data) => {
+                    self.text.push_str(&data.decode().unwrap_or_default());
+                    continue;
+                }
+                Event::GeneralRef(reference) => {
+                    let resolved = match reference.resolve_char_ref() {
+                        Ok(Some(ch)) => Some(ch.to_string()),
+                        _ => reference.decode().ok().and_then(|name| resolve_predefined_entity(&name)).map(str::to_string),
+                    };
+                    match resolved {
+                        Some(resolved) => self.text.push_str(&resolved),
+                        None => return Some(Err(self.syntax_error("unknown entity reference", byte))),
+                    }
+                    continue;
+                }
+                Event::Eof if !self.path.is_empty() || byte == 0 => {
+                    return Some(Err(self.syntax_error("unexpected end of camt document", byte)));
+                }
+                Event::Eof => {
+                    self.done = true;
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if let Some(entry) = entry {
+                self.entries += 1;
+                let position = Position { record: Some(self.entries), byte: Some(entry.byte), ..Position::default() };
+
+                return Some(self.transaction(entry, self.entries).map_err(|err| err.at(position).into()));
+            }
+        }
+
+        None
+    }
+}
+
+/// Писатель выписки camt.053.
+pub struct CamtWriter<W: Write> {
+    inner: W,
+    /// Время создания сообщения (`CreDtTm`).
+    created: String,
+    /// Транзакции текущей выписки.
+    statement: Vec<Transaction>,
+    /// Количество записанных выписок.
+    statements: u64,
+}
+
+impl<W: Write> CamtWriter<W> {
+    /// Создаёт писателя с временем создания сообщения `created` (`CreDtTm`);
+    /// его дата служит датой остатков выписки без датированных записей.
+    pub fn with_time(writer: W, created: NaiveDateTime) -> Self {
+        Self {
+            inner: writer,
+            created: format!("{}T{}", created.date(), created.time()),
+            statement: Vec::new(),
+            statements: 0,
+        }
+    }
+
+    fn header(&mut self) -> std::io::Result<()> {
+        write!(
+            self.inner,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <Document xmlns=\"{}\">\n  \
+             <BkToCstmrStmt>\n    \
+             <GrpHdr>\n      \
+             <MsgId>{}</MsgId>\n      \
+             <CreDtTm>{}</CreDtTm>\n    \
+             </GrpHdr>\n",
+            NAMESPACE, self.created, self.created
+        )
+    }
+
+    /// Записывает накопленную выписку.
+    fn flush_statement(&mut self) -> Result<(), ParserError> {
+        let Some(first) = self.statement.first() else { return Ok(()) };
+        let currency = first.currency;
+
+        let mut closing = Amount::from_minor(0, currency.exponent());
+        for tx in &self.statement {
+            closing = closing.checked_add(&tx.money()?.amount())
+                .ok_or_else(|| ParserError::Invalid(format!("Statement balance overflow for account {}", tx.account)))?;
+        }
+        let date = self.statement.iter().filter_map(|tx| tx.booking_date).max()
+            .map_or_else(|| self.created[..10].to_string(), |date| date.to_string());
+
+        self.statements += 1;
+        let mut out = String::new();
+        out.push_str("    <Stmt>\n");
+        out.push_str(&format!("      <Id>{}</Id>\n", self.statements));
+        out.push_str(&format!("      <CreDtTm>{}</CreDtTm>\n", self.created));
+        out.push_str(&format!("      <Acct>\n        <Id>{}</Id>\n      </Acct>\n", account_id(&first.account)));
+        out.push_str(&balance("OPBD", Amount::from_minor(0, currency.exponent()), currency, &date));
+        out.push_str(&balance("CLBD", closing, currency, &date));
+        for tx in &self.statement {
+            out.push_str(&entry(tx, tx.money()?.amount()));
+        }
+        out.push_str("    </Stmt>\n");
+
+        self.statement.clear();
+        self.inner.write_all(out.as_bytes()).map_err(ParserError::Io)
+    }
+}
+
+impl<W: Write> TransactionWriter for CamtWriter<W> {
+    /// Добавляет транзакцию в текущую выписку.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`] при ошибке записи
+    /// и [`ParserError::Invalid`] при переполнении остатка выписки.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let same = self.statement.first()
+            .is_none_or(|first| first.account == tx.account && first.currency == tx.currency);
+        if !same {
+            if self.statements == 0 {
+                self.header().map_err(ParserError::Io)?;
+            }
+            self.flush_statement()?;
+        }
+
+        self.statement.push(tx.clone());
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        if self.statements == 0 {
+            self.header().map_err(ParserError::Io)?;
+        }
+        self.flush_statement()?;
+        self.inner.write_all(b"  </BkToCstmrStmt>\n</Document>\n").map_err(ParserError::Io)?;
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
+
+/// Идентификатор счёта: IBAN, если значение на него похоже, иначе `Othr`.
+fn account_id(account: &str) -> String {
+    let bytes = account.as_bytes();
+    let iban = (15..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+
+    if iban {
+        format!("<IBAN>{}</IBAN>", account)
+    } else {
+        format!("<Othr><Id>{}</Id></Othr>", escape(account))
+    }
+}
+
+fn balance(code: &str, amount: Amount, currency: Currency, date: &str) -> String {
+    format!(
+        "      <Bal>\n        \
+         <Tp><CdOrPrtry><Cd>{}</Cd></CdOrPrtry></Tp>\n        \
+         <Amt Ccy=\"{}\">{}</Amt>\n        \
+         <CdtDbtInd>{}</CdtDbtInd>\n        \
+         <Dt><Dt>{}</Dt></Dt>\n      \
+         </Bal>\n",
+        code, currency, abs(amount), indicator(amount, None), date
+    )
+}
+
+fn entry(tx: &Transaction, amount: Amount) -> String {
+    let indicator = indicator(amount, tx.direction);
+    let status = match tx.status.unwrap_or(Status::Booked) {
+        Status::Booked => "BOOK",
+        Status::Pending => "PDNG",
+        Status::Info => "INFO",
+    };
+
+    let mut out = String::from("      <Ntry>\n");
+    out.push_str(&format!("        <NtryRef>{}</NtryRef>\n", tx.id));
+    out.push_str(&format!("        <Amt Ccy=\"{}\">{}</Amt>\n", tx.currency, abs(amount)));
+    out.push_str(&format!("        <CdtDbtInd>{}</CdtDbtInd>\n", indicator));
+    out.push_str(&format!("        <Sts>{}</Sts>\n", status));
+    if let Some(date) = tx.booking_date {
+        out.push_str(&format!("        <BookgDt><Dt>{}</Dt></BookgDt>\n", date));
+    }
+    if let Some(date) = tx.value_date {
+        out.push_str(&format!("        <ValDt><Dt>{}</Dt></ValDt>\n", date));
+    }
+    out.push_str("        <BkTxCd/>\n");
+
+    if tx.counterparty.is_some() || tx.description.is_some() {
+        out.push_str("        <NtryDtls>\n          <TxDtls>\n");
+        if let Some(counterparty) = &tx.counterparty {
+            // Для списания контрагент — получатель, для зачисления — плательщик.
+            let party = if indicator == "DBIT" { "CdtrAcct" } else { "DbtrAcct" };
+            out.push_str(&format!(
+                "            <RltdPties><{party}><Id>{}</Id></{party}></RltdPties>\n",
+                account_id(counterparty)
+            ));
+        }
+        if let Some(description) = &tx.description {
+            out.push_str(&format!("            <RmtInf><Ustrd>{}</Ustrd></RmtInf>\n", escape(description.as_str())));
+        }
+        out.push_str("          </TxDtls>\n        </NtryDtls>\n");
+    }
+
+    out.push_str("      </Ntry>\n");
+    out
+}
+
+/// Индикатор `CRDT`/`DBIT`: по знаку суммы, для нуля — по направлению.
+fn indicator(amount: Amount, direction: Option<Direction>) -> &'static str {
+    match (amount.minor_units().signum(), direction) {
+        (-1, _) | (0, Some(Direction::Debit)) => "DBIT",
+        _ => "CRDT",
+    }
+}
+
+fn abs(amount: Amount) -> Amount {
+    Amount::from_minor(amount.minor_units().saturating_abs(), amount.exponent())
+}
+
+/// Разбирает дату camt (`Dt` или начало `DtTm`).
+fn date(s: &str) -> Result<NaiveDate, ParserError> {
+    parse_date(s.get(..10).unwrap_or(s))
+}
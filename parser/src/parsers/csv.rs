@@ -1,21 +1,30 @@
 //! CSV-парсер банковских транзакций.
 //!
-//! Формат CSV ожидается в следующем виде (без заголовков):
+//! Формат CSV ожидается в следующем виде:
 //!
 //! ```text
-//! id,account,amount,currency
+//! id,account,amount,currency[,booking_date,value_date,description,counterparty,direction,status]
 //! ```
 //!
 //! Где:
 //! - `id` — целое беззнаковое число (`u64`)
 //! - `account` — строка
-//! - `amount` — целое число (`i64`)
-//! - `currency` — строка (например, `USD`)
+//! - `amount` — десятичная сумма (`12.34`) с точностью валюты
+//! - `currency` — код валюты ISO 4217 (например, `USD`)
+//!
+//! Первая строка может быть заголовком (см. [`HeaderMode`]). Если заголовок
+//! есть, колонки сопоставляются с полями транзакции по именам, поэтому
+//! порядок колонок может быть любым, лишние колонки игнорируются, а вместо
+//! стандартных имён допускаются распространённые синонимы (`acct`, `amt`,
+//! `ccy` и т. п., см. [`Column`]) и собственные синонимы из [`CsvOptions::alias`].
+//! Без заголовка колонки читаются по порядку, как показано выше.
 
-use std::io::{Read, Write};
-use crate::errors::ParserError;
-use crate::{Format};
-use crate::models::Transaction;
+use std::io::{ErrorKind, Read, Write};
+use encoding_rs::{Encoder, EncoderResult, Encoding};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+use crate::errors::{ParserError, Position, RecordError};
+use crate::{Amount, Currency, Format, TransactionWriter};
+use crate::models::{Transaction, parse_date};
 
 
 /// CSV-парсер транзакций.
@@ -24,47 +33,646 @@ use crate::models::Transaction;
 /// - читать транзакции из CSV-потока;
 /// - записывать транзакции в CSV-поток.
 ///
-/// Используется для человеко-читаемого хранения и обмена данными.
+/// Использует настройки [`CsvOptions`] по умолчанию; для других настроек
+/// используйте [`CsvOptions::reader`] и [`CsvOptions::writer`].
 pub struct Csv;
 
 impl Format for Csv {
-    /// Считывает транзакции из CSV-потока.
+    type Reader<R: Read> = CsvReader<R>;
+    type Writer<W: Write> = CsvWriter<W>;
+
+    /// Создаёт потоковый итератор по строкам CSV.
     ///
-    /// Каждая строка CSV должна содержать ровно 4 поля:
-    /// `id, account, amount, currency`.
+    /// Наличие заголовка определяется автоматически ([`HeaderMode::Auto`]).
     ///
     /// # Ошибки
     ///
-    /// Возвращает:
-    /// - [`ParserError::Csv`] — если CSV повреждён или имеет неверный формат;
-    /// - [`ParserError::InvalidCsv`] — если запись не соответствует модели [`Transaction`].
-    fn read<R: Read>(reader: R) -> Result<Vec<Transaction>, ParserError> {
-        let mut rdr  = csv::Reader::from_reader(reader);
-        let mut transactions = Vec::new();
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Invalid`] — если в заголовке нет обязательных колонок;
+    /// - [`ParserError::Record`] — если CSV повреждён или запись не соответствует
+    ///   модели [`Transaction`]; ошибка содержит номер записи, строки и поля.
+    fn reader<R: Read>(reader: R) -> CsvReader<R> {
+        CsvOptions::default().reader(reader)
+    }
+
+    /// Создаёт писателя CSV.
+    ///
+    /// Первой строкой пишется заголовок, затем каждая транзакция
+    /// сериализуется в отдельную строку.
+    fn writer<W: Write>(writer: W) -> CsvWriter<W> {
+        CsvOptions::default().writer(writer)
+    }
+}
+
+/// Колонка CSV, соответствующая полю транзакции.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// `id`, синонимы: `tx_id`, `transaction_id`.
+    Id,
+    /// `account`, синонимы: `acct`, `account_id`, `account_number`, `iban`.
+    Account,
+    /// `amount`, синонимы: `amt`, `sum`.
+    Amount,
+    /// `currency`, синонимы: `ccy`, `cur`, `curr`.
+    Currency,
+    /// `booking_date`, синонимы: `date`, `book_date`, `posting_date`.
+    BookingDate,
+    /// `value_date`, синоним: `valuta`.
+    ValueDate,
+    /// `description`, синонимы: `desc`, `details`, `memo`, `narrative`.
+    Description,
+    /// `counterparty`, синонимы: `payee`, `beneficiary`.
+    Counterparty,
+    /// `direction`, синонимы: `dir`, `credit_debit`.
+    Direction,
+    /// `status`, синоним: `state`.
+    Status,
+}
+
+impl Column {
+    /// Все колонки в порядке записи.
+    pub const ALL: [Column; 10] = [
+        Column::Id,
+        Column::Account,
+        Column::Amount,
+        Column::Currency,
+        Column::BookingDate,
+        Column::ValueDate,
+        Column::Description,
+        Column::Counterparty,
+        Column::Direction,
+        Column::Status,
+    ];
+
+    /// Стандартное имя колонки.
+    pub fn name(&self) -> &'static str {
+        self.aliases()[0]
+    }
+
+    /// Обязательна ли колонка.
+    pub fn is_required(&self) -> bool {
+        matches!(self, Column::Id | Column::Account | Column::Amount | Column::Currency)
+    }
+
+    /// Находит колонку по стандартному имени или встроенному синониму.
+    pub(crate) fn from_name(name: &str) -> Option<Column> {
+        let name = normalize(name);
+        Column::ALL.into_iter().find(|column| column.aliases().contains(&name.as_str()))
+    }
+
+    /// Стандартное имя и встроенные синонимы.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Column::Id => &["id", "tx_id", "transaction_id"],
+            Column::Account => &["account", "acct", "account_id", "account_number", "iban"],
+            Column::Amount => &["amount", "amt", "sum"],
+            Column::Currency => &["currency", "ccy", "cur", "curr"],
+            Column::BookingDate => &["booking_date", "date", "book_date", "posting_date"],
+            Column::ValueDate => &["value_date", "valuta"],
+            Column::Description => &["description", "desc", "details", "memo", "narrative"],
+            Column::Counterparty => &["counterparty", "payee", "beneficiary"],
+            Column::Direction => &["direction", "dir", "credit_debit"],
+            Column::Status => &["status", "state"],
+        }
+    }
+}
+
+/// Режим обработки первой строки CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderMode {
+    /// Первая строка обязательно является заголовком.
+    Required,
+    /// Заголовка нет, колонки читаются по порядку.
+    Absent,
+    /// Первая строка считается заголовком, если хотя бы одна её колонка
+    /// совпадает с известным именем.
+    #[default]
+    Auto,
+}
+
+/// Настройки чтения и записи CSV: заголовок, синонимы колонок и диалект.
+///
+/// По умолчанию используется стандартный диалект: разделитель `,`,
+/// кавычки `"` (удваиваются внутри значения), десятичная точка и UTF-8.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    header: HeaderMode,
+    aliases: Vec<(String, Column)>,
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    trim: bool,
+    comment: Option<u8>,
+    decimal_separator: char,
+    encoding: Option<&'static Encoding>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            header: HeaderMode::default(),
+            aliases: Vec::new(),
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            trim: false,
+            comment: None,
+            decimal_separator: '.',
+            encoding: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Создаёт настройки по умолчанию.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Задаёт режим заголовка.
+    ///
+    /// При записи заголовок пишется во всех режимах, кроме [`HeaderMode::Absent`].
+    pub fn header(mut self, mode: HeaderMode) -> Self {
+        self.header = mode;
+        self
+    }
+
+    /// Добавляет синоним имени колонки, например `Buchungstag` для
+    /// [`Column::BookingDate`].
+    ///
+    /// Имена сравниваются без учёта регистра, пробелы и дефисы
+    /// приравниваются к `_`.
+    pub fn alias(mut self, name: impl Into<String>, column: Column) -> Self {
+        self.aliases.push((normalize(&name.into()), column));
+        self
+    }
+
+    /// Задаёт разделитель полей (например, `b';'`).
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Задаёт символ кавычек.
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Задаёт символ экранирования кавычек внутри значения (например, `b'\\'`)
+    /// вместо их удвоения.
+    pub fn escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    /// Включает удаление пробелов по краям полей при чтении.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Задаёт символ, с которого начинаются строки-комментарии;
+    /// такие строки пропускаются при чтении.
+    pub fn comment(mut self, comment: u8) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    /// Задаёт десятичный разделитель сумм: `.` или `,`.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`] для других символов.
+    pub fn decimal_separator(mut self, separator: char) -> Result<Self, ParserError> {
+        if separator != '.' && separator != ',' {
+            return Err(ParserError::Invalid(format!("Unsupported decimal separator: {:?}", separator)));
+        }
+
+        self.decimal_separator = separator;
+        Ok(self)
+    }
+
+    /// Задаёт кодировку по метке WHATWG (`windows-1251`, `latin1`, `utf-8`, ...).
+    ///
+    /// При чтении данные перекодируются в UTF-8, при записи — из UTF-8.
+    /// Без явной кодировки ожидается UTF-8; метка порядка байт (BOM)
+    /// учитывается в любом случае.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если кодировка неизвестна.
+    pub fn encoding(mut self, label: &str) -> Result<Self, ParserError> {
+        let encoding = Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| ParserError::Invalid(format!("Unknown encoding: {}", label)))?;
+
+        self.encoding = Some(encoding).filter(|encoding| *encoding != encoding_rs::UTF_8);
+        Ok(self)
+    }
+
+    /// Создаёт потоковый итератор по строкам CSV с этими настройками.
+    pub fn reader<R: Read>(&self, reader: R) -> CsvReader<R> {
+        let decoded = DecodeReaderBytesBuilder::new()
+            .encoding(self.encoding)
+            .build(reader);
+
+        CsvReader {
+            records: csv::ReaderBuilder::new()
+                .has_headers(false)
+                .delimiter(self.delimiter)
+                .quote(self.quote)
+                .escape(self.escape)
+                .double_quote(self.escape.is_none())
+                .trim(if self.trim { csv::Trim::All } else { csv::Trim::None })
+                .comment(self.comment)
+                .from_reader(decoded)
+                .into_records(),
+            options: self.clone(),
+            columns: None,
+            index: 0,
+        }
+    }
+
+    /// Создаёт писателя CSV с этими настройками.
+    pub fn writer<W: Write>(&self, writer: W) -> CsvWriter<W> {
+        let encoded = Encoded {
+            inner: writer,
+            encoder: self.encoding.map(Encoding::new_encoder),
+            pending: Vec::new(),
+        };
+
+        CsvWriter {
+            inner: self.writer_builder().from_writer(encoded),
+            header: self.header != HeaderMode::Absent,
+            decimal_separator: self.decimal_separator,
+        }
+    }
+
+    /// Настройки писателя в диалекте этих настроек.
+    fn writer_builder(&self) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .double_quote(self.escape.is_none());
+        if let Some(escape) = self.escape {
+            builder.escape(escape);
+        }
+
+        builder
+    }
+
+    /// Исходный текст записи для сообщений об ошибках: поля снова
+    /// соединяются разделителем диалекта и при необходимости берутся в кавычки.
+    fn raw(&self, record: &csv::StringRecord) -> String {
+        let mut writer = self.writer_builder().terminator(csv::Terminator::Any(b'\n')).from_writer(Vec::new());
+        let raw = writer.write_record(record).ok().and_then(|_| writer.into_inner().ok()).unwrap_or_default();
+
+        String::from_utf8_lossy(&raw).trim_end_matches('\n').to_string()
+    }
+
+    /// Ищет колонку по имени из заголовка.
+    fn resolve(&self, name: &str) -> Option<Column> {
+        let name = normalize(name);
+
+        self.aliases
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, column)| *column)
+            .or_else(|| Column::from_name(&name))
+    }
+
+    /// Сопоставляет колонки заголовка с полями транзакции.
+    fn columns(&self, header: &csv::StringRecord) -> Result<ColumnMap, ParserError> {
+        let mut columns = ColumnMap { indices: [None; Column::ALL.len()] };
+
+        for (index, name) in header.iter().enumerate() {
+            let Some(column) = self.resolve(name) else { continue };
+            let slot = &mut columns.indices[column as usize];
+            if slot.is_some() {
+                return Err(ParserError::Invalid(format!(
+                    "Invalid CSV header: duplicate column for {}: {}", column.name(), name
+                )));
+            }
+            *slot = Some(index);
+        }
+
+        let missing: Vec<_> = Column::ALL
+            .iter()
+            .filter(|column| column.is_required() && columns.get(**column).is_none())
+            .map(Column::name)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ParserError::Invalid(format!(
+                "Invalid CSV header: missing columns: {}", missing.join(", ")
+            )));
+        }
+
+        Ok(columns)
+    }
+}
+
+/// Приводит имя колонки к виду для сравнения.
+fn normalize(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c == ' ' || c == '-' { '_' } else { c.to_ascii_lowercase() })
+        .collect()
+}
+
+/// Номера колонок CSV для каждого поля транзакции.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnMap {
+    indices: [Option<usize>; Column::ALL.len()],
+}
+
+impl ColumnMap {
+    /// Колонки по порядку, без заголовка.
+    pub(crate) fn positional() -> Self {
+        Self { indices: std::array::from_fn(Some) }
+    }
+
+    fn get(&self, column: Column) -> Option<usize> {
+        self.indices[column as usize]
+    }
+}
+
+/// Потоковый итератор транзакций из CSV-потока.
+pub struct CsvReader<R: Read> {
+    records: csv::StringRecordsIntoIter<DecodeReaderBytes<R, Vec<u8>>>,
+    options: CsvOptions,
+    columns: Option<ColumnMap>,
+    index: u64,
+}
+
+impl<R: Read> CsvReader<R> {
+    /// Разбирает первую строку; `true` означает, что она была заголовком.
+    ///
+    /// Сопоставление колонок запоминается, только если заголовок корректен.
+    fn read_header(&mut self, first: &csv::StringRecord) -> Result<bool, ParserError> {
+        let is_header = match self.options.header {
+            HeaderMode::Required => true,
+            HeaderMode::Absent => false,
+            HeaderMode::Auto => first.iter().any(|name| self.options.resolve(name).is_some()),
+        };
 
-        for record in rdr.records() {
-            let record = record.map_err(|err| ParserError::Csv(err))?;
-            let tx = Transaction::from_csv_record(&record)?;
-            transactions.push(tx);
+        let columns = if is_header { self.options.columns(first)? } else { ColumnMap::positional() };
+        self.columns = Some(columns);
+
+        Ok(is_header)
+    }
+}
+
+impl<R: Read> Iterator for CsvReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.records.next()? {
+                Ok(record) => record,
+                Err(err) => {
+                    self.index += 1;
+                    return Some(Err(record_error(err, self.index)));
+                }
+            };
+
+            let columns = match &self.columns {
+                Some(columns) => columns,
+                None => match self.read_header(&record) {
+                    Ok(true) => continue,
+                    Ok(false) => self.columns.as_ref()?,
+                    Err(err) => return Some(Err(err)),
+                },
+            };
+
+            self.index += 1;
+            return Some(parse_record(&record, columns, &self.options, Some(self.index)));
         }
+    }
+}
+
+/// Разбирает запись CSV по сопоставлению колонок.
+///
+/// Десятичный разделитель из `options` заменяется точкой перед разбором суммы,
+/// а исходный текст записи в ошибке восстанавливается в диалекте `options`.
+pub(crate) fn parse_record(
+    record: &csv::StringRecord,
+    columns: &ColumnMap,
+    options: &CsvOptions,
+    index: Option<u64>,
+) -> Result<Transaction, ParserError> {
+    let decimal_separator = options.decimal_separator;
+    let fields = CsvFields { record, columns, options, index };
 
-        Ok(transactions)
+    let currency: Currency = fields.parse(Column::Currency, str::parse)?;
+    let mut tx = Transaction::new(
+        fields.parse(Column::Id, |s| s.parse().map_err(|err| ParserError::Invalid(format!("Invalid id: {}", err))))?,
+        fields.required(Column::Account)?,
+        fields.parse(Column::Amount, |s| parse_amount(s, decimal_separator, currency.exponent()))?,
+        currency,
+    );
+
+    tx.booking_date = fields.optional(Column::BookingDate, parse_date)?;
+    tx.value_date = fields.optional(Column::ValueDate, parse_date)?;
+    tx.description = fields.optional(Column::Description, |s| Ok(s.to_string()))?;
+    tx.counterparty = fields.optional(Column::Counterparty, |s| Ok(s.to_string()))?;
+    tx.direction = fields.optional(Column::Direction, str::parse)?;
+    tx.status = fields.optional(Column::Status, str::parse)?;
+
+    Ok(tx)
+}
+
+fn parse_amount(s: &str, decimal_separator: char, exponent: u8) -> Result<Amount, ParserError> {
+    if decimal_separator == '.' {
+        return Amount::parse(s, exponent);
     }
+    if s.contains('.') {
+        return Err(ParserError::Invalid(format!("Invalid amount: {}", s)));
+    }
+
+    Amount::parse(&s.replace(decimal_separator, "."), exponent)
+}
 
-    /// Записывает транзакции в CSV-поток.
+/// Поля CSV-записи с привязкой ошибок к месту в исходном файле.
+struct CsvFields<'a> {
+    record: &'a csv::StringRecord,
+    columns: &'a ColumnMap,
+    options: &'a CsvOptions,
+    index: Option<u64>,
+}
+
+impl CsvFields<'_> {
+    fn get(&self, column: Column) -> Option<(usize, &str)> {
+        let index = self.columns.get(column)?;
+        self.record.get(index).map(|value| (index, value))
+    }
+
+    fn required(&self, column: Column) -> Result<&str, ParserError> {
+        self.get(column)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                let index = self.columns.get(column).unwrap_or(self.record.len());
+                self.error(index, column, None, "missing field".to_string())
+            })
+    }
+
+    fn parse<T>(&self, column: Column, parse: impl FnOnce(&str) -> Result<T, ParserError>) -> Result<T, ParserError> {
+        let value = self.required(column)?;
+        let index = self.columns.get(column).unwrap_or_default();
+        parse(value).map_err(|err| self.error(index, column, Some(value), err.into_reason()))
+    }
+
+    /// Отсутствующее или пустое поле даёт `None`.
+    fn optional<T>(&self, column: Column, parse: impl FnOnce(&str) -> Result<T, ParserError>) -> Result<Option<T>, ParserError> {
+        match self.get(column).filter(|(_, value)| !value.is_empty()) {
+            Some(_) => self.parse(column, parse).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn error(&self, index: usize, column: Column, value: Option<&str>, reason: String) -> ParserError {
+        let mut position = Position {
+            record: self.index,
+            column: Some(index as u64 + 1),
+            ..Position::default()
+        };
+        if let Some(pos) = self.record.position() {
+            position.line = Some(pos.line());
+            position.byte = Some(pos.byte());
+        }
+
+        let mut err = RecordError::new("csv", reason).at(position).field(column.name()).raw(self.options.raw(self.record));
+        if let Some(value) = value {
+            err = err.value(value);
+        }
+
+        err.into()
+    }
+}
+
+/// Привязывает ошибку чтения CSV к месту в файле.
+fn record_error(err: csv::Error, index: u64) -> ParserError {
+    let reason = match err.kind() {
+        csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+            format!("expected {} fields, found {}", expected_len, len)
+        }
+        csv::ErrorKind::Utf8 { err, .. } => {
+            format!("invalid UTF-8 in field {}", err.field() + 1)
+        }
+        csv::ErrorKind::Io(_) => match err.into_kind() {
+            csv::ErrorKind::Io(err) => return ParserError::Io(err),
+            _ => unreachable!(),
+        },
+        _ => return ParserError::Csv(err),
+    };
+
+    let mut position = Position { record: Some(index), ..Position::default() };
+    if let Some(pos) = err.position() {
+        position.line = Some(pos.line());
+        position.byte = Some(pos.byte());
+    }
+
+    RecordError::new("csv", reason).at(position).into()
+}
+
+/// Писатель, перекодирующий UTF-8 в заданную кодировку.
+struct Encoded<W: Write> {
+    inner: W,
+    encoder: Option<Encoder>,
+    /// Начало многобайтового символа, оборванного на границе записи.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Write for Encoded<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let Some(encoder) = &mut self.encoder else {
+            return self.inner.write(buf);
+        };
+
+        self.pending.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(std::io::Error::new(ErrorKind::InvalidData, err)),
+        };
+        let text = std::str::from_utf8(&self.pending[..valid]).expect("prefix is valid UTF-8");
+
+        let mut out = Vec::with_capacity(text.len());
+        let mut encoded = [0u8; 1024];
+        let mut rest = text;
+        loop {
+            let (result, read, written) = encoder.encode_from_utf8_without_replacement(rest, &mut encoded, false);
+            out.extend_from_slice(&encoded[..written]);
+            rest = &rest[read..];
+
+            match result {
+                EncoderResult::InputEmpty => break,
+                EncoderResult::OutputFull => continue,
+                EncoderResult::Unmappable(c) => {
+                    return Err(std::io::Error::new(ErrorKind::InvalidData, format!(
+                        "Character {:?} cannot be encoded in {}", c, encoder.encoding().name()
+                    )));
+                }
+            }
+        }
+
+        self.inner.write_all(&out)?;
+        self.pending.drain(..valid);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Инкрементальный писатель транзакций в CSV-поток.
+pub struct CsvWriter<W: Write> {
+    inner: csv::Writer<Encoded<W>>,
+    header: bool,
+    decimal_separator: char,
+}
+
+impl<W: Write> TransactionWriter for CsvWriter<W> {
+    /// Записывает транзакцию отдельной строкой CSV.
     ///
-    /// Каждая транзакция сериализуется в отдельную строку
-    /// без заголовков.
+    /// Перед первой транзакцией пишется заголовок, если он не отключён.
     ///
     /// # Ошибки
     ///
-    /// Возвращает [`ParserError::Csv`], если произошла ошибка записи.
-    fn write<W: Write>(writer: W, transaction: &[Transaction]) -> Result<(), ParserError> {
-        let mut writer = csv::Writer::from_writer(writer);
-        for tx in transaction {
-            writer.serialize(tx).map_err(|err| ParserError::Csv(err))?;
+    /// Возвращает [`ParserError::Csv`], если произошла ошибка записи
+    /// или символ нельзя представить в выбранной кодировке.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        if self.header {
+            self.inner.write_record(Column::ALL.map(|column| column.name())).map_err(ParserError::Csv)?;
+            self.header = false;
+        }
+
+        let mut amount = tx.money()?.amount().to_string();
+        if self.decimal_separator != '.' {
+            amount = amount.replace('.', &self.decimal_separator.to_string());
         }
 
-        Ok(())
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let record = [
+            tx.id.to_string(),
+            tx.account.clone(),
+            amount,
+            tx.currency.to_string(),
+            optional(tx.booking_date.map(|date| date.to_string())),
+            optional(tx.value_date.map(|date| date.to_string())),
+            optional(tx.description.clone()),
+            optional(tx.counterparty.clone()),
+            optional(tx.direction.map(|direction| direction.to_string())),
+            optional(tx.status.map(|status| status.to_string())),
+        ];
+
+        self.inner.write_record(&record).map_err(ParserError::Csv)
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.inner.flush().map_err(ParserError::Io)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,665 @@
+//! Формат записей фиксированной ширины (выгрузки мейнфреймов).
+//!
+//! Каждая строка — одна транзакция; поля занимают заранее известные
+//! позиции. Расположение полей задаётся описанием [`Layout`], которое
+//! обычно загружается из JSON-файла:
+//!
+//! ```text
+//! {
+//!   "fields": [
+//!     { "name": "id",           "offset": 0,  "width": 10, "type": "integer" },
+//!     { "name": "account",      "offset": 10, "width": 20, "type": "text" },
+//!     { "name": "amount",       "offset": 30, "width": 12, "type": "decimal", "decimals": 2 },
+//!     { "name": "currency",     "offset": 42, "width": 3,  "type": "text" },
+//!     { "name": "booking_date", "offset": 45, "width": 8,  "type": "date", "format": "YYYYMMDD" }
+//!   ]
+//! }
+//! ```
+//!
+//! Для каждого поля задаются:
+//! - `name` — имя поля транзакции (допускаются синонимы [`Column`]);
+//! - `offset` и `width` — позиция в строке и ширина, в символах, начиная с 0;
+//! - `type` — `text`, `integer` (`id`), `decimal` (`amount`) или `date`;
+//! - `padding` — символ заполнения: `space` или `zero`;
+//! - `align` — выравнивание значения: `left` или `right`;
+//! - `decimals` — число подразумеваемых знаков после запятой для `decimal`:
+//!   `000000001999` при `"decimals": 2` означает `19.99`. Без `decimals`
+//!   сумма записывается с десятичной точкой;
+//! - `format` — шаблон даты из `YYYY`, `YY`, `MM`, `DD` и разделителей,
+//!   по умолчанию `YYYYMMDD`.
+//!
+//! По умолчанию числа выравниваются вправо и дополняются нулями, текст
+//! и даты — влево пробелами. Знак минуса пишется перед числом. Пустое
+//! (из символов заполнения) значение необязательного поля, а также дата
+//! из одних нулей означают отсутствие значения. Промежутки между полями
+//! при записи заполняются пробелами, при чтении игнорируются.
+//!
+//! Без описания полей формат не работает, поэтому в [`crate::Registry::default`]
+//! его нет: [`FixedFormat`] связывает описание с форматом, и такой формат
+//! регистрируется через [`crate::Registry::register`].
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer};
+use crate::csv::Column;
+use crate::errors::{ParserError, Position, RecordError};
+use crate::models::Transaction;
+use crate::{Amount, Currency, DynFormat, TransactionStream, TransactionWriter};
+
+/// Тип значения поля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    /// Строка.
+    Text,
+    /// Целое беззнаковое число.
+    Integer,
+    /// Десятичная сумма.
+    Decimal,
+    /// Дата по шаблону [`Field::format`].
+    Date,
+}
+
+/// Символ заполнения поля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Padding {
+    /// Пробел.
+    Space,
+    /// Символ `0`.
+    Zero,
+}
+
+impl FieldType {
+    /// Имя типа в описании (`text`, `integer`, `decimal`, `date`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::Text => "text",
+            FieldType::Integer => "integer",
+            FieldType::Decimal => "decimal",
+            FieldType::Date => "date",
+        }
+    }
+}
+
+impl Padding {
+    fn char(&self) -> char {
+        match self {
+            Padding::Space => ' ',
+            Padding::Zero => '0',
+        }
+    }
+}
+
+/// Выравнивание значения внутри поля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Align {
+    /// По левому краю, заполнение справа.
+    Left,
+    /// По правому краю, заполнение слева.
+    Right,
+}
+
+/// Описание одного поля записи.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Field {
+    /// Поле транзакции.
+    #[serde(rename = "name", deserialize_with = "column")]
+    pub column: Column,
+    /// Позиция первого символа поля в строке, начиная с 0.
+    pub offset: usize,
+    /// Ширина поля в символах.
+    pub width: usize,
+    /// Тип значения.
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    /// Символ заполнения; по умолчанию зависит от типа.
+    #[serde(default)]
+    pub padding: Option<Padding>,
+    /// Выравнивание; по умолчанию зависит от типа.
+    #[serde(default)]
+    pub align: Option<Align>,
+    /// Число подразумеваемых знаков после запятой (только для `decimal`).
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    /// Шаблон даты (только для `date`), по умолчанию `YYYYMMDD`.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl Field {
+    /// Создаёт поле с заполнением и выравниванием по умолчанию.
+    pub fn new(column: Column, offset: usize, width: usize, kind: FieldType) -> Self {
+        Self { column, offset, width, kind, padding: None, align: None, decimals: None, format: None }
+    }
+
+    fn padding(&self) -> Padding {
+        self.padding.unwrap_or(match self.kind {
+            FieldType::Integer | FieldType::Decimal => Padding::Zero,
+            FieldType::Text | FieldType::Date => Padding::Space,
+        })
+    }
+
+    fn align(&self) -> Align {
+        self.align.unwrap_or(match self.kind {
+            FieldType::Integer | FieldType::Decimal => Align::Right,
+            FieldType::Text | FieldType::Date => Align::Left,
+        })
+    }
+
+    fn format(&self) -> &str {
+        self.format.as_deref().unwrap_or("YYYYMMDD")
+    }
+
+    /// Тип, которым может быть представлено поле транзакции.
+    fn expected_kind(column: Column) -> FieldType {
+        match column {
+            Column::Id => FieldType::Integer,
+            Column::Amount => FieldType::Decimal,
+            Column::BookingDate | Column::ValueDate => FieldType::Date,
+            _ => FieldType::Text,
+        }
+    }
+}
+
+fn column<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Column, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Column::from_name(&name).ok_or_else(|| serde::de::Error::custom(format!("unknown field name: {}", name)))
+}
+
+/// Описание расположения полей в записи фиксированной ширины.
+///
+/// Создаётся из списка полей ([`Layout::new`]) или загружается из JSON
+/// ([`Layout::from_json`], [`Layout::load`]); служит фабрикой читателей
+/// и писателей, как [`CsvOptions`](crate::csv::CsvOptions) для CSV.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    fields: Vec<Field>,
+    width: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayoutFile {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    /// Создаёт описание из списка полей.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если нет обязательного поля,
+    /// поле повторяется, имеет нулевую ширину, пересекается с другим полем
+    /// или его тип не подходит для поля транзакции.
+    pub fn new(mut fields: Vec<Field>) -> Result<Self, ParserError> {
+        let invalid = |reason: String| ParserError::Invalid(format!("Invalid layout: {}", reason));
+
+        fields.sort_by_key(|field| field.offset);
+
+        for (index, field) in fields.iter().enumerate() {
+            let name = field.column.name();
+            if field.width == 0 {
+                return Err(invalid(format!("field {} has zero width", name)));
+            }
+            if fields[..index].iter().any(|other| other.column == field.column) {
+                return Err(invalid(format!("field {} is defined more than once", name)));
+            }
+            let expected = Field::expected_kind(field.column);
+            if field.kind != expected {
+                return Err(invalid(format!("field {} must have type {}", name, expected.name())));
+            }
+            if field.decimals.is_some() && field.kind != FieldType::Decimal {
+                return Err(invalid(format!("decimals are only allowed for decimal fields, not {}", name)));
+            }
+            if field.format.is_some() && field.kind != FieldType::Date {
+                return Err(invalid(format!("format is only allowed for date fields, not {}", name)));
+            }
+            if field.kind == FieldType::Date {
+                let pattern = field.format();
+                if date_pattern_width(pattern) > field.width || !pattern.contains("YY") || !pattern.contains("MM") || !pattern.contains("DD") {
+                    return Err(invalid(format!("invalid date format for field {}: {}", name, pattern)));
+                }
+            }
+        }
+
+        if let Some(pair) = fields.windows(2).find(|pair| pair[0].offset + pair[0].width > pair[1].offset) {
+            return Err(invalid(format!("field {} overlaps field {}", pair[1].column.name(), pair[0].column.name())));
+        }
+
+        if let Some(column) = Column::ALL
+            .into_iter()
+            .find(|column| column.is_required() && !fields.iter().any(|field| field.column == *column))
+        {
+            return Err(invalid(format!("missing required field {}", column.name())));
+        }
+
+        let width = fields.iter().map(|field| field.offset + field.width).max().unwrap_or(0);
+        Ok(Self { fields, width })
+    }
+
+    /// Читает описание в формате JSON.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если JSON повреждён
+    /// или описание некорректно (см. [`Layout::new`]).
+    pub fn from_json<R: Read>(reader: R) -> Result<Self, ParserError> {
+        let file: LayoutFile = serde_json::from_reader(reader)
+            .map_err(|err| ParserError::Invalid(format!("Invalid layout: {}", err)))?;
+
+        Self::new(file.fields)
+    }
+
+    /// Загружает описание в формате JSON из файла.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если файл не удалось открыть,
+    /// и ошибки [`Layout::from_json`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        Self::from_json(BufReader::new(File::open(path).map_err(ParserError::Io)?))
+    }
+
+    /// Поля в порядке следования в записи.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Ширина записи в символах.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Создаёт потоковый итератор по записям.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если значение поля не удалось разобрать;
+    ///   ошибка содержит номер строки, колонку начала поля и саму строку.
+    pub fn reader<R: Read>(&self, reader: R) -> FixedReader<R> {
+        FixedReader {
+            inner: BufReader::new(reader),
+            layout: self.clone(),
+            line: String::new(),
+            line_no: 0,
+            records: 0,
+            offset: 0,
+        }
+    }
+
+    /// Создаёт писателя, сериализующего каждую транзакцию в строку
+    /// шириной [`Layout::width`].
+    pub fn writer<W: Write>(&self, writer: W) -> FixedWriter<W> {
+        FixedWriter { inner: writer, layout: self.clone() }
+    }
+}
+
+/// Формат фиксированной ширины с конкретным описанием полей.
+///
+/// Не определяется ни по содержимому, ни по расширению: его выбирают
+/// только по имени [`FixedFormat::NAME`].
+pub struct FixedFormat {
+    layout: Layout,
+}
+
+impl FixedFormat {
+    /// Имя формата в реестре.
+    pub const NAME: &'static str = "fixed";
+
+    /// Создаёт формат с описанием полей `layout`.
+    pub fn new(layout: Layout) -> Self {
+        Self { layout }
+    }
+
+    /// Описание полей формата.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+}
+
+impl DynFormat for FixedFormat {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn mime_types(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionStream<'a> {
+        Box::new(self.layout.reader(reader))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(self.layout.writer(writer))
+    }
+}
+
+/// Потоковый итератор транзакций из записей фиксированной ширины.
+pub struct FixedReader<R: Read> {
+    inner: BufReader<R>,
+    layout: Layout,
+    line: String,
+    line_no: u64,
+    records: u64,
+    offset: u64,
+}
+
+impl<R: Read> Iterator for FixedReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            let len = match self.inner.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(len) => len,
+                Err(err) => return Some(Err(ParserError::Io(err))),
+            };
+
+            self.line_no += 1;
+            let byte = self.offset;
+            self.offset += len as u64;
+
+            let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            self.records += 1;
+            let position = Position {
+                record: Some(self.records),
+                line: Some(self.line_no),
+                column: None,
+                byte: Some(byte),
+            };
+
+            return Some(parse_line(&self.layout, line, position).map_err(ParserError::Record));
+        }
+    }
+}
+
+/// Значения полей записи после снятия заполнения.
+#[derive(Default)]
+struct Values<'a> {
+    values: [Option<(&'a Field, usize, &'a str)>; Column::ALL.len()],
+}
+
+impl<'a> Values<'a> {
+    fn get(&self, column: Column) -> Option<(&'a Field, usize, &'a str)> {
+        self.values[column as usize]
+    }
+}
+
+fn parse_line(layout: &Layout, line: &str, position: Position) -> Result<Transaction, Box<RecordError>> {
+    let error = |field: &Field, byte: usize, reason: String| {
+        Box::new(
+            RecordError::new("fixed", reason)
+                .at(Position {
+                    column: Some(field.offset as u64 + 1),
+                    byte: position.byte.map(|start| start + byte as u64),
+                    ..position
+                })
+                .field(field.column.name())
+                .raw(line),
+        )
+    };
+
+    let mut values = Values::default();
+    for field in &layout.fields {
+        let (byte, text) = slice(line, field.offset, field.width);
+        // Ведущие нули чисел снимаются при разборе, а поле из одних нулей
+        // остаётся нулём, а не пустым значением.
+        let text = match (field.kind, field.align()) {
+            (FieldType::Text, Align::Left) => text.trim_end_matches(field.padding().char()),
+            (FieldType::Text, Align::Right) => text.trim_start_matches(field.padding().char()),
+            _ => text,
+        };
+        let text = text.trim_matches(' ');
+        if !text.is_empty() {
+            values.values[field.column as usize] = Some((field, byte, text));
+        }
+    }
+
+    let required = |column: Column| {
+        values.get(column).ok_or_else(|| {
+            let field = layout.fields.iter().find(|field| field.column == column).expect("required field");
+            error(field, slice(line, field.offset, field.width).0, "missing field".to_string())
+        })
+    };
+    let invalid = |(field, byte, value): (&Field, usize, &str), err: ParserError| {
+        Box::new(error(field, byte, err.into_reason()).value(value))
+    };
+
+    let id = required(Column::Id)?;
+    let id_value = id.2.parse().map_err(|err| invalid(id, ParserError::Invalid(format!("Invalid id: {}", err))))?;
+
+    let account = required(Column::Account)?.2;
+
+    let currency = required(Column::Currency)?;
+    let currency_value: Currency = currency.2.parse().map_err(|err| invalid(currency, err))?;
+
+    let amount = required(Column::Amount)?;
+    let amount_value = parse_amount(amount.0, amount.2, currency_value.exponent()).map_err(|err| invalid(amount, err))?;
+
+    let mut tx = Transaction::new(id_value, account, amount_value, currency_value);
+
+    let date = |column: Column| -> Result<Option<NaiveDate>, Box<RecordError>> {
+        let Some(value) = values.get(column) else { return Ok(None) };
+        if value.2.bytes().all(|b| b == b'0') {
+            return Ok(None);
+        }
+        parse_date(value.0.format(), value.2)
+            .map(Some)
+            .ok_or_else(|| invalid(value, ParserError::Invalid(format!("Invalid date: {}", value.2))))
+    };
+    tx.booking_date = date(Column::BookingDate)?;
+    tx.value_date = date(Column::ValueDate)?;
+    tx.description = values.get(Column::Description).map(|(_, _, value)| value.to_string());
+    tx.counterparty = values.get(Column::Counterparty).map(|(_, _, value)| value.to_string());
+    if let Some(value) = values.get(Column::Direction) {
+        tx.direction = Some(value.2.parse().map_err(|err| invalid(value, err))?);
+    }
+    if let Some(value) = values.get(Column::Status) {
+        tx.status = Some(value.2.parse().map_err(|err| invalid(value, err))?);
+    }
+
+    Ok(tx)
+}
+
+/// Возвращает смещение в байтах и текст `width` символов строки, начиная
+/// с символа `offset`. Строка может быть короче описания записи.
+fn slice(line: &str, offset: usize, width: usize) -> (usize, &str) {
+    let byte = |index: usize| line.char_indices().nth(index).map_or(line.len(), |(byte, _)| byte);
+    let start = byte(offset);
+    let end = byte(offset + width);
+
+    (start, &line[start..end])
+}
+
+/// Разбирает сумму с десятичной точкой или подразумеваемыми знаками.
+fn parse_amount(field: &Field, value: &str, exponent: u8) -> Result<Amount, ParserError> {
+    let Some(decimals) = field.decimals else {
+        return Amount::parse(value, exponent);
+    };
+
+    let (sign, digits) = match value.strip_prefix(['-', '+']) {
+        Some(digits) => (&value[..1], digits),
+        None => ("", value),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParserError::Invalid(format!("Invalid amount: {}", value)));
+    }
+
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - decimals);
+
+    Amount::parse(&format!("{}{}.{}", sign, int_part, frac_part), exponent)
+        .map_err(|_| ParserError::Invalid(format!("Invalid amount: {}", value)))
+}
+
+/// Ширина даты, записанной по шаблону.
+fn date_pattern_width(pattern: &str) -> usize {
+    pattern.chars().count()
+}
+
+/// Разбирает дату по шаблону из `YYYY`, `YY`, `MM`, `DD` и разделителей.
+///
+/// Двузначный год относится к 2000-м годам.
+fn parse_date(pattern: &str, value: &str) -> Option<NaiveDate> {
+    let (mut year, mut month, mut day) = (None, None, None);
+    let mut rest = value;
+    let mut pattern = pattern;
+
+    while !pattern.is_empty() {
+        let (token, width) = ["YYYY", "YY", "MM", "DD"]
+            .into_iter()
+            .find(|token| pattern.starts_with(token))
+            .map_or(("", 1), |token| (token, token.len()));
+
+        if token.is_empty() {
+            let literal = pattern.chars().next()?;
+            rest = rest.strip_prefix(literal)?;
+            pattern = &pattern[literal.len_utf8()..];
+            continue;
+        }
+
+        let digits = rest.get(..width).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+        let number: u32 = digits.parse().ok()?;
+        match token {
+            "YYYY" => year = Some(number as i32),
+            "YY" => year = Some(2000 + number as i32),
+            "MM" => month = Some(number),
+            _ => day = Some(number),
+        }
+        rest = &rest[width..];
+        pattern = &pattern[width..];
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year?, month?, day?)
+}
+
+/// Записывает дату по шаблону из `YYYY`, `YY`, `MM`, `DD` и разделителей.
+fn format_date(pattern: &str, date: NaiveDate) -> String {
+    let mut out = String::new();
+    let mut pattern = pattern;
+
+    while let Some(c) = pattern.chars().next() {
+        let token = ["YYYY", "YY", "MM", "DD"].into_iter().find(|token| pattern.starts_with(token));
+        match token {
+            Some("YYYY") => out.push_str(&format!("{:04}", date.year())),
+            Some("YY") => out.push_str(&format!("{:02}", date.year().rem_euclid(100))),
+            Some("MM") => out.push_str(&format!("{:02}", date.month())),
+            Some(_) => out.push_str(&format!("{:02}", date.day())),
+            None => out.push(c),
+        }
+        pattern = &pattern[token.map_or(c.len_utf8(), str::len)..];
+    }
+
+    out
+}
+
+/// Инкрементальный писатель транзакций в записи фиксированной ширины.
+pub struct FixedWriter<W: Write> {
+    inner: W,
+    layout: Layout,
+}
+
+impl<W: Write> FixedWriter<W> {
+    /// Форматирует значение поля без заполнения.
+    fn value(field: &Field, tx: &Transaction) -> Result<Option<String>, ParserError> {
+        Ok(match field.column {
+            Column::Id => Some(tx.id.to_string()),
+            Column::Account => Some(tx.account.clone()),
+            Column::Amount => Some(match field.decimals {
+                Some(decimals) => {
+                    let amount = tx.amount.rescale(decimals).ok_or_else(|| ParserError::Invalid(format!(
+                        "Amount {} cannot be written with {} implied decimals", tx.amount, decimals
+                    )))?;
+                    amount.minor_units().to_string()
+                }
+                None => tx.money()?.amount().to_string(),
+            }),
+            Column::Currency => Some(tx.currency.to_string()),
+            Column::BookingDate => tx.booking_date.map(|date| format_date(field.format(), date)),
+            Column::ValueDate => tx.value_date.map(|date| format_date(field.format(), date)),
+            Column::Description => tx.description.clone(),
+            Column::Counterparty => tx.counterparty.clone(),
+            Column::Direction => tx.direction.map(|direction| direction.to_string()),
+            Column::Status => tx.status.map(|status| status.to_string()),
+        })
+    }
+}
+
+impl<W: Write> TransactionWriter for FixedWriter<W> {
+    /// Записывает транзакцию одной строкой шириной [`Layout::width`].
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает:
+    /// - [`ParserError::Invalid`] — если значение не помещается в поле,
+    ///   содержит перевод строки или сумму нельзя записать с заданным
+    ///   числом подразумеваемых знаков;
+    /// - [`ParserError::Io`] — при ошибке записи.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let mut line = String::with_capacity(self.layout.width);
+        let mut column = 0;
+
+        for field in &self.layout.fields {
+            line.extend(std::iter::repeat_n(' ', field.offset - column));
+            column = field.offset + field.width;
+
+            let Some(value) = Self::value(field, tx)? else {
+                line.extend(std::iter::repeat_n(' ', field.width));
+                continue;
+            };
+
+            let len = value.chars().count();
+            if len > field.width || value.contains(['\n', '\r']) {
+                return Err(ParserError::Invalid(format!(
+                    "Value '{}' does not fit field {} of width {}", value, field.column.name(), field.width
+                )));
+            }
+
+            let fill = std::iter::repeat_n(field.padding().char(), field.width - len);
+            match (field.align(), field.padding()) {
+                (Align::Left, _) => {
+                    line.push_str(&value);
+                    line.extend(fill);
+                }
+                // Знак ставится перед нулями: `-0001999`.
+                (Align::Right, Padding::Zero) if value.starts_with('-') => {
+                    line.push('-');
+                    line.extend(fill);
+                    line.push_str(&value[1..]);
+                }
+                (Align::Right, _) => {
+                    line.extend(fill);
+                    line.push_str(&value);
+                }
+            }
+        }
+
+        writeln!(self.inner, "{}", line).map_err(ParserError::Io)
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
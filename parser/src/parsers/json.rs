@@ -0,0 +1,406 @@
+//! JSON и NDJSON форматы транзакций.
+//!
+//! [`JsonParser`] читает и пишет JSON-массив объектов:
+//!
+//! ```text
+//! [
+//! {"id":1,"account":"ACC1","amount":"100.00","currency":"USD"},
+//! {"id":2,"account":"ACC2","amount":"-50.00","currency":"EUR","status":"booked"}
+//! ]
+//! ```
+//!
+//! [`NdjsonParser`] — по одному объекту на строку (newline-delimited JSON),
+//! пустые строки пропускаются.
+//!
+//! Имена полей совпадают с полями [`Transaction`]. Сумма пишется строкой,
+//! но читается и из числа: число разбирается по исходному тексту, без
+//! промежуточного `f64`, поэтому точность не теряется. Необязательные поля
+//! могут отсутствовать или быть `null`; неизвестные поля игнорируются.
+//! Оба формата читаются потоково, не загружая весь файл в память.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use crate::csv::Column;
+use crate::{Amount, Currency, Format, Position, RecordError, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, parse_date};
+
+/// Парсер JSON-массива транзакций.
+pub struct JsonParser;
+
+impl Format for JsonParser {
+    type Reader<R: Read> = JsonReader<R>;
+    type Writer<W: Write> = JsonWriter<W>;
+
+    /// Создаёт потоковый итератор по элементам JSON-массива.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если JSON повреждён или элемент
+    ///   не соответствует модели [`Transaction`]; ошибка содержит номер
+    ///   элемента, смещение его начала и имя поля.
+    ///
+    /// После синтаксической ошибки итератор завершается; после ошибки
+    /// в значении поля чтение продолжается со следующего элемента.
+    fn reader<R: Read>(reader: R) -> JsonReader<R> {
+        JsonReader {
+            inner: BufReader::new(reader),
+            offset: 0,
+            index: 0,
+            state: ArrayState::Start,
+        }
+    }
+
+    /// Создаёт писателя JSON-массива, по одному элементу на строку.
+    fn writer<W: Write>(writer: W) -> JsonWriter<W> {
+        JsonWriter { inner: writer, written: 0 }
+    }
+}
+
+/// Парсер NDJSON: по одной транзакции на строку.
+pub struct NdjsonParser;
+
+impl Format for NdjsonParser {
+    type Reader<R: Read> = NdjsonReader<R>;
+    type Writer<W: Write> = NdjsonWriter<W>;
+
+    /// Создаёт потоковый итератор по строкам NDJSON.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если строка не является корректным JSON
+    ///   или объект не соответствует модели [`Transaction`]; ошибка содержит
+    ///   номер строки, колонку и имя поля.
+    fn reader<R: Read>(reader: R) -> NdjsonReader<R> {
+        NdjsonReader {
+            inner: BufReader::new(reader),
+            line: String::new(),
+            line_no: 0,
+            records: 0,
+            offset: 0,
+        }
+    }
+
+    /// Создаёт писателя NDJSON.
+    fn writer<W: Write>(writer: W) -> NdjsonWriter<W> {
+        NdjsonWriter { inner: writer }
+    }
+}
+
+/// Положение читателя внутри JSON-массива.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArrayState {
+    /// Открывающая скобка ещё не прочитана.
+    Start,
+    /// Ожидается первый элемент или закрывающая скобка.
+    First,
+    /// Ожидается запятая или закрывающая скобка.
+    Next,
+    /// Массив прочитан или чтение прервано ошибкой.
+    Done,
+}
+
+/// Потоковый итератор транзакций из JSON-массива.
+pub struct JsonReader<R: Read> {
+    inner: BufReader<R>,
+    offset: u64,
+    index: u64,
+    state: ArrayState,
+}
+
+/// Читатель, считающий прочитанные байты.
+struct Counted<'a, R> {
+    inner: &'a mut R,
+    count: u64,
+}
+
+impl<R: Read> Read for Counted<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+
+        Ok(n)
+    }
+}
+
+impl<R: Read> JsonReader<R> {
+    /// Пропускает пробельные символы и возвращает следующий байт, не потребляя его.
+    fn peek(&mut self) -> std::io::Result<Option<u8>> {
+        loop {
+            let buf = self.inner.fill_buf()?;
+            let Some(&byte) = buf.first() else { return Ok(None) };
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+
+            self.inner.consume(1);
+            self.offset += 1;
+        }
+    }
+
+    fn bump(&mut self) {
+        self.inner.consume(1);
+        self.offset += 1;
+    }
+
+    fn syntax_error(&mut self, reason: impl Into<String>) -> ParserError {
+        self.state = ArrayState::Done;
+        RecordError::new("json", reason)
+            .at(Position { record: Some(self.index + 1), byte: Some(self.offset), ..Position::default() })
+            .into()
+    }
+
+    /// Переходит к началу следующего элемента; `false` означает конец массива.
+    fn advance(&mut self) -> Result<bool, ParserError> {
+        let expected = match self.state {
+            ArrayState::Start => {
+                if self.peek().map_err(ParserError::Io)? != Some(b'[') {
+                    return Err(self.syntax_error("expected JSON array"));
+                }
+                self.bump();
+                self.state = ArrayState::First;
+                return self.advance();
+            }
+            ArrayState::First => None,
+            ArrayState::Next => Some(b','),
+            ArrayState::Done => return Ok(false),
+        };
+
+        match self.peek().map_err(ParserError::Io)? {
+            Some(b']') => {
+                self.bump();
+                self.state = ArrayState::Done;
+                if self.peek().map_err(ParserError::Io)?.is_some() {
+                    return Err(self.syntax_error("unexpected data after JSON array"));
+                }
+                Ok(false)
+            }
+            Some(byte) if Some(byte) == expected => {
+                self.bump();
+                Ok(true)
+            }
+            Some(_) if expected.is_none() => Ok(true),
+            Some(_) => Err(self.syntax_error("expected ',' or ']'")),
+            None => Err(self.syntax_error("unexpected end of JSON array")),
+        }
+    }
+}
+
+impl<R: Read> Iterator for JsonReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.advance() {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => return Some(Err(err)),
+        }
+
+        if let Err(err) = self.peek() {
+            return Some(Err(ParserError::Io(err)));
+        }
+
+        let start = self.offset;
+        let mut counted = Counted { inner: &mut self.inner, count: 0 };
+        let value = Value::deserialize(&mut serde_json::Deserializer::from_reader(&mut counted));
+        self.offset += counted.count;
+
+        let value = match value {
+            Ok(value) => value,
+            Err(err) if err.is_io() => return Some(Err(ParserError::Io(err.into()))),
+            Err(err) => {
+                self.offset = start;
+                return Some(Err(self.syntax_error(err.to_string())));
+            }
+        };
+
+        self.index += 1;
+        // Разбор скаляра читает один байт после значения, поэтому
+        // продолжать чтение массива можно только после объекта или массива.
+        self.state = match value {
+            Value::Object(_) | Value::Array(_) => ArrayState::Next,
+            _ => ArrayState::Done,
+        };
+        let position = Position { record: Some(self.index), byte: Some(start), ..Position::default() };
+
+        Some(transaction(value, "json").map_err(|err| err.at(position).into()))
+    }
+}
+
+/// Потоковый итератор транзакций из NDJSON.
+pub struct NdjsonReader<R: Read> {
+    inner: BufReader<R>,
+    line: String,
+    line_no: u64,
+    records: u64,
+    offset: u64,
+}
+
+impl<R: Read> Iterator for NdjsonReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            let len = match self.inner.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(len) => len,
+                Err(err) => return Some(Err(ParserError::Io(err))),
+            };
+
+            self.line_no += 1;
+            let byte = self.offset;
+            self.offset += len as u64;
+
+            let line = self.line.trim_end();
+            if line.trim_start().is_empty() {
+                continue;
+            }
+
+            self.records += 1;
+            let position = Position {
+                record: Some(self.records),
+                line: Some(self.line_no),
+                column: None,
+                byte: Some(byte),
+            };
+
+            let result = match serde_json::from_str(line) {
+                Ok(value) => transaction(value, "ndjson").map_err(|err| err.at(position)),
+                Err(err) => Err(RecordError::new("ndjson", err.to_string())
+                    .at(Position { column: Some(err.column() as u64), ..position })),
+            };
+
+            return Some(result.map_err(|err| ParserError::Record(Box::new(err.raw(line)))));
+        }
+    }
+}
+
+/// Собирает транзакцию из JSON-объекта.
+fn transaction(value: Value, format: &'static str) -> Result<Transaction, Box<RecordError>> {
+    let Value::Object(object) = value else {
+        return Err(Box::new(RecordError::new(format, "expected JSON object").value(value.to_string())));
+    };
+    let fields = JsonFields { object, format };
+
+    let id = fields.required(Column::Id, |value| match value {
+        Value::Number(number) => number.as_u64().ok_or_else(|| format!("Invalid id: {}", number)),
+        Value::String(s) => s.parse().map_err(|err| format!("Invalid id: {}", err)),
+        _ => Err("expected number".to_string()),
+    })?;
+    let account = fields.required(Column::Account, string)?;
+    let currency: Currency = fields.required(Column::Currency, |value| parse(string(value)?, str::parse))?;
+    let amount = fields.required(Column::Amount, |value| {
+        let text = match value {
+            // С `arbitrary_precision` число хранит исходный текст литерала.
+            Value::Number(number) => number.to_string(),
+            value => string(value)?,
+        };
+        Amount::parse(&text, currency.exponent()).map_err(ParserError::into_reason)
+    })?;
+
+    let mut tx = Transaction::new(id, account, amount, currency);
+    tx.booking_date = fields.optional(Column::BookingDate, |value| parse(string(value)?, parse_date))?;
+    tx.value_date = fields.optional(Column::ValueDate, |value| parse(string(value)?, parse_date))?;
+    tx.description = fields.optional(Column::Description, string)?;
+    tx.counterparty = fields.optional(Column::Counterparty, string)?;
+    tx.direction = fields.optional(Column::Direction, |value| parse(string(value)?, str::parse))?;
+    tx.status = fields.optional(Column::Status, |value| parse(string(value)?, str::parse))?;
+
+    Ok(tx)
+}
+
+fn string(value: &Value) -> Result<String, String> {
+    value.as_str().map(str::to_string).ok_or_else(|| "expected string".to_string())
+}
+
+fn parse<T>(s: String, parse: impl FnOnce(&str) -> Result<T, ParserError>) -> Result<T, String> {
+    parse(&s).map_err(ParserError::into_reason)
+}
+
+/// Поля JSON-объекта с привязкой ошибок к имени поля.
+struct JsonFields {
+    object: Map<String, Value>,
+    format: &'static str,
+}
+
+impl JsonFields {
+    fn required<T>(&self, column: Column, parse: impl FnOnce(&Value) -> Result<T, String>) -> Result<T, Box<RecordError>> {
+        match self.object.get(column.name()) {
+            None | Some(Value::Null) => Err(Box::new(RecordError::new(self.format, "missing field").field(column.name()))),
+            Some(value) => parse(value).map_err(|reason| self.error(column, value, reason)),
+        }
+    }
+
+    /// Отсутствующее поле или `null` дают `None`.
+    fn optional<T>(&self, column: Column, parse: impl FnOnce(&Value) -> Result<T, String>) -> Result<Option<T>, Box<RecordError>> {
+        match self.object.get(column.name()) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => parse(value).map(Some).map_err(|reason| self.error(column, value, reason)),
+        }
+    }
+
+    fn error(&self, column: Column, value: &Value, reason: String) -> Box<RecordError> {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            value => value.to_string(),
+        };
+
+        Box::new(RecordError::new(self.format, reason).field(column.name()).value(value))
+    }
+}
+
+/// Инкрементальный писатель JSON-массива.
+pub struct JsonWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> TransactionWriter for JsonWriter<W> {
+    /// Записывает транзакцию очередным элементом массива.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let separator: &[u8] = if self.written == 0 { b"[\n" } else { b",\n" };
+        self.inner.write_all(separator).map_err(ParserError::Io)?;
+        serde_json::to_writer(&mut self.inner, tx).map_err(|err| ParserError::Io(err.into()))?;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    /// Закрывает массив; пустой поток даёт `[]`.
+    fn finish(&mut self) -> Result<(), ParserError> {
+        let end: &[u8] = if self.written == 0 { b"[]\n" } else { b"\n]\n" };
+        self.inner.write_all(end).map_err(ParserError::Io)?;
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
+
+/// Инкрементальный писатель NDJSON.
+pub struct NdjsonWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> TransactionWriter for NdjsonWriter<W> {
+    /// Записывает транзакцию отдельной строкой.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        serde_json::to_writer(&mut self.inner, tx).map_err(|err| ParserError::Io(err.into()))?;
+        self.inner.write_all(b"\n").map_err(ParserError::Io)
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
@@ -0,0 +1,370 @@
+//! Текстовый формат «ключ: значение» с многострочными записями.
+//!
+//! Каждая транзакция записывается блоком строк `КЛЮЧ: значение`,
+//! блоки разделяются пустыми строками:
+//!
+//! ```text
+//! TX_ID: 1
+//! ACCOUNT: DE89 3704 0044
+//! AMOUNT: 100.00
+//! CURRENCY: USD
+//! DESCRIPTION: Rent for May
+//!
+//! TX_ID: 2
+//! CURRENCY: EUR
+//! AMOUNT: -50.00
+//! ACCOUNT: ACC2
+//! ```
+//!
+//! Ключи (без учёта регистра): `TX_ID`, `ACCOUNT`, `AMOUNT`, `CURRENCY` —
+//! обязательные; `BOOKING_DATE`, `VALUE_DATE`, `DESCRIPTION`, `COUNTERPARTY`,
+//! `DIRECTION`, `STATUS` — необязательные. Порядок ключей в блоке любой,
+//! повторение ключа — ошибка. Неизвестные ключи по умолчанию отклоняются
+//! (см. [`UnknownKeys`]).
+//!
+//! Значение занимает остаток строки без пробелов по краям. Значение
+//! с пробелами по краям, пустое или начинающееся с кавычки записывается
+//! в двойных кавычках с теми же экранирующими последовательностями,
+//! что и в TXT (`\"`, `\\`, `\n`, `\r`, `\t`). Строки, начинающиеся с `#`,
+//! считаются комментариями.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use crate::csv::Column;
+use crate::parsers::txt::{quoted, read_quoted};
+use crate::{Amount, Currency, Format, Position, RecordError, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, parse_date};
+
+/// Ключи блока и соответствующие им поля транзакции.
+const KEYS: [(&str, Column); 10] = [
+    ("TX_ID", Column::Id),
+    ("ACCOUNT", Column::Account),
+    ("AMOUNT", Column::Amount),
+    ("CURRENCY", Column::Currency),
+    ("BOOKING_DATE", Column::BookingDate),
+    ("VALUE_DATE", Column::ValueDate),
+    ("DESCRIPTION", Column::Description),
+    ("COUNTERPARTY", Column::Counterparty),
+    ("DIRECTION", Column::Direction),
+    ("STATUS", Column::Status),
+];
+
+/// Проверяет, является ли строка полем блока с известным ключом.
+pub(crate) fn is_known_field(line: &str) -> bool {
+    line.split_once(':')
+        .is_some_and(|(key, _)| KEYS.iter().any(|(name, _)| key.trim().eq_ignore_ascii_case(name)))
+}
+
+/// Парсер формата «ключ: значение».
+///
+/// Использует настройки [`KvOptions`] по умолчанию.
+pub struct KvParser;
+
+impl Format for KvParser {
+    type Reader<R: Read> = KvReader<R>;
+    type Writer<W: Write> = KvWriter<W>;
+
+    /// Создаёт потоковый итератор по блокам.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если блок содержит повторяющийся или
+    ///   неизвестный ключ, в нём нет обязательного поля или поле не удалось
+    ///   распарсить; ошибка содержит номер записи и строки поля.
+    ///   Чтение продолжается со следующего блока.
+    fn reader<R: Read>(reader: R) -> KvReader<R> {
+        KvOptions::default().reader(reader)
+    }
+
+    /// Создаёт писателя, сериализующего каждую транзакцию в блок.
+    fn writer<W: Write>(writer: W) -> KvWriter<W> {
+        KvWriter { inner: writer, written: 0 }
+    }
+}
+
+/// Обработка ключей, не относящихся к полям транзакции.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownKeys {
+    /// Блок с неизвестным ключом отклоняется.
+    #[default]
+    Reject,
+    /// Неизвестные ключи пропускаются.
+    Ignore,
+}
+
+/// Настройки чтения формата «ключ: значение».
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KvOptions {
+    unknown_keys: UnknownKeys,
+}
+
+impl KvOptions {
+    /// Создаёт настройки по умолчанию.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Задаёт обработку неизвестных ключей.
+    pub fn unknown_keys(mut self, policy: UnknownKeys) -> Self {
+        self.unknown_keys = policy;
+        self
+    }
+
+    /// Создаёт потоковый итератор по блокам с этими настройками.
+    pub fn reader<R: Read>(&self, reader: R) -> KvReader<R> {
+        KvReader {
+            inner: BufReader::new(reader),
+            options: *self,
+            line: String::new(),
+            line_no: 0,
+            records: 0,
+            offset: 0,
+        }
+    }
+}
+
+/// Потоковый итератор транзакций из блоков «ключ: значение».
+pub struct KvReader<R: Read> {
+    inner: BufReader<R>,
+    options: KvOptions,
+    line: String,
+    line_no: u64,
+    records: u64,
+    offset: u64,
+}
+
+/// Поле блока.
+struct Field {
+    column: Column,
+    value: String,
+    position: Position,
+}
+
+/// Прочитанный блок до разбора полей.
+struct Block {
+    position: Position,
+    raw: String,
+    fields: Vec<Field>,
+    error: Option<Box<RecordError>>,
+}
+
+impl<R: Read> Iterator for KvReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block: Option<Block> = None;
+
+        loop {
+            self.line.clear();
+            let len = match self.inner.read_line(&mut self.line) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(err) => return Some(Err(ParserError::Io(err))),
+            };
+
+            self.line_no += 1;
+            let byte = self.offset;
+            self.offset += len as u64;
+
+            let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() && block.is_some() {
+                break;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let block = block.get_or_insert_with(|| Block {
+                position: Position {
+                    record: Some(self.records + 1),
+                    line: Some(self.line_no),
+                    column: None,
+                    byte: Some(byte),
+                },
+                raw: String::new(),
+                fields: Vec::new(),
+                error: None,
+            });
+            if !block.raw.is_empty() {
+                block.raw.push('\n');
+            }
+            block.raw.push_str(line);
+
+            // После ошибки блок дочитывается, чтобы следующий начался с нужной строки.
+            if block.error.is_none() {
+                let position = Position { line: Some(self.line_no), byte: Some(byte), ..block.position };
+                match parse_field(line, position, self.options) {
+                    Ok(Some(field)) => match block.fields.iter().find(|seen| seen.column == field.column) {
+                        Some(seen) => {
+                            let reason = format!("duplicate key, first defined on line {}", seen.position.line.unwrap_or_default());
+                            block.error = Some(Box::new(field_error(&field, reason)));
+                        }
+                        None => block.fields.push(field),
+                    },
+                    Ok(None) => {}
+                    Err(err) => block.error = Some(err),
+                }
+            }
+        }
+
+        let block = block?;
+        self.records += 1;
+
+        Some(build(block))
+    }
+}
+
+/// Разбирает строку `КЛЮЧ: значение`; `None` — пропущенный неизвестный ключ.
+fn parse_field(line: &str, position: Position, options: KvOptions) -> Result<Option<Field>, Box<RecordError>> {
+    let at = |offset: usize| Position {
+        column: Some(line[..offset].chars().count() as u64 + 1),
+        byte: position.byte.map(|byte| byte + offset as u64),
+        ..position
+    };
+
+    let Some((key, rest)) = line.split_once(':') else {
+        let offset = line.len() - line.trim_start().len();
+        return Err(Box::new(RecordError::new("kv", "expected KEY: value").at(at(offset)).value(line.trim())));
+    };
+
+    let name = key.trim();
+    let Some(&(_, column)) = KEYS.iter().find(|(known, _)| name.eq_ignore_ascii_case(known)) else {
+        return match options.unknown_keys {
+            UnknownKeys::Ignore => Ok(None),
+            UnknownKeys::Reject => {
+                let offset = key.len() - key.trim_start().len();
+                Err(Box::new(RecordError::new("kv", format!("Unknown key: {}", name)).at(at(offset)).value(name)))
+            }
+        };
+    };
+
+    let offset = key.len() + 1 + (rest.len() - rest.trim_start().len());
+    let rest = rest.trim();
+
+    let value = if rest.starts_with('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        chars.next();
+        read_quoted(&mut chars, 0, &mut value)
+            .map_err(|(index, reason)| Box::new(RecordError::new("kv", reason).at(at(offset + index)).field(column.name())))?;
+
+        let tail = chars.as_str().trim();
+        if !tail.is_empty() && !tail.starts_with('#') {
+            let index = rest.len() - chars.as_str().len();
+            return Err(Box::new(RecordError::new("kv", "unexpected text after closing quote")
+                .at(at(offset + index))
+                .field(column.name())
+                .value(tail)));
+        }
+        value
+    } else {
+        rest.to_string()
+    };
+
+    Ok(Some(Field { column, value, position: at(offset) }))
+}
+
+fn field_error(field: &Field, reason: String) -> RecordError {
+    RecordError::new("kv", reason)
+        .at(field.position)
+        .field(field.column.name())
+        .value(field.value.as_str())
+}
+
+/// Собирает транзакцию из полей блока.
+fn build(block: Block) -> Result<Transaction, ParserError> {
+    let raw = block.raw;
+    if let Some(err) = block.error {
+        return Err(err.raw(raw).into());
+    }
+
+    let get = |column: Column| block.fields.iter().find(|field| field.column == column);
+    let required = |column: Column| {
+        get(column).ok_or_else(|| {
+            ParserError::from(RecordError::new("kv", "missing field").at(block.position).field(column.name()).raw(raw.as_str()))
+        })
+    };
+    let invalid = |field: &Field, err: ParserError| field_error(field, err.into_reason()).raw(raw.as_str());
+
+    let id = required(Column::Id)?;
+    let id = id.value.parse().map_err(|err| invalid(id, ParserError::Invalid(format!("Invalid id: {}", err))))?;
+
+    let currency = required(Column::Currency)?;
+    let currency: Currency = currency.value.parse().map_err(|err| invalid(currency, err))?;
+
+    let amount = required(Column::Amount)?;
+    let amount = Amount::parse(&amount.value, currency.exponent()).map_err(|err| invalid(amount, err))?;
+
+    let account = required(Column::Account)?.value.clone();
+    let mut tx = Transaction::new(id, account, amount, currency);
+
+    for field in &block.fields {
+        let value = field.value.as_str();
+        match field.column {
+            Column::BookingDate => tx.booking_date = Some(parse_date(value).map_err(|err| invalid(field, err))?),
+            Column::ValueDate => tx.value_date = Some(parse_date(value).map_err(|err| invalid(field, err))?),
+            Column::Description => tx.description = Some(value.to_string()),
+            Column::Counterparty => tx.counterparty = Some(value.to_string()),
+            Column::Direction => tx.direction = Some(value.parse().map_err(|err| invalid(field, err))?),
+            Column::Status => tx.status = Some(value.parse().map_err(|err| invalid(field, err))?),
+            Column::Id | Column::Account | Column::Amount | Column::Currency => {}
+        }
+    }
+
+    Ok(tx)
+}
+
+/// Инкрементальный писатель транзакций в блоки «ключ: значение».
+pub struct KvWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> TransactionWriter for KvWriter<W> {
+    /// Записывает транзакцию блоком, отделяя его от предыдущего пустой строкой.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let values = [
+            Some(tx.id.to_string()),
+            Some(tx.account.clone()),
+            Some(tx.money()?.amount().to_string()),
+            Some(tx.currency.to_string()),
+            tx.booking_date.map(|date| date.to_string()),
+            tx.value_date.map(|date| date.to_string()),
+            tx.description.clone(),
+            tx.counterparty.clone(),
+            tx.direction.map(|direction| direction.to_string()),
+            tx.status.map(|status| status.to_string()),
+        ];
+
+        let mut block = String::new();
+        if self.written > 0 {
+            block.push('\n');
+        }
+        for ((key, _), value) in KEYS.iter().zip(values) {
+            let Some(value) = value else { continue };
+            let plain = !value.is_empty()
+                && value.trim() == value
+                && !value.starts_with('"')
+                && !value.contains(|c: char| c.is_control());
+            let value = if plain { value } else { quoted(&value) };
+            block.push_str(&format!("{}: {}\n", key, value));
+        }
+
+        self.inner.write_all(block.as_bytes()).map_err(ParserError::Io)?;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
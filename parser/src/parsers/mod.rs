@@ -1,3 +1,13 @@
 pub mod csv;
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod binary;
-pub mod txt;
\ No newline at end of file
+pub mod camt;
+pub mod fixed;
+pub mod json;
+pub mod kv;
+pub mod mt940;
+pub mod ofx;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod txt;
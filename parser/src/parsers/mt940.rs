@@ -0,0 +1,597 @@
+//! Выписки SWIFT MT940.
+//!
+//! Сообщение MT940 состоит из полей-тегов, каждое начинается с новой строки:
+//!
+//! ```text
+//! :20:STMT1
+//! :25:DE89370400440532013000
+//! :28C:1
+//! :60F:C240301EUR1000,00
+//! :61:2403020301D19,99NTRF1001
+//! :86:Invoice 17
+//! :62F:C240302EUR980,01
+//! -
+//! ```
+//!
+//! Каждое поле `:61:` превращается в [`Transaction`]:
+//! - дата валютирования (`ГГММДД`) и необязательная дата проводки (`ММДД`);
+//! - признак `C`/`D` (`RC`/`RD` для сторно) задаёт знак суммы и направление;
+//! - сумма записывается с десятичной запятой (`19,99`);
+//! - ссылка клиента становится идентификатором транзакции, если это число
+//!   не длиннее 16 цифр, иначе (например, `NONREF`) идентификатор равен
+//!   [`NONREF_ID`] плюс порядковый номер записи в файле и не совпадает
+//!   с числовыми ссылками.
+//!
+//! Счёт берётся из `:25:`, валюта — из входящего остатка `:60F:`,
+//! описание — из следующего за записью поля `:86:` (строки склеиваются).
+//! Реквизиты и остатки выписок (`:20:`, `:25:`, `:28C:`, `:60F:`, `:62F:`)
+//! доступны через [`Mt940Reader::statements`]. Заголовки SWIFT (`{1:...}{4:`)
+//! и завершающие строки `-` / `-}` пропускаются.
+//!
+//! Писатель формирует по одной выписке на каждую последовательность
+//! транзакций с одинаковыми счётом и валютой. Входящий остаток неизвестен
+//! и пишется нулевым, исходящий равен сумме записей. Описание переносится
+//! на строки `:86:` так, чтобы строка продолжения не начиналась с `-`, `:`
+//! или пробела; описание длиннее 6 строк по 65 символов или с символами
+//! вне набора SWIFT X не записывается.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use chrono::{Datelike, NaiveDate};
+use crate::{Amount, Currency, Direction, Format, Position, RecordError, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, now_utc};
+
+/// Максимальная длина строки описания `:86:`.
+const LINE_LEN: usize = 65;
+
+/// Максимальное количество строк описания `:86:`.
+const DESCRIPTION_LINES: usize = 6;
+
+/// Максимальная длина ссылки клиента в `:61:`.
+const REFERENCE_LEN: usize = 16;
+
+/// Начало диапазона идентификаторов записей без числовой ссылки.
+///
+/// Числовая ссылка не длиннее [`REFERENCE_LEN`] цифр всегда меньше этого
+/// значения. Писатель заменяет идентификаторы из диапазона
+/// `NONREF_ID..2 * NONREF_ID` ссылкой `NONREF`.
+pub const NONREF_ID: u64 = 10u64.pow(REFERENCE_LEN as u32);
+
+/// Парсер выписок MT940.
+pub struct Mt940Parser;
+
+impl Format for Mt940Parser {
+    type Reader<R: Read> = Mt940Reader<R>;
+    type Writer<W: Write> = Mt940Writer<W>;
+
+    /// Создаёт потоковый итератор по записям `:61:`.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если запись или остаток имеют неверный
+    ///   формат; ошибка содержит номер записи, строку начала поля и его текст.
+    fn reader<R: Read>(reader: R) -> Mt940Reader<R> {
+        Mt940Reader {
+            inner: BufReader::new(reader),
+            line: String::new(),
+            line_no: 0,
+            offset: 0,
+            peeked: None,
+            entries: 0,
+            statements: Vec::new(),
+            pending: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Создаёт писателя выписок MT940.
+    ///
+    /// Транзакции одной выписки накапливаются в памяти, пока не сменятся
+    /// счёт или валюта: исходящий остаток пишется после записей, но входящий
+    /// должен предшествовать им.
+    ///
+    /// Выписки без датированных записей получают текущую дату UTC;
+    /// другую дату задаёт [`Mt940Writer::with_date`].
+    fn writer<W: Write>(writer: W) -> Mt940Writer<W> {
+        Mt940Writer::with_date(writer, now_utc().date())
+    }
+}
+
+/// Остаток по счёту на дату.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Balance {
+    /// Дата остатка.
+    pub date: NaiveDate,
+
+    /// Сумма остатка; отрицательная для дебетового остатка.
+    pub amount: Amount,
+
+    /// Валюта остатка.
+    pub currency: Currency,
+}
+
+/// Реквизиты и остатки выписки MT940.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statement {
+    /// Референс выписки (`:20:`).
+    pub reference: String,
+
+    /// Счёт (`:25:`).
+    pub account: String,
+
+    /// Номер выписки (`:28C:`).
+    pub number: Option<String>,
+
+    /// Входящий остаток (`:60F:` или промежуточный `:60M:`).
+    pub opening: Option<Balance>,
+
+    /// Исходящий остаток (`:62F:` или промежуточный `:62M:`).
+    pub closing: Option<Balance>,
+}
+
+/// Строка файла без перевода строки.
+struct Line {
+    text: String,
+    line_no: u64,
+    byte: u64,
+}
+
+/// Поле MT940 со строками продолжения.
+struct Tag {
+    name: String,
+    lines: Vec<String>,
+    line_no: u64,
+    byte: u64,
+}
+
+/// Потоковый итератор транзакций из MT940.
+pub struct Mt940Reader<R: Read> {
+    inner: BufReader<R>,
+    line: String,
+    line_no: u64,
+    offset: u64,
+    /// Строка, прочитанная при поиске конца предыдущего поля.
+    peeked: Option<Line>,
+    /// Количество прочитанных полей `:61:`.
+    entries: u64,
+    statements: Vec<Statement>,
+    /// Запись `:61:`, к которой ещё может относиться поле `:86:`.
+    pending: Option<Result<Transaction, Box<RecordError>>>,
+    /// Готовые к выдаче результаты.
+    ready: VecDeque<Result<Transaction, ParserError>>,
+}
+
+impl<R: Read> Mt940Reader<R> {
+    /// Выписки, начатые на текущий момент.
+    ///
+    /// Остатки последней выписки заполняются по мере чтения:
+    /// исходящий остаток известен только после её последней записи.
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+
+    fn read_line(&mut self) -> std::io::Result<Option<Line>> {
+        self.line.clear();
+        let len = self.inner.read_line(&mut self.line)?;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        self.line_no += 1;
+        let byte = self.offset;
+        self.offset += len as u64;
+
+        let text = self.line.trim_end_matches(['\n', '\r']);
+        // Заголовки SWIFT: всё до блока текста `{4:` пропускается.
+        let text = match text.strip_prefix('{') {
+            Some(_) => text.find("{4:").map_or("", |start| &text[start + 3..]),
+            None => text,
+        };
+
+        Ok(Some(Line { text: text.to_string(), line_no: self.line_no, byte }))
+    }
+
+    /// Читает следующее поле вместе со строками продолжения.
+    fn next_tag(&mut self) -> std::io::Result<Option<Tag>> {
+        loop {
+            let line = match self.peeked.take() {
+                Some(line) => line,
+                None => match self.read_line()? {
+                    Some(line) => line,
+                    None => return Ok(None),
+                },
+            };
+            let Some((name, value)) = split_tag(&line.text) else { continue };
+
+            let mut tag = Tag {
+                name: name.to_string(),
+                lines: vec![value.to_string()],
+                line_no: line.line_no,
+                byte: line.byte,
+            };
+            while let Some(next) = self.read_line()? {
+                if split_tag(&next.text).is_some() {
+                    self.peeked = Some(next);
+                    break;
+                }
+                if next.text.trim().is_empty() || next.text.starts_with('-') {
+                    break;
+                }
+                tag.lines.push(next.text);
+            }
+
+            return Ok(Some(tag));
+        }
+    }
+
+    /// Обрабатывает поле, кроме `:86:`.
+    fn process(&mut self, tag: Tag) {
+        let position = Position { line: Some(tag.line_no), byte: Some(tag.byte), ..Position::default() };
+        let value = tag.lines[0].trim();
+
+        match tag.name.as_str() {
+            "20" => self.statements.push(Statement { reference: value.to_string(), ..Statement::default() }),
+            "25" => self.statement().account = value.to_string(),
+            "28C" => self.statement().number = Some(value.to_string()),
+            "60F" | "60M" | "62F" | "62M" => match balance(value) {
+                Ok(balance) if tag.name.starts_with("60") => self.statement().opening = Some(balance),
+                Ok(balance) => self.statement().closing = Some(balance),
+                Err(err) => {
+                    let name = if tag.name.starts_with("60") { "opening_balance" } else { "closing_balance" };
+                    let err = err.field(name).at(Position { record: Some(self.entries + 1), ..position }).raw(tag.lines.join("\n"));
+                    self.ready.push_back(Err(err.into()));
+                }
+            },
+            "61" => {
+                self.entries += 1;
+                let statement = self.statements.last().cloned().unwrap_or_default();
+                let position = Position { record: Some(self.entries), ..position };
+                let raw = tag.lines.join("\n");
+
+                self.pending = Some(
+                    entry(value, &statement, self.entries)
+                        .map_err(|err| Box::new(err.at(position).raw(raw)))
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn statement(&mut self) -> &mut Statement {
+        if self.statements.is_empty() {
+            self.statements.push(Statement::default());
+        }
+
+        self.statements.last_mut().expect("statement exists")
+    }
+
+    fn flush_pending(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            self.ready.push_back(pending.map_err(ParserError::Record));
+        }
+    }
+}
+
+impl<R: Read> Iterator for Mt940Reader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(item);
+            }
+
+            let tag = match self.next_tag() {
+                Ok(Some(tag)) => tag,
+                Ok(None) => {
+                    self.flush_pending();
+                    return self.ready.pop_front();
+                }
+                Err(err) => return Some(Err(ParserError::Io(err))),
+            };
+
+            if tag.name == "86" {
+                // Описание относится к предыдущей записи `:61:`.
+                if let Some(Ok(tx)) = self.pending.as_mut() {
+                    tx.description = Some(tag.lines.concat());
+                }
+                continue;
+            }
+
+            self.flush_pending();
+            self.process(tag);
+        }
+    }
+}
+
+/// Выделяет имя поля и значение из строки вида `:61:...`.
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix(':')?;
+    let (name, value) = rest.split_once(':')?;
+
+    // Проверка по байтам: имя из многобайтовых символов нельзя резать по индексу.
+    let bytes = name.as_bytes();
+    let valid = (2..=3).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2..].iter().all(u8::is_ascii_uppercase);
+
+    valid.then_some((name, value))
+}
+
+/// Разбирает остаток вида `C240301EUR1000,00`.
+fn balance(value: &str) -> Result<Balance, Box<RecordError>> {
+    let error = |reason: &str| Box::new(RecordError::new("mt940", reason).value(value));
+
+    let (mark, rest) = value.split_at_checked(1).ok_or_else(|| error("missing balance mark"))?;
+    let (date, rest) = rest.split_at_checked(6).ok_or_else(|| error("missing balance date"))?;
+    let (currency, amount) = rest.split_at_checked(3).ok_or_else(|| error("missing balance currency"))?;
+
+    let date = yymmdd(date).ok_or_else(|| error("Invalid balance date"))?;
+    let currency: Currency = currency.parse().map_err(|err: ParserError| error(&err.into_reason()))?;
+    let amount = parse_amount(amount, currency).ok_or_else(|| error("Invalid balance amount"))?;
+    let amount = match mark {
+        "C" => amount,
+        "D" => negate(amount),
+        _ => return Err(error("Invalid balance mark")),
+    };
+
+    Ok(Balance { date, amount, currency })
+}
+
+/// Разбирает поле `:61:` в транзакцию.
+fn entry(value: &str, statement: &Statement, index: u64) -> Result<Transaction, Box<RecordError>> {
+    let error = |field: &'static str, reason: &str, value: &str| {
+        Box::new(RecordError::new("mt940", reason).field(field).value(value))
+    };
+    let currency = statement.opening.map(|balance| balance.currency)
+        .ok_or_else(|| Box::new(RecordError::new("mt940", "missing opening balance :60F:").field("currency")))?;
+    if statement.account.is_empty() {
+        return Err(Box::new(RecordError::new("mt940", "missing account :25:").field("account")));
+    }
+
+    let (date, rest) = value.split_at_checked(6).ok_or_else(|| error("value_date", "missing value date", value))?;
+    let value_date = yymmdd(date).ok_or_else(|| error("value_date", "Invalid date", date))?;
+
+    // Дата проводки без года: год берётся ближайший к дате валютирования.
+    let (booking_date, rest) = match rest.get(..4).filter(|s| s.bytes().all(|b| b.is_ascii_digit())) {
+        Some(mmdd) => {
+            let date = (-1..=1)
+                .filter_map(|shift| NaiveDate::parse_from_str(&format!("{}{}", value_date.year() + shift, mmdd), "%Y%m%d").ok())
+                .min_by_key(|date| (*date - value_date).num_days().abs())
+                .ok_or_else(|| error("booking_date", "Invalid date", mmdd))?;
+            (Some(date), &rest[4..])
+        }
+        None => (None, rest),
+    };
+
+    let (direction, rest) = if let Some(rest) = rest.strip_prefix("RC") {
+        (Direction::Debit, rest)
+    } else if let Some(rest) = rest.strip_prefix("RD") {
+        (Direction::Credit, rest)
+    } else if let Some(rest) = rest.strip_prefix('C') {
+        (Direction::Credit, rest)
+    } else if let Some(rest) = rest.strip_prefix('D') {
+        (Direction::Debit, rest)
+    } else {
+        return Err(error("direction", "Invalid debit/credit mark", rest));
+    };
+
+    // Необязательный код средств (третья буква валюты).
+    let rest = match rest.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => &rest[1..],
+        _ => rest,
+    };
+
+    let end = rest.find(|c: char| !c.is_ascii_digit() && c != ',').unwrap_or(rest.len());
+    let (amount, rest) = rest.split_at(end);
+    let amount = parse_amount(amount, currency).ok_or_else(|| error("amount", "Invalid amount", amount))?;
+    let amount = match direction {
+        Direction::Credit => amount,
+        Direction::Debit => negate(amount),
+    };
+
+    let reference = rest.get(4..).ok_or_else(|| error("id", "missing transaction type", rest))?;
+    let reference = reference.split("//").next().unwrap_or_default();
+    let numeric = reference.len() <= REFERENCE_LEN && !reference.is_empty() && reference.bytes().all(|b| b.is_ascii_digit());
+    let id = match numeric {
+        true => reference.parse().map_err(|_| error("id", "Invalid reference", reference))?,
+        false => NONREF_ID + index,
+    };
+
+    let mut tx = Transaction::new(id, statement.account.clone(), amount, currency);
+    tx.booking_date = booking_date;
+    tx.value_date = Some(value_date);
+    tx.direction = Some(direction);
+
+    Ok(tx)
+}
+
+fn yymmdd(s: &str) -> Option<NaiveDate> {
+    if s.len() != 6 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year: i32 = s[..2].parse().ok()?;
+    let century = if year < 80 { 2000 } else { 1900 };
+
+    NaiveDate::from_ymd_opt(century + year, s[2..4].parse().ok()?, s[4..].parse().ok()?)
+}
+
+/// Разбирает сумму с десятичной запятой (`1000,00`, `5,`).
+fn parse_amount(s: &str, currency: Currency) -> Option<Amount> {
+    if s.matches(',').count() != 1 || !s.bytes().all(|b| b.is_ascii_digit() || b == b',') {
+        return None;
+    }
+
+    let s = s.replace(',', ".");
+    Amount::parse(s.trim_end_matches('.'), currency.exponent()).ok()
+}
+
+fn format_amount(amount: Amount) -> String {
+    let text = Amount::from_minor(amount.minor_units().saturating_abs(), amount.exponent()).to_string();
+    match text.contains('.') {
+        true => text.replace('.', ","),
+        false => text + ",",
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    format!("{:02}{:02}{:02}", date.year() % 100, date.month(), date.day())
+}
+
+/// Разбивает описание на строки `:86:`.
+///
+/// Читатель склеивает строки без разделителя, поэтому перенос возможен
+/// в любом месте; строка продолжения не должна начинаться с `-` (конец
+/// сообщения), `:` (новое поле) или пробела (иначе она может оказаться пустой).
+fn description_lines(id: u64, description: &str) -> Result<Vec<String>, ParserError> {
+    let description: Vec<char> = description.replace(['\n', '\r'], " ").chars().collect();
+    if let Some(c) = description.iter().find(|c| !is_swift_x(**c)) {
+        return Err(ParserError::Invalid(format!(
+            "Description of transaction {} contains {:?}, which is not allowed in MT940", id, c
+        )));
+    }
+
+    let too_long = || ParserError::Invalid(format!(
+        "Description of transaction {} does not fit into {} lines of {} characters", id, DESCRIPTION_LINES, LINE_LEN
+    ));
+    let mut lines = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut end = (start + LINE_LEN).min(description.len());
+        while end < description.len() && end > start && matches!(description[end], '-' | ':' | ' ') {
+            end -= 1;
+        }
+        if end == start && end < description.len() {
+            return Err(too_long());
+        }
+
+        lines.push(description[start..end].iter().collect::<String>());
+        if end == description.len() {
+            break;
+        }
+        start = end;
+    }
+
+    if lines.len() > DESCRIPTION_LINES {
+        return Err(too_long());
+    }
+
+    Ok(lines)
+}
+
+/// Входит ли символ в набор SWIFT X.
+fn is_swift_x(c: char) -> bool {
+    c.is_ascii_alphanumeric() || " /-?:().,'+".contains(c)
+}
+
+fn negate(amount: Amount) -> Amount {
+    Amount::from_minor(-amount.minor_units(), amount.exponent())
+}
+
+/// Писатель выписок MT940.
+pub struct Mt940Writer<W: Write> {
+    inner: W,
+    /// Дата для остатков выписки без датированных записей.
+    today: NaiveDate,
+    /// Транзакции текущей выписки.
+    statement: Vec<Transaction>,
+    /// Количество записанных выписок.
+    statements: u64,
+}
+
+impl<W: Write> Mt940Writer<W> {
+    /// Создаёт писателя, который датирует `date` остатки выписок
+    /// и записи без даты.
+    pub fn with_date(writer: W, date: NaiveDate) -> Self {
+        Self {
+            inner: writer,
+            today: date,
+            statement: Vec::new(),
+            statements: 0,
+        }
+    }
+
+    /// Записывает накопленную выписку.
+    fn flush_statement(&mut self) -> Result<(), ParserError> {
+        let Some(first) = self.statement.first() else { return Ok(()) };
+        let currency = first.currency;
+
+        let mut closing = Amount::from_minor(0, currency.exponent());
+        for tx in &self.statement {
+            closing = closing.checked_add(&tx.money()?.amount())
+                .ok_or_else(|| ParserError::Invalid(format!("Statement balance overflow for account {}", tx.account)))?;
+        }
+        let dates: Vec<NaiveDate> = self.statement.iter().filter_map(|tx| tx.value_date.or(tx.booking_date)).collect();
+        let opening_date = dates.iter().min().copied().unwrap_or(self.today);
+        let closing_date = dates.iter().max().copied().unwrap_or(self.today);
+
+        self.statements += 1;
+        let mut out = format!(":20:STMT{}\n:25:{}\n:28C:{}\n", self.statements, first.account, self.statements);
+        let opening = Amount::from_minor(0, currency.exponent());
+        out.push_str(&format!(":60F:C{}{}{}\n", format_date(opening_date), currency, format_amount(opening)));
+
+        for tx in &self.statement {
+            let amount = tx.money()?.amount();
+            let reference = match tx.id {
+                id if (NONREF_ID..2 * NONREF_ID).contains(&id) => "NONREF".to_string(),
+                id => id.to_string(),
+            };
+            if reference.len() > REFERENCE_LEN {
+                return Err(ParserError::Invalid(format!("Transaction id {} is too long for MT940 reference", tx.id)));
+            }
+
+            let value_date = tx.value_date.or(tx.booking_date).unwrap_or(opening_date);
+            let booking_date = tx.booking_date.map(|date| format!("{:02}{:02}", date.month(), date.day())).unwrap_or_default();
+            let mark = match (amount.minor_units().signum(), tx.direction) {
+                (-1, _) | (0, Some(Direction::Debit)) => "D",
+                _ => "C",
+            };
+            out.push_str(&format!(
+                ":61:{}{}{}{}NTRF{}\n",
+                format_date(value_date), booking_date, mark, format_amount(amount), reference
+            ));
+
+            if let Some(description) = &tx.description {
+                for (index, line) in description_lines(tx.id, description)?.iter().enumerate() {
+                    let prefix = if index == 0 { ":86:" } else { "" };
+                    out.push_str(&format!("{}{}\n", prefix, line));
+                }
+            }
+        }
+
+        let mark = if closing.minor_units() < 0 { "D" } else { "C" };
+        out.push_str(&format!(":62F:{}{}{}{}\n-\n", mark, format_date(closing_date), currency, format_amount(closing)));
+
+        self.statement.clear();
+        self.inner.write_all(out.as_bytes()).map_err(ParserError::Io)
+    }
+}
+
+impl<W: Write> TransactionWriter for Mt940Writer<W> {
+    /// Добавляет транзакцию в текущую выписку.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`] при ошибке записи
+    /// и [`ParserError::Invalid`], если идентификатор длиннее 16 знаков,
+    /// описание не помещается в поле `:86:` или остаток выписки переполнен.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let same = self.statement.first()
+            .is_none_or(|first| first.account == tx.account && first.currency == tx.currency);
+        if !same {
+            self.flush_statement()?;
+        }
+
+        self.statement.push(tx.clone());
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.flush_statement()?;
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
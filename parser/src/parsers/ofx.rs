@@ -0,0 +1,499 @@
+//! Выгрузки OFX/QFX (Quicken, Microsoft Money).
+//!
+//! Читатель принимает OFX 1.x (SGML, листовые элементы без закрывающих
+//! тегов) и OFX 2.x (XML). Каждая запись `STMTTRN` превращается
+//! в [`Transaction`]:
+//!
+//! - `FITID` — идентификатор транзакции; нечисловой `FITID`, как и ссылка
+//!   `NONREF` в MT940, даёт идентификатор [`NONREF_ID`] плюс порядковый
+//!   номер записи;
+//! - `TRNAMT` — сумма со знаком;
+//! - `ACCTID` из `BANKACCTFROM` (или `CCACCTFROM`) — счёт;
+//! - `CURDEF` выписки (или `CURRENCY/CURSYM` записи) — валюта;
+//! - `DTPOSTED` и `DTAVAIL` — даты проводки и валютирования;
+//! - `MEMO` (или `NAME`) — описание, `ACCTID` из `BANKACCTTO` — контрагент;
+//! - `TRNTYPE` `CREDIT`/`DEBIT` — направление, для остальных типов
+//!   направление определяется знаком суммы.
+//!
+//! Писатель формирует банковскую выписку OFX 2.2: по одному `STMTRS`
+//! на каждую последовательность транзакций с одинаковыми счётом и валютой.
+//! Код банка (`BANKID`) неизвестен и пишется нулевым, остаток
+//! (`LEDGERBAL`) равен сумме записей выписки.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use encoding_rs::{Encoding, UTF_8};
+use quick_xml::escape::{escape, unescape};
+use crate::{Amount, Currency, Direction, Format, Position, RecordError, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, now_utc};
+use crate::mt940::NONREF_ID;
+
+/// Парсер выгрузок OFX/QFX.
+pub struct OfxParser;
+
+impl Format for OfxParser {
+    type Reader<R: Read> = OfxReader<R>;
+    type Writer<W: Write> = OfxWriter<W>;
+
+    /// Создаёт потоковый итератор по записям `STMTTRN`.
+    ///
+    /// Кодировка OFX 1.x берётся из заголовка `CHARSET`, по умолчанию UTF-8.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Record`] — если документ повреждён или запись
+    ///   не соответствует модели [`Transaction`]; ошибка содержит номер
+    ///   записи, смещение её начала и имя элемента.
+    ///
+    /// После ошибки в структуре документа итератор завершается; после
+    /// ошибки в значении элемента чтение продолжается со следующей записи.
+    fn reader<R: Read>(reader: R) -> OfxReader<R> {
+        OfxReader {
+            inner: BufReader::new(reader),
+            buf: Vec::new(),
+            offset: 0,
+            encoding: UTF_8,
+            path: Vec::new(),
+            leaf: false,
+            account: None,
+            currency: None,
+            entry: None,
+            entries: 0,
+            done: false,
+        }
+    }
+
+    /// Создаёт писателя выписки OFX 2.
+    ///
+    /// Транзакции одной выписки накапливаются в памяти, пока не сменятся
+    /// счёт или валюта: период выписки пишется перед записями.
+    ///
+    /// Время формирования — текущее время UTC; другое время задаёт
+    /// [`OfxWriter::with_time`].
+    fn writer<W: Write>(writer: W) -> OfxWriter<W> {
+        OfxWriter::with_time(writer, now_utc())
+    }
+}
+
+/// Значения элементов записи `STMTTRN`, собранные до её закрытия.
+#[derive(Default)]
+struct Entry {
+    byte: u64,
+    fitid: Option<String>,
+    kind: Option<String>,
+    amount: Option<String>,
+    currency: Option<String>,
+    posted: Option<String>,
+    available: Option<String>,
+    name: Option<String>,
+    memo: Option<String>,
+    counterparty: Option<String>,
+}
+
+/// Тег OFX.
+enum Tag {
+    Start(String),
+    End(String),
+}
+
+/// Потоковый итератор транзакций из OFX.
+pub struct OfxReader<R: Read> {
+    inner: BufReader<R>,
+    buf: Vec<u8>,
+    offset: u64,
+    /// Кодировка текста (из заголовка OFX 1.x).
+    encoding: &'static Encoding,
+    /// Имена открытых элементов.
+    path: Vec<String>,
+    /// Последний открытый элемент уже получил значение: в SGML такой
+    /// элемент закрывается неявно следующим тегом.
+    leaf: bool,
+    /// Счёт текущей выписки.
+    account: Option<String>,
+    /// Валюта текущей выписки.
+    currency: Option<String>,
+    entry: Option<Entry>,
+    entries: u64,
+    done: bool,
+}
+
+impl<R: Read> OfxReader<R> {
+    fn syntax_error(&mut self, reason: impl Into<String>, byte: u64) -> ParserError {
+        self.done = true;
+        RecordError::new("ofx", reason)
+            .at(Position { record: Some(self.entries + 1), byte: Some(byte), ..Position::default() })
+            .into()
+    }
+
+    /// Читает до `delimiter` включительно; возвращает `false` в конце потока.
+    fn read_until(&mut self, delimiter: u8) -> std::io::Result<bool> {
+        self.buf.clear();
+        let len = self.inner.read_until(delimiter, &mut self.buf)?;
+        self.offset += len as u64;
+
+        Ok(self.buf.last() == Some(&delimiter))
+    }
+
+    /// Обрабатывает текст между тегами.
+    fn text(&mut self, text: &str) {
+        let Some(name) = self.path.last() else {
+            // Заголовок OFX 1.x: строки `КЛЮЧ:ЗНАЧЕНИЕ` до `<OFX>`.
+            let charset = text.lines().find_map(|line| line.trim().strip_prefix("CHARSET:"));
+            if let Some(charset) = charset.filter(|charset| charset.bytes().all(|b| b.is_ascii_digit())) {
+                self.encoding = Encoding::for_label(format!("windows-{}", charset).as_bytes()).unwrap_or(UTF_8);
+            }
+            return;
+        };
+
+        let value = unescape(text).map_or_else(|_| text.to_string(), |value| value.into_owned());
+        let parent = self.path.len().checked_sub(2).map(|i| self.path[i].as_str()).unwrap_or_default();
+
+        match self.entry.as_mut() {
+            Some(entry) => {
+                let slot = match (parent, name.as_str()) {
+                    ("STMTTRN", "FITID") => Some(&mut entry.fitid),
+                    ("STMTTRN", "TRNTYPE") => Some(&mut entry.kind),
+                    ("STMTTRN", "TRNAMT") => Some(&mut entry.amount),
+                    ("STMTTRN", "DTPOSTED") => Some(&mut entry.posted),
+                    ("STMTTRN", "DTAVAIL") => Some(&mut entry.available),
+                    ("STMTTRN", "NAME") => Some(&mut entry.name),
+                    ("STMTTRN", "MEMO") => Some(&mut entry.memo),
+                    ("CURRENCY", "CURSYM") => Some(&mut entry.currency),
+                    ("BANKACCTTO" | "CCACCTTO", "ACCTID") => Some(&mut entry.counterparty),
+                    _ => None,
+                };
+                if let Some(slot) = slot {
+                    *slot = Some(value);
+                }
+            }
+            None => match (parent, name.as_str()) {
+                ("STMTRS" | "CCSTMTRS", "CURDEF") => self.currency = Some(value),
+                ("BANKACCTFROM" | "CCACCTFROM", "ACCTID") => self.account = Some(value),
+                _ => {}
+            },
+        }
+
+        self.leaf = true;
+    }
+
+    /// Обрабатывает тег; возвращает запись, если закрыт `STMTTRN`.
+    fn tag(&mut self, tag: Tag, byte: u64) -> Result<Option<Entry>, ParserError> {
+        let mut closed = None;
+        if std::mem::take(&mut self.leaf) {
+            // Закрывающий тег листового элемента (XML) или неявное закрытие (SGML).
+            if matches!(&tag, Tag::End(name) if self.path.last() == Some(name)) {
+                self.path.pop();
+                return Ok(None);
+            }
+            closed = self.pop();
+        }
+
+        match tag {
+            Tag::Start(name) => {
+                if self.path.is_empty() && name != "OFX" {
+                    return Err(self.syntax_error(format!("expected OFX document, found <{}>", name), byte));
+                }
+
+                match name.as_str() {
+                    "STMTRS" | "CCSTMTRS" => {
+                        self.account = None;
+                        self.currency = None;
+                    }
+                    "STMTTRN" => self.entry = Some(Entry { byte, ..Entry::default() }),
+                    _ => {}
+                }
+                self.path.push(name);
+            }
+            Tag::End(name) => {
+                if !self.path.contains(&name) {
+                    return Err(self.syntax_error(format!("unexpected closing tag </{}>", name), byte));
+                }
+                // В SGML закрывающий тег агрегата закрывает и все вложенные элементы.
+                while let Some(last) = self.path.last().cloned() {
+                    closed = closed.or(self.pop());
+                    if last == name {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(closed)
+    }
+
+    fn pop(&mut self) -> Option<Entry> {
+        match self.path.pop().as_deref() {
+            Some("STMTTRN") => self.entry.take(),
+            _ => None,
+        }
+    }
+
+    /// Собирает транзакцию из записи выписки с порядковым номером `index`.
+    fn transaction(&self, entry: Entry, index: u64) -> Result<Transaction, Box<RecordError>> {
+        let error = |field: &'static str, value: &Option<String>, reason: String| {
+            let error = RecordError::new("ofx", reason).field(field);
+            Box::new(match value {
+                Some(value) => error.value(value.clone()),
+                None => error,
+            })
+        };
+        let required = |field: &'static str, value: &Option<String>| {
+            value.clone().ok_or_else(|| error(field, value, "missing element".to_string()))
+        };
+
+        let account = required("ACCTID", &self.account)?;
+        let id = required("FITID", &entry.fitid)?.parse().unwrap_or(NONREF_ID + index);
+        let currency = entry.currency.as_ref().or(self.currency.as_ref()).cloned();
+        let currency: Currency = required("CURDEF", &currency)?
+            .parse()
+            .map_err(|err: ParserError| error("CURDEF", &currency, err.into_reason()))?;
+
+        let amount = required("TRNAMT", &entry.amount)?;
+        // Некоторые выгрузки используют десятичную запятую.
+        let amount = match amount.contains('.') {
+            true => amount,
+            false => amount.replace(',', "."),
+        };
+        let amount = Amount::parse(&amount, currency.exponent())
+            .map_err(|err| error("TRNAMT", &entry.amount, err.into_reason()))?;
+
+        let date = |field: &'static str, value: &Option<String>| {
+            value.as_deref()
+                .map(|s| s.get(..8).and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok()))
+                .map(|date| date.ok_or_else(|| error(field, value, "Invalid date".to_string())))
+                .transpose()
+        };
+
+        let mut tx = Transaction::new(id, account, amount, currency);
+        tx.booking_date = date("DTPOSTED", &entry.posted)?;
+        tx.value_date = date("DTAVAIL", &entry.available)?;
+        tx.description = entry.memo.or(entry.name);
+        tx.counterparty = entry.counterparty;
+        tx.direction = Some(match entry.kind.as_deref() {
+            Some("CREDIT") => Direction::Credit,
+            Some("DEBIT") => Direction::Debit,
+            _ if amount.minor_units() < 0 => Direction::Debit,
+            _ => Direction::Credit,
+        });
+
+        Ok(tx)
+    }
+}
+
+impl<R: Read> Iterator for OfxReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let terminated = match self.read_until(b'<') {
+                Ok(terminated) => terminated,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(ParserError::Io(err)));
+                }
+            };
+            let byte = self.offset - u64::from(terminated);
+
+            let text = self.buf.strip_suffix(b"<").unwrap_or(&self.buf);
+            let (text, _) = self.encoding.decode_without_bom_handling(text);
+            let text = text.trim().to_string();
+            if !text.is_empty() {
+                self.text(&text);
+            }
+
+            if !terminated {
+                if !self.path.is_empty() || byte == 0 {
+                    return Some(Err(self.syntax_error("unexpected end of OFX document", byte)));
+                }
+                self.done = true;
+                break;
+            }
+
+            match self.read_until(b'>') {
+                Ok(true) => {}
+                Ok(false) => return Some(Err(self.syntax_error("unterminated tag", byte))),
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(ParserError::Io(err)));
+                }
+            }
+
+            let content = String::from_utf8_lossy(&self.buf[..self.buf.len() - 1]).trim().to_string();
+            // Инструкции обработки (`<?OFX ...?>`), комментарии и пустые элементы пропускаются.
+            if content.starts_with(['?', '!']) || content.ends_with('/') {
+                continue;
+            }
+            let tag = match content.strip_prefix('/') {
+                Some(name) => Tag::End(name.trim().to_string()),
+                None => Tag::Start(content.split_whitespace().next().unwrap_or_default().to_string()),
+            };
+
+            match self.tag(tag, byte) {
+                Ok(Some(entry)) => {
+                    self.entries += 1;
+                    let position = Position { record: Some(self.entries), byte: Some(entry.byte), ..Position::default() };
+
+                    return Some(self.transaction(entry, self.entries).map_err(|err| err.at(position).into()));
+                }
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+/// Писатель выписки OFX 2.
+pub struct OfxWriter<W: Write> {
+    inner: W,
+    /// Время формирования ответа (`DTSERVER`).
+    created: String,
+    /// Дата для выписки без датированных записей.
+    today: NaiveDate,
+    /// Транзакции текущей выписки.
+    statement: Vec<Transaction>,
+    /// Количество записанных выписок.
+    statements: u64,
+}
+
+impl<W: Write> OfxWriter<W> {
+    /// Создаёт писателя с временем формирования ответа `created`
+    /// (`DTSERVER`); его дата служит периодом выписки без датированных записей.
+    pub fn with_time(writer: W, created: NaiveDateTime) -> Self {
+        Self {
+            inner: writer,
+            created: format!("{}{:02}{:02}{:02}", date(created.date()), created.hour(), created.minute(), created.second()),
+            today: created.date(),
+            statement: Vec::new(),
+            statements: 0,
+        }
+    }
+
+    fn header(&mut self) -> std::io::Result<()> {
+        write!(
+            self.inner,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+             <?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n\
+             <OFX>\n  \
+             <SIGNONMSGSRSV1>\n    \
+             <SONRS>\n      \
+             <STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n      \
+             <DTSERVER>{}</DTSERVER>\n      \
+             <LANGUAGE>ENG</LANGUAGE>\n    \
+             </SONRS>\n  \
+             </SIGNONMSGSRSV1>\n  \
+             <BANKMSGSRSV1>\n",
+            self.created
+        )
+    }
+
+    /// Записывает накопленную выписку.
+    fn flush_statement(&mut self) -> Result<(), ParserError> {
+        let Some(first) = self.statement.first() else { return Ok(()) };
+        let currency = first.currency;
+
+        let mut balance = Amount::from_minor(0, currency.exponent());
+        for tx in &self.statement {
+            balance = balance.checked_add(&tx.money()?.amount())
+                .ok_or_else(|| ParserError::Invalid(format!("Statement balance overflow for account {}", tx.account)))?;
+        }
+        let dates: Vec<NaiveDate> = self.statement.iter().filter_map(|tx| tx.booking_date.or(tx.value_date)).collect();
+        let start = dates.iter().min().copied().unwrap_or(self.today);
+        let end = dates.iter().max().copied().unwrap_or(self.today);
+
+        self.statements += 1;
+        let mut out = String::new();
+        out.push_str("    <STMTTRNRS>\n");
+        out.push_str(&format!("      <TRNUID>{}</TRNUID>\n", self.statements));
+        out.push_str("      <STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n");
+        out.push_str("      <STMTRS>\n");
+        out.push_str(&format!("        <CURDEF>{}</CURDEF>\n", currency));
+        out.push_str(&format!("        <BANKACCTFROM>{}</BANKACCTFROM>\n", account(&first.account)));
+        out.push_str("        <BANKTRANLIST>\n");
+        out.push_str(&format!("          <DTSTART>{}</DTSTART>\n", date(start)));
+        out.push_str(&format!("          <DTEND>{}</DTEND>\n", date(end)));
+        for tx in &self.statement {
+            out.push_str(&entry(tx, tx.money()?.amount(), start));
+        }
+        out.push_str("        </BANKTRANLIST>\n");
+        out.push_str(&format!(
+            "        <LEDGERBAL><BALAMT>{}</BALAMT><DTASOF>{}</DTASOF></LEDGERBAL>\n",
+            balance, date(end)
+        ));
+        out.push_str("      </STMTRS>\n    </STMTTRNRS>\n");
+
+        self.statement.clear();
+        self.inner.write_all(out.as_bytes()).map_err(ParserError::Io)
+    }
+}
+
+impl<W: Write> TransactionWriter for OfxWriter<W> {
+    /// Добавляет транзакцию в текущую выписку.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`] при ошибке записи
+    /// и [`ParserError::Invalid`] при переполнении остатка выписки.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let same = self.statement.first()
+            .is_none_or(|first| first.account == tx.account && first.currency == tx.currency);
+        if !same {
+            if self.statements == 0 {
+                self.header().map_err(ParserError::Io)?;
+            }
+            self.flush_statement()?;
+        }
+
+        self.statement.push(tx.clone());
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        if self.statements == 0 {
+            self.header().map_err(ParserError::Io)?;
+        }
+        self.flush_statement()?;
+        self.inner.write_all(b"  </BANKMSGSRSV1>\n</OFX>\n").map_err(ParserError::Io)?;
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
+
+fn date(date: NaiveDate) -> String {
+    format!("{:04}{:02}{:02}", date.year(), date.month(), date.day())
+}
+
+/// Реквизиты счёта: код банка неизвестен, тип счёта — расчётный.
+fn account(account: &str) -> String {
+    format!("<BANKID>0</BANKID><ACCTID>{}</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE>", escape(account))
+}
+
+fn entry(tx: &Transaction, amount: Amount, default_date: NaiveDate) -> String {
+    let kind = match (amount.minor_units().signum(), tx.direction) {
+        (-1, _) | (0, Some(Direction::Debit)) => "DEBIT",
+        _ => "CREDIT",
+    };
+    let posted = tx.booking_date.or(tx.value_date).unwrap_or(default_date);
+
+    let mut out = String::from("          <STMTTRN>\n");
+    out.push_str(&format!("            <TRNTYPE>{}</TRNTYPE>\n", kind));
+    out.push_str(&format!("            <DTPOSTED>{}</DTPOSTED>\n", date(posted)));
+    if let Some(available) = tx.value_date {
+        out.push_str(&format!("            <DTAVAIL>{}</DTAVAIL>\n", date(available)));
+    }
+    out.push_str(&format!("            <TRNAMT>{}</TRNAMT>\n", amount));
+    out.push_str(&format!("            <FITID>{}</FITID>\n", tx.id));
+    if let Some(counterparty) = &tx.counterparty {
+        out.push_str(&format!("            <BANKACCTTO>{}</BANKACCTTO>\n", account(counterparty)));
+    }
+    if let Some(description) = &tx.description {
+        out.push_str(&format!("            <MEMO>{}</MEMO>\n", escape(description.as_str())));
+    }
+    out.push_str("          </STMTTRN>\n");
+
+    out
+}
@@ -0,0 +1,231 @@
+//! Формат Apache Parquet.
+//!
+//! Доступен с feature `parquet`. Транзакции хранятся в колонках со схемой
+//! [`crate::arrow::schema`], что позволяет загружать их в аналитические
+//! инструменты без разбора строк и без потери типов.
+//!
+//! Parquet хранит метаданные в конце файла, поэтому читатель сначала
+//! загружает поток целиком, а затем выдаёт транзакции пакетами. Писатель
+//! накапливает транзакции пакетами по [`BATCH_SIZE`] и по мере заполнения
+//! групп строк передаёт готовые байты в выходной поток.
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use bytes::Bytes;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use crate::errors::ParserError;
+use crate::models::Transaction;
+use crate::registry::{DynFormat, TransactionStream};
+use crate::{Format, TransactionWriter, arrow};
+
+/// Магические байты в начале и в конце файла Parquet.
+pub const MAGIC: [u8; 4] = *b"PAR1";
+
+/// Число транзакций в пакете при чтении и записи.
+pub const BATCH_SIZE: usize = 8192;
+
+/// Parquet-формат транзакций.
+pub struct Parquet;
+
+impl Format for Parquet {
+    type Reader<R: Read> = ParquetReader<R>;
+    type Writer<W: Write> = ParquetWriter<W>;
+
+    /// Создаёт итератор транзакций файла Parquet.
+    ///
+    /// Поток читается целиком при первом обращении к итератору.
+    ///
+    /// # Ошибки
+    ///
+    /// Итератор возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Invalid`] — если файл повреждён или в нём нет
+    ///   обязательных колонок;
+    /// - [`ParserError::Record`] — если значение в строке некорректно.
+    fn reader<R: Read>(reader: R) -> ParquetReader<R> {
+        ParquetReader {
+            source: Some(reader),
+            batches: None,
+            ready: VecDeque::new(),
+            records: 0,
+        }
+    }
+
+    /// Создаёт писателя Parquet со сжатием Snappy.
+    fn writer<W: Write>(writer: W) -> ParquetWriter<W> {
+        ParquetWriter {
+            inner: writer,
+            writer: None,
+            pending: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+impl DynFormat for Parquet {
+    fn name(&self) -> &'static str {
+        "parquet"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["parquet"]
+    }
+
+    fn mime_types(&self) -> &'static [&'static str] {
+        &["application/vnd.apache.parquet"]
+    }
+
+    fn sniff(&self, prefix: &[u8]) -> bool {
+        prefix.starts_with(&MAGIC)
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionStream<'a> {
+        Box::new(<Parquet as Format>::reader(reader))
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        Box::new(<Parquet as Format>::writer(writer))
+    }
+}
+
+/// Потоковый итератор транзакций из файла Parquet.
+pub struct ParquetReader<R: Read> {
+    source: Option<R>,
+    batches: Option<ParquetRecordBatchReader>,
+    ready: VecDeque<Result<Transaction, ParserError>>,
+    records: u64,
+}
+
+impl<R: Read> ParquetReader<R> {
+    /// Загружает поток и открывает файл.
+    fn open(mut source: R) -> Result<ParquetRecordBatchReader, ParserError> {
+        let mut data = Vec::new();
+        source.read_to_end(&mut data).map_err(ParserError::Io)?;
+
+        ParquetRecordBatchReaderBuilder::try_new(Bytes::from(data))
+            .and_then(|builder| builder.with_batch_size(BATCH_SIZE).build())
+            .map_err(parquet_error)
+    }
+}
+
+impl<R: Read> Iterator for ParquetReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(item);
+            }
+
+            if let Some(source) = self.source.take() {
+                match Self::open(source) {
+                    Ok(batches) => self.batches = Some(batches),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+
+            let batch = match self.batches.as_mut()?.next()? {
+                Ok(batch) => batch,
+                Err(err) => {
+                    self.batches = None;
+                    return Some(Err(ParserError::Invalid(format!("Parquet error: {}", err))));
+                }
+            };
+
+            match arrow::rows(&batch, "parquet", self.records) {
+                Ok(rows) => self.ready.extend(rows),
+                Err(err) => {
+                    self.batches = None;
+                    return Some(Err(err));
+                }
+            }
+            self.records += batch.num_rows() as u64;
+        }
+    }
+}
+
+/// Инкрементальный писатель транзакций в файл Parquet.
+pub struct ParquetWriter<W: Write> {
+    inner: W,
+    /// Писатель Parquet, выводящий байты в буфер; создаётся при первом пакете.
+    writer: Option<ArrowWriter<Vec<u8>>>,
+    pending: Vec<Transaction>,
+    /// Файл уже завершён: повторный `finish` ничего не пишет.
+    finished: bool,
+}
+
+impl<W: Write> ParquetWriter<W> {
+    /// Записывает накопленный пакет и передаёт готовые байты в выходной поток.
+    fn flush_batch(&mut self) -> Result<(), ParserError> {
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                self.writer.insert(ArrowWriter::try_new(Vec::new(), arrow::schema(), Some(properties)).map_err(parquet_error)?)
+            }
+        };
+
+        if !self.pending.is_empty() {
+            let batch = arrow::to_record_batch(&std::mem::take(&mut self.pending))?;
+            writer.write(&batch).map_err(parquet_error)?;
+        }
+
+        self.inner.write_all(writer.inner_mut()).map_err(ParserError::Io)?;
+        writer.inner_mut().clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> TransactionWriter for ParquetWriter<W> {
+    /// Добавляет транзакцию в текущий пакет.
+    ///
+    /// Сумма проверяется сразу, поэтому ошибка относится к этой
+    /// транзакции, а не к записи пакета целиком.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает:
+    /// - [`ParserError::Invalid`] — если сумму нельзя сохранить
+    ///   в колонке `amount`;
+    /// - [`ParserError::Io`] — при ошибке записи;
+    /// - [`ParserError::Invalid`] — если файл уже завершён.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        if self.finished {
+            return Err(ParserError::Invalid("Parquet file is already finished".to_string()));
+        }
+        arrow::scaled_amount(tx)?;
+        self.pending.push(tx.clone());
+        if self.pending.len() >= BATCH_SIZE {
+            self.flush_batch()?;
+        }
+
+        Ok(())
+    }
+
+    /// Записывает оставшиеся транзакции и метаданные файла.
+    ///
+    /// Повторный вызов только сбрасывает буфер выходного потока.
+    fn finish(&mut self) -> Result<(), ParserError> {
+        if self.finished {
+            return self.inner.flush().map_err(ParserError::Io);
+        }
+        self.finished = true;
+        self.flush_batch()?;
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish().map_err(parquet_error)?;
+            self.inner.write_all(writer.inner_mut()).map_err(ParserError::Io)?;
+        }
+
+        self.inner.flush().map_err(ParserError::Io)
+    }
+}
+
+/// Текст ошибки `ParquetError` уже начинается с `Parquet error:`.
+fn parquet_error(err: parquet::errors::ParquetError) -> ParserError {
+    ParserError::Invalid(err.to_string())
+}
@@ -8,104 +8,308 @@
 //!
 //! Поля разделяются пробелами:
 //! - `id` — `u64`
-//! - `account` — строка без пробелов
-//! - `amount` — `i64`
-//! - `currency` — строка без пробелов (например `USD`)
+//! - `account` — строка
+//! - `amount` — десятичная сумма (`12.34`) с точностью валюты
+//! - `currency` — код валюты ISO 4217 (например `USD`)
+//!
+//! За обязательными полями могут следовать необязательные в виде `ключ=значение`
+//! в любом порядке:
+//!
+//! ```text
+//! 1 "DE89 3704 0044" 100.00 USD booking_date=2024-01-31 description="Rent, May" status=booked
+//! ```
+//!
+//! Допустимые ключи: `booking_date`, `value_date` (даты `ГГГГ-ММ-ДД`),
+//! `description`, `counterparty`, `direction` (`credit`/`debit`)
+//! и `status` (`booked`/`pending`/`info`).
+//!
+//! Значение с пробелами, кавычками или пустое значение заключается в двойные
+//! кавычки. Внутри кавычек действуют экранирующие последовательности `\"`,
+//! `\\`, `\n`, `\r` и `\t`. Кавычки можно использовать в любой части поля:
+//! `description="a b"` и `"description=a b"` равнозначны.
+//!
+//! Пустые строки пропускаются. Символ `#` в начале поля (вне кавычек)
+//! начинает комментарий до конца строки:
+//!
+//! ```text
+//! # выгрузка за май
+//! 2 ACC2 -50.00 EUR  # возврат
+//! ```
 
-use std::fmt::format;
-use std::io::{BufRead, Read, Write};
-use crate::{Format, models::Transaction, errors::ParserError};
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::str::CharIndices;
+use crate::{Amount, Currency, Format, Position, RecordError, TransactionWriter, errors::ParserError};
+use crate::models::{Transaction, parse_date};
 
 /// TXT-парсер транзакций.
 ///
 /// Использует построчное чтение и пробелы в качестве разделителей.
-/// Предназначен для простого человеко-читаемого формата без кавычек
-/// и экранирования.
+/// Предназначен для простого человеко-читаемого формата; значения
+/// с пробелами заключаются в кавычки.
 pub struct TxtParser;
 
 impl Format for TxtParser {
-    /// Считывает транзакции из текстового потока.
+    type Reader<R: Read> = TxtReader<R>;
+    type Writer<W: Write> = TxtWriter<W>;
+
+    /// Создаёт потоковый итератор по строкам текстового потока.
     ///
-    /// Каждая строка должна содержать ровно 4 поля,
-    /// разделённых пробелами.
+    /// Каждая строка должна содержать 4 обязательных поля,
+    /// разделённых пробелами, и необязательные поля `ключ=значение`.
+    /// Пустые строки и комментарии пропускаются.
     ///
     /// # Ошибки
     ///
-    /// Возвращает:
+    /// Итератор возвращает:
     /// - [`ParserError::Io`] — при ошибке чтения потока;
-    /// - [`ParserError::Invalid`] — если строка имеет неверный формат
-    ///   или поле не удалось распарсить.
-    fn read<R: Read>(reader: R) -> Result<Vec<Transaction>, ParserError> {
-        let reader = std::io::BufReader::new(reader);
-        let mut res = Vec::new();
-
-        for (i, line) in reader.lines().enumerate() {
-            let line = line.map_err(|err| ParserError::Io(err))?;
-            let mut split = line.split_whitespace();
-
-            let id = split
-                .next()
-                .ok_or_else(|| ParserError::Invalid(format!("Invalid id, line {}: {}", i+1, line)))?
-                .parse()
-                .map_err(|_| ParserError::Invalid(format!("Invalid id, line {}: {}", i+1, line)))?;
-
-            let account = split
-                .next()
-                .ok_or_else(|| ParserError::Invalid(format!("Invalid account, line {}: {}", i + 1, line)))?
-                .to_string();
-
-            let amount = split
-                .next()
-                .ok_or_else(|| ParserError::Invalid(format!("Invalid amount, line {}: {}", i+1, line)))?
-                .parse()
-                .map_err(|_| ParserError::Invalid(format!("Invalid amount, line {}: {}", i+1, line)))?;
-
-            let currency = split
-                .next()
-                .ok_or_else(|| ParserError::Invalid(format!("Invalid currency, line {}: {}", i+1, line)))?
-                .to_string();
-
-            if split.next().is_some() {
-                return Err(ParserError::Invalid(format!(
-                    "Invalid line {}: {}",
-                    i + 1,
-                    line
-                )));
-            }
-
-            res.push(Transaction{
-                id,
-                account,
-                amount,
-                currency,
-            });
+    /// - [`ParserError::Record`] — если строка имеет неверный формат
+    ///   или поле не удалось распарсить; ошибка содержит номер строки,
+    ///   колонку и смещение в байтах.
+    fn reader<R: Read>(reader: R) -> TxtReader<R> {
+        TxtReader {
+            inner: BufReader::new(reader),
+            line: String::new(),
+            line_no: 0,
+            records: 0,
+            offset: 0,
         }
-
-        Ok(res)
     }
 
-    /// Записывает транзакции в текстовый поток.
-    ///
-    /// Каждая транзакция сериализуется в одну строку:
+    /// Создаёт писателя, сериализующего каждую транзакцию в одну строку:
     ///
     /// ```text
-    /// id account amount currency
+    /// id account amount currency [ключ=значение ...]
     /// ```
+    fn writer<W: Write>(writer: W) -> TxtWriter<W> {
+        TxtWriter { inner: writer }
+    }
+}
+
+/// Потоковый итератор транзакций из текстового потока.
+pub struct TxtReader<R: Read> {
+    inner: BufReader<R>,
+    line: String,
+    line_no: u64,
+    records: u64,
+    offset: u64,
+}
+
+impl<R: Read> Iterator for TxtReader<R> {
+    type Item = Result<Transaction, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            let len = match self.inner.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(len) => len,
+                Err(err) => return Some(Err(ParserError::Io(err))),
+            };
+
+            self.line_no += 1;
+            let byte = self.offset;
+            self.offset += len as u64;
+
+            let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            self.records += 1;
+            let position = Position {
+                record: Some(self.records),
+                line: Some(self.line_no),
+                column: None,
+                byte: Some(byte),
+            };
+
+            return Some(parse_line(line, position));
+        }
+    }
+}
+
+/// Поле строки после снятия кавычек и экранирования.
+struct Token {
+    /// Смещение начала поля в строке, в байтах.
+    offset: usize,
+    text: String,
+}
+
+/// Разбивает строку на поля, разделённые пробелами, до начала комментария.
+///
+/// При ошибке возвращает смещение места ошибки и её причину.
+fn tokens(line: &str) -> Result<Vec<Token>, (usize, String)> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut chars = line.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => tokens.extend(current.take()),
+            '#' if current.is_none() => break,
+            '"' => {
+                let token = current.get_or_insert_with(|| Token { offset: index, text: String::new() });
+                read_quoted(&mut chars, index, &mut token.text)?;
+            }
+            c => current.get_or_insert_with(|| Token { offset: index, text: String::new() }).text.push(c),
+        }
+    }
+    tokens.extend(current);
+
+    Ok(tokens)
+}
+
+/// Читает значение в кавычках после открывающей кавычки по смещению `open`,
+/// снимая экранирование.
+///
+/// При ошибке возвращает смещение места ошибки и её причину.
+pub(crate) fn read_quoted(chars: &mut CharIndices<'_>, open: usize, out: &mut String) -> Result<(), (usize, String)> {
+    loop {
+        match chars.next() {
+            None => return Err((open, "unterminated quote".to_string())),
+            Some((_, '"')) => return Ok(()),
+            Some((escape, '\\')) => out.push(match chars.next() {
+                Some((_, '"')) => '"',
+                Some((_, '\\')) => '\\',
+                Some((_, 'n')) => '\n',
+                Some((_, 'r')) => '\r',
+                Some((_, 't')) => '\t',
+                Some((_, other)) => return Err((escape, format!("invalid escape: \\{}", other))),
+                None => return Err((escape, "unterminated quote".to_string())),
+            }),
+            Some((_, c)) => out.push(c),
+        }
+    }
+}
+
+fn parse_line(line: &str, position: Position) -> Result<Transaction, ParserError> {
+    // Колонка считается в символах, смещение — в байтах от начала потока.
+    let error = |offset: usize, reason: String| {
+        RecordError::new("txt", reason)
+            .at(Position {
+                column: Some(line[..offset].chars().count() as u64 + 1),
+                byte: position.byte.map(|byte| byte + offset as u64),
+                ..position
+            })
+            .raw(line)
+    };
+    let tokens = tokens(line).map_err(|(offset, reason)| error(offset, reason))?;
+
+    let field = |index: usize, name: &'static str| {
+        tokens
+            .get(index)
+            .map(|token| (token.offset, token.text.as_str()))
+            .ok_or_else(|| ParserError::from(error(line.len(), "missing field".to_string()).field(name)))
+    };
+    let invalid = |offset: usize, name: &'static str, value: &str, reason: String| {
+        error(offset, reason).field(name).value(value)
+    };
+
+    let (offset, value) = field(0, "id")?;
+    let id = value.parse().map_err(|err| invalid(offset, "id", value, format!("Invalid id: {}", err)))?;
+
+    let (_, account) = field(1, "account")?;
+
+    let (offset, value) = field(3, "currency")?;
+    let currency: Currency = value.parse().map_err(|err: ParserError| invalid(offset, "currency", value, err.into_reason()))?;
+
+    let (offset, value) = field(2, "amount")?;
+    let amount = Amount::parse(value, currency.exponent())
+        .map_err(|err| invalid(offset, "amount", value, err.into_reason()))?;
+
+    let mut tx = Transaction::new(id, account, amount, currency);
+
+    for Token { offset, text } in &tokens[4..] {
+        let offset = *offset;
+        let Some((key, value)) = text.split_once('=') else {
+            return Err(error(offset, "expected key=value".to_string()).value(text).into());
+        };
+        let value_error = |err: ParserError, name: &'static str| {
+            invalid(offset + key.len() + 1, name, value, err.into_reason())
+        };
+
+        match key {
+            "booking_date" => tx.booking_date = Some(parse_date(value).map_err(|err| value_error(err, "booking_date"))?),
+            "value_date" => tx.value_date = Some(parse_date(value).map_err(|err| value_error(err, "value_date"))?),
+            "description" => tx.description = Some(value.to_string()),
+            "counterparty" => tx.counterparty = Some(value.to_string()),
+            "direction" => tx.direction = Some(value.parse().map_err(|err| value_error(err, "direction"))?),
+            "status" => tx.status = Some(value.parse().map_err(|err| value_error(err, "status"))?),
+            _ => return Err(error(offset, format!("Unknown field: {}", key)).value(text).into()),
+        }
+    }
+
+    Ok(tx)
+}
+
+/// Заключает значение в кавычки, если без них его нельзя прочитать обратно.
+fn quote(value: &str) -> Cow<'_, str> {
+    let plain = !value.is_empty()
+        && !value.starts_with('#')
+        && !value.contains(|c: char| c.is_whitespace() || c.is_control() || c == '"' || c == '\\');
+    if plain {
+        return Cow::Borrowed(value);
+    }
+
+    Cow::Owned(quoted(value))
+}
+
+/// Заключает значение в кавычки, экранируя специальные символы.
+pub(crate) fn quoted(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Инкрементальный писатель транзакций в текстовый поток.
+pub struct TxtWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> TransactionWriter for TxtWriter<W> {
+    /// Записывает транзакцию одной строкой, заключая значения в кавычки
+    /// там, где это необходимо.
     ///
     /// # Ошибки
     ///
-    /// Возвращает [`ParserError::Invalid`], если произошла ошибка записи.
-    fn write<W: Write>(mut writer: W, transaction: &[Transaction]) -> Result<(), ParserError> {
-        for tx in transaction {
-            writeln!(
-                writer,
-                "{} {} {} {}",
-                tx.id, tx.account, tx.amount, tx.currency,
-            ).map_err(|_| ParserError::Invalid(format!(
-                "Invalid transaction {}", tx.id
-            )))?;
+    /// Возвращает [`ParserError::Io`], если произошла ошибка записи.
+    fn write(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let amount = tx.money()?.amount();
+        let mut line = format!("{} {} {} {}", tx.id, quote(&tx.account), amount, tx.currency);
+
+        let optional = [
+            ("booking_date", tx.booking_date.map(|date| date.to_string())),
+            ("value_date", tx.value_date.map(|date| date.to_string())),
+            ("description", tx.description.clone()),
+            ("counterparty", tx.counterparty.clone()),
+            ("direction", tx.direction.map(|direction| direction.to_string())),
+            ("status", tx.status.map(|status| status.to_string())),
+        ];
+        for (key, value) in optional {
+            let Some(value) = value else { continue };
+            line.push_str(&format!(" {}={}", key, quote(&value)));
         }
 
-        Ok(())
+        writeln!(self.inner, "{}", line).map_err(ParserError::Io)
+    }
+
+    fn finish(&mut self) -> Result<(), ParserError> {
+        self.inner.flush().map_err(ParserError::Io)
     }
-}
\ No newline at end of file
+}
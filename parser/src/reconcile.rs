@@ -0,0 +1,205 @@
+//! Сверка двух списков транзакций по ключу.
+//!
+//! В отличие от [`crate::diff`], порядок записей не важен: записи
+//! сопоставляются по значению ключа ([`MatchKey`]) — по умолчанию по `id`,
+//! либо по составному ключу из нескольких полей, например
+//! `account+amount+currency`. Сопоставленные пары сравниваются по всем
+//! полям, записи без пары попадают в [`Diff::removed`] и [`Diff::added`],
+//! а повторяющиеся ключи — в [`Reconciliation::duplicates`].
+//!
+//! Если ключ повторяется, записи с этим ключом сопоставляются в порядке
+//! следования, а оставшиеся считаются несопоставленными.
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use crate::csv::Column;
+use crate::diff::{self, Change, Diff, Entry};
+use crate::{ParserError, Transaction};
+
+/// Поля, по которым сопоставляются записи.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchKey {
+    columns: Vec<Column>,
+}
+
+impl MatchKey {
+    /// Ключ из одного поля `id`.
+    pub fn id() -> Self {
+        Self { columns: vec![Column::Id] }
+    }
+
+    /// Составной ключ из указанных полей.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`], если список полей пуст
+    /// или поле повторяется.
+    pub fn new(columns: impl IntoIterator<Item = Column>) -> Result<Self, ParserError> {
+        let mut key = Self { columns: Vec::new() };
+        for column in columns {
+            if key.columns.contains(&column) {
+                return Err(ParserError::Invalid(format!("Duplicate key field: {}", column.name())));
+            }
+            key.columns.push(column);
+        }
+
+        if key.columns.is_empty() {
+            return Err(ParserError::Invalid("Match key has no fields".to_string()));
+        }
+        Ok(key)
+    }
+
+    /// Поля ключа.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Значение ключа для транзакции.
+    fn value(&self, tx: &Transaction) -> Vec<Option<String>> {
+        self.columns.iter().map(|column| diff::value(*column, tx)).collect()
+    }
+}
+
+impl Default for MatchKey {
+    fn default() -> Self {
+        Self::id()
+    }
+}
+
+impl FromStr for MatchKey {
+    type Err = ParserError;
+
+    /// Разбирает ключ вида `account+amount+currency`; допускаются
+    /// синонимы имён полей (см. [`Column`]) и разделитель `,`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let columns = s
+            .split(['+', ','])
+            .map(|name| Column::from_name(name).ok_or_else(|| ParserError::Invalid(format!("Unknown key field: {}", name.trim()))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(columns)
+    }
+}
+
+impl std::fmt::Display for MatchKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, column) in self.columns.iter().enumerate() {
+            if index > 0 {
+                f.write_str("+")?;
+            }
+            f.write_str(column.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// Сторона сверки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// Первый список.
+    Left,
+    /// Второй список.
+    Right,
+}
+
+/// Записи одного списка с одинаковым ключом.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate<'a> {
+    /// Список, в котором повторяется ключ.
+    pub side: Side,
+    /// Значение ключа; поля разделены `|`, незаполненное поле — пустая строка.
+    pub key: String,
+    /// Записи с этим ключом в порядке следования.
+    pub entries: Vec<Entry<'a>>,
+}
+
+/// Результат сверки.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reconciliation<'a> {
+    /// Сопоставленные пары с отличиями и записи без пары.
+    pub diff: Diff<'a>,
+    /// Повторяющиеся ключи в каждом из списков.
+    pub duplicates: Vec<Duplicate<'a>>,
+}
+
+impl Reconciliation<'_> {
+    /// Сошлись ли списки полностью: все записи сопоставлены,
+    /// пары совпадают и ключи не повторяются.
+    pub fn is_clean(&self) -> bool {
+        self.diff.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+/// Сверяет два списка транзакций по ключу `key`.
+pub fn reconcile<'a>(left: &'a [Transaction], right: &'a [Transaction], key: &MatchKey) -> Reconciliation<'a> {
+    let mut result = Reconciliation::default();
+
+    let left_groups = groups(left, key);
+    let mut right_groups = groups(right, key);
+
+    for (value, indices) in &left_groups {
+        let candidates = right_groups.remove(value).unwrap_or_default();
+
+        for (position, &index) in indices.iter().enumerate() {
+            let entry = Entry { index: index + 1, transaction: &left[index] };
+            let Some(&other) = candidates.get(position) else {
+                result.diff.removed.push(entry);
+                continue;
+            };
+
+            let other = Entry { index: other + 1, transaction: &right[other] };
+            let fields = diff::field_diffs(entry.transaction, other.transaction);
+            if fields.is_empty() {
+                result.diff.unchanged += 1;
+            } else {
+                result.diff.changed.push(Change { left: entry, right: other, fields });
+            }
+        }
+
+        let extra = candidates.get(indices.len()..).unwrap_or_default();
+        result.diff.added.extend(extra.iter().map(|&index| Entry { index: index + 1, transaction: &right[index] }));
+        duplicate(&mut result.duplicates, Side::Right, value, &candidates, right);
+        duplicate(&mut result.duplicates, Side::Left, value, indices, left);
+    }
+
+    for (value, indices) in &right_groups {
+        result.diff.added.extend(indices.iter().map(|&index| Entry { index: index + 1, transaction: &right[index] }));
+        duplicate(&mut result.duplicates, Side::Right, value, indices, right);
+    }
+
+    result.diff.removed.sort_by_key(|entry| entry.index);
+    result.diff.added.sort_by_key(|entry| entry.index);
+    result.diff.changed.sort_by_key(|change| change.left.index);
+    result.duplicates.sort_by_key(|duplicate| (duplicate.side == Side::Right, duplicate.entries[0].index));
+
+    result
+}
+
+/// Номера записей (с 0) для каждого значения ключа в порядке следования.
+fn groups(transactions: &[Transaction], key: &MatchKey) -> HashMap<Vec<Option<String>>, Vec<usize>> {
+    let mut groups: HashMap<_, Vec<usize>> = HashMap::new();
+    for (index, tx) in transactions.iter().enumerate() {
+        groups.entry(key.value(tx)).or_default().push(index);
+    }
+
+    groups
+}
+
+/// Добавляет в отчёт повторяющийся ключ, если записей с ним больше одной.
+fn duplicate<'a>(
+    duplicates: &mut Vec<Duplicate<'a>>,
+    side: Side,
+    value: &[Option<String>],
+    indices: &[usize],
+    transactions: &'a [Transaction],
+) {
+    if indices.len() < 2 {
+        return;
+    }
+
+    duplicates.push(Duplicate {
+        side,
+        key: value.iter().map(|part| part.as_deref().unwrap_or_default()).collect::<Vec<_>>().join("|"),
+        entries: indices.iter().map(|&index| Entry { index: index + 1, transaction: &transactions[index] }).collect(),
+    });
+}
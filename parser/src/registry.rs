@@ -0,0 +1,226 @@
+//! Реестр форматов с диспетчеризацией во время выполнения.
+//!
+//! Трейт [`Format`] использует статические обобщённые методы и не может
+//! быть трейт-объектом. Модуль вводит объектно-безопасный трейт
+//! [`DynFormat`] и реестр [`Registry`], который сопоставляет имена,
+//! расширения файлов и MIME-типы с реализациями форматов.
+//!
+//! Внешний формат (например, [`crate::parquet::Parquet`]) достаточно
+//! реализовать как [`DynFormat`] и зарегистрировать через
+//! [`Registry::register`]. Встроенный формат добавляется вариантом
+//! [`FormatKind`], строкой в таблице [`FORMATS`] и, если его можно узнать
+//! по содержимому, правилом в [`FormatKind::sniff`]; имя, расширения и
+//! конструкторы больше нигде не перечисляются.
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use crate::detect;
+use crate::{Format, FormatKind, ParserError, Sniffed, Transaction, TransactionWriter, binary, camt, csv, json, kv, mt940, ofx, txt};
+
+/// Потоковый итератор транзакций с динамической диспетчеризацией.
+pub type TransactionStream<'a> = Box<dyn Iterator<Item = Result<Transaction, ParserError>> + 'a>;
+
+/// Объектно-безопасный интерфейс формата.
+pub trait DynFormat {
+    /// Короткое имя формата (например, `csv`).
+    fn name(&self) -> &'static str;
+
+    /// Расширения файлов без точки, в нижнем регистре.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// MIME-типы формата.
+    fn mime_types(&self) -> &'static [&'static str];
+
+    /// Проверяет, похожи ли первые байты потока на данный формат.
+    ///
+    /// По умолчанию формат не распознаётся по содержимому.
+    fn sniff(&self, _prefix: &[u8]) -> bool {
+        false
+    }
+
+    /// Создаёт потоковый итератор транзакций.
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionStream<'a>;
+
+    /// Создаёт инкрементального писателя транзакций.
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a>;
+}
+
+/// Описание встроенного формата.
+///
+/// Таблица [`FORMATS`] — единственное место, где перечислены имена,
+/// расширения, MIME-типы и конструкторы встроенных форматов: по ней
+/// работают [`FormatKind::as_str`], разбор имени формата, определение
+/// по расширению и [`DynFormat`] для [`FormatKind`].
+pub(crate) struct Descriptor {
+    /// Вариант перечисления.
+    pub kind: FormatKind,
+    /// Короткое имя формата.
+    pub name: &'static str,
+    /// Расширения без точки, в нижнем регистре.
+    pub extensions: &'static [&'static str],
+    /// MIME-типы.
+    pub mime_types: &'static [&'static str],
+    /// Конструктор потокового итератора.
+    pub reader: for<'a> fn(Box<dyn Read + 'a>) -> TransactionStream<'a>,
+    /// Конструктор писателя.
+    pub writer: for<'a> fn(Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a>,
+}
+
+/// Встроенные форматы в порядке [`FormatKind::ALL`].
+pub(crate) const FORMATS: [Descriptor; 9] = [
+    descriptor::<binary::BinParser>(FormatKind::Bin, "bin", &["bin"], &["application/octet-stream"]),
+    descriptor::<csv::Csv>(FormatKind::Csv, "csv", &["csv"], &["text/csv"]),
+    descriptor::<txt::TxtParser>(FormatKind::Txt, "txt", &["txt"], &["text/plain"]),
+    descriptor::<kv::KvParser>(FormatKind::Kv, "kv", &["kv"], &[]),
+    descriptor::<json::JsonParser>(FormatKind::Json, "json", &["json"], &["application/json"]),
+    descriptor::<json::NdjsonParser>(FormatKind::Ndjson, "ndjson", &["ndjson", "jsonl"], &["application/x-ndjson"]),
+    descriptor::<camt::CamtParser>(FormatKind::Camt, "camt", &["xml"], &["application/xml"]),
+    descriptor::<mt940::Mt940Parser>(FormatKind::Mt940, "mt940", &["mt940", "sta"], &[]),
+    descriptor::<ofx::OfxParser>(FormatKind::Ofx, "ofx", &["ofx", "qfx"], &["application/x-ofx"]),
+];
+
+const fn descriptor<F: Format + 'static>(
+    kind: FormatKind,
+    name: &'static str,
+    extensions: &'static [&'static str],
+    mime_types: &'static [&'static str],
+) -> Descriptor {
+    Descriptor { kind, name, extensions, mime_types, reader: stream::<F>, writer: writer::<F> }
+}
+
+fn stream<'a, F: Format + 'static>(reader: Box<dyn Read + 'a>) -> TransactionStream<'a> {
+    Box::new(F::reader(reader))
+}
+
+fn writer<'a, F: Format + 'static>(writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+    Box::new(F::writer(writer))
+}
+
+impl DynFormat for FormatKind {
+    fn name(&self) -> &'static str {
+        self.descriptor().name
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.descriptor().extensions
+    }
+
+    fn mime_types(&self) -> &'static [&'static str] {
+        self.descriptor().mime_types
+    }
+
+    fn sniff(&self, prefix: &[u8]) -> bool {
+        FormatKind::sniff(prefix) == Some(*self)
+    }
+
+    fn reader<'a>(&self, reader: Box<dyn Read + 'a>) -> TransactionStream<'a> {
+        (self.descriptor().reader)(reader)
+    }
+
+    fn writer<'a>(&self, writer: Box<dyn Write + 'a>) -> Box<dyn TransactionWriter + 'a> {
+        (self.descriptor().writer)(writer)
+    }
+}
+
+/// Реестр доступных форматов.
+///
+/// [`Registry::default`] содержит все встроенные форматы ([`FormatKind::ALL`]),
+/// а с feature `parquet` — ещё и [`crate::parquet::Parquet`].
+/// Форматы опрашиваются в порядке регистрации.
+pub struct Registry {
+    formats: Vec<Box<dyn DynFormat>>,
+}
+
+impl Registry {
+    /// Создаёт пустой реестр.
+    pub fn new() -> Self {
+        Self { formats: Vec::new() }
+    }
+
+    /// Регистрирует формат.
+    ///
+    /// Формат с уже зарегистрированным именем заменяет прежний.
+    pub fn register(&mut self, format: Box<dyn DynFormat>) {
+        match self.formats.iter().position(|f| f.name() == format.name()) {
+            Some(index) => self.formats[index] = format,
+            None => self.formats.push(format),
+        }
+    }
+
+    /// Имена зарегистрированных форматов в порядке регистрации.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.formats.iter().map(|f| f.name()).collect()
+    }
+
+    /// Ищет формат по имени.
+    pub fn by_name(&self, name: &str) -> Option<&dyn DynFormat> {
+        self.find(|f| f.name() == name)
+    }
+
+    /// Ищет формат по расширению файла (без учёта регистра, с точкой или без).
+    pub fn by_extension(&self, extension: &str) -> Option<&dyn DynFormat> {
+        let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+        self.find(|f| f.extensions().contains(&extension.as_str()))
+    }
+
+    /// Ищет формат по MIME-типу, игнорируя параметры вида `; charset=utf-8`.
+    pub fn by_mime(&self, mime: &str) -> Option<&dyn DynFormat> {
+        let mime = mime.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        self.find(|f| f.mime_types().contains(&mime.as_str()))
+    }
+
+    /// Ищет формат по расширению пути.
+    pub fn for_path(&self, path: &Path) -> Option<&dyn DynFormat> {
+        self.by_extension(path.extension()?.to_str()?)
+    }
+
+    /// Ищет формат по имени.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`] со списком доступных форматов,
+    /// если формат не зарегистрирован.
+    pub fn get(&self, name: &str) -> Result<&dyn DynFormat, ParserError> {
+        self.by_name(name).ok_or_else(|| ParserError::Invalid(format!(
+            "Unknown format: {} (supported: {})",
+            name,
+            self.names().join(", ")
+        )))
+    }
+
+    /// Определяет формат потока по содержимому и, при необходимости, по пути к файлу.
+    ///
+    /// Аналог [`crate::detect`] для всех зарегистрированных форматов.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает:
+    /// - [`ParserError::Io`] — при ошибке чтения потока;
+    /// - [`ParserError::Invalid`] — если формат определить не удалось.
+    pub fn detect<R: Read>(&self, path: Option<&Path>, mut reader: R) -> Result<(&dyn DynFormat, Sniffed<R>), ParserError> {
+        let prefix = detect::read_prefix(&mut reader)?;
+
+        let format = self.find(|f| !prefix.is_empty() && f.sniff(&prefix))
+            .or_else(|| path.and_then(|path| self.for_path(path)))
+            .ok_or_else(|| detect::undetected(path))?;
+
+        Ok((format, Cursor::new(prefix).chain(reader)))
+    }
+
+    fn find(&self, predicate: impl Fn(&dyn DynFormat) -> bool) -> Option<&dyn DynFormat> {
+        self.formats.iter().map(|f| f.as_ref()).find(|f| predicate(*f))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        // Parquet проверяется первым: иначе его бинарное содержимое примет за своё BIN.
+        #[cfg(feature = "parquet")]
+        registry.register(Box::new(crate::parquet::Parquet));
+        for kind in FormatKind::ALL {
+            registry.register(Box::new(kind));
+        }
+
+        registry
+    }
+}
@@ -0,0 +1,403 @@
+//! Машиночитаемые отчёты о сверке (JSON, CSV, JUnit XML).
+//!
+//! [`Report`] собирается из результата сравнения или сверки
+//! ([`Report::from_reconciliation`], [`Report::from_fuzzy`]) и содержит
+//! итоговые счётчики ([`Summary`]), по одной записи [`Mismatch`] на
+//! каждое расхождение и, для нечёткой сверки, по записи [`Match`] на каждое
+//! найденное соответствие. Отчёт пишется в одном из форматов [`ReportFormat`]:
+//!
+//! - `json` — объект `{"identical", "summary", "mismatches", "matches"}`
+//!   (`matches` — только для нечёткой сверки);
+//! - `csv` — по строке на каждое расхождение, а для изменённых записей —
+//!   на каждое отличающееся поле, затем по строке `match` или `near_match`
+//!   с уверенностью и расхождением на каждое соответствие; номера нескольких
+//!   записей разделены `;`;
+//! - `junit` — набор тестов `comparer`: по тесту с `<failure>` на каждое
+//!   расхождение и успешные тесты для совпавших записей — по одному на
+//!   соответствие нечёткой сверки или один общий.
+use std::fmt::Formatter;
+use std::io::Write;
+use std::str::FromStr;
+use quick_xml::escape::escape;
+use serde::Serialize;
+use crate::Amount;
+use crate::diff::FieldDiff;
+use crate::fuzzy::FuzzyReconciliation;
+use crate::reconcile::{Reconciliation, Side};
+use crate::ParserError;
+
+/// Заголовок отчёта в формате CSV.
+const CSV_HEADER: [&str; 9] = [
+    "kind", "file1_records", "file2_records", "key", "field", "file1_value", "file2_value", "confidence", "difference",
+];
+
+/// Формат машиночитаемого отчёта.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportFormat {
+    /// JSON-объект со сводкой и списком расхождений.
+    Json,
+    /// CSV, строка на каждое расхождение.
+    Csv,
+    /// JUnit XML для CI.
+    Junit,
+}
+
+impl ReportFormat {
+    /// Все форматы отчёта.
+    pub const ALL: [ReportFormat; 3] = [ReportFormat::Json, ReportFormat::Csv, ReportFormat::Junit];
+
+    /// Имя формата, используемое в CLI (`json`, `csv`, `junit`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+            ReportFormat::Junit => "junit",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            "junit" | "xml" => Ok(ReportFormat::Junit),
+            _ => Err(ParserError::Invalid(format!("Unknown report format: {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Вид расхождения.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// Запись есть только в первом файле.
+    OnlyInFile1,
+    /// Запись есть только во втором файле.
+    OnlyInFile2,
+    /// Сопоставленные записи различаются по полям.
+    Changed,
+    /// Ключ повторяется в одном из файлов.
+    DuplicateKey,
+}
+
+impl MismatchKind {
+    /// Имя вида в отчёте (`only_in_file1`, `changed`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MismatchKind::OnlyInFile1 => "only_in_file1",
+            MismatchKind::OnlyInFile2 => "only_in_file2",
+            MismatchKind::Changed => "changed",
+            MismatchKind::DuplicateKey => "duplicate_key",
+        }
+    }
+}
+
+/// Отличие одного поля в отчёте.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldMismatch {
+    /// Имя поля (`amount`, `booking_date`, ...).
+    pub field: &'static str,
+    /// Значение в первом файле.
+    pub file1: Option<String>,
+    /// Значение во втором файле.
+    pub file2: Option<String>,
+}
+
+impl From<&FieldDiff> for FieldMismatch {
+    fn from(diff: &FieldDiff) -> Self {
+        Self { field: diff.field.name(), file1: diff.left.clone(), file2: diff.right.clone() }
+    }
+}
+
+/// Одно расхождение.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+    /// Вид расхождения.
+    pub kind: MismatchKind,
+    /// Номера записей первого файла, начиная с 1.
+    pub file1_records: Vec<usize>,
+    /// Номера записей второго файла, начиная с 1.
+    pub file2_records: Vec<usize>,
+    /// Значение повторяющегося ключа.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Отличия по полям для [`MismatchKind::Changed`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldMismatch>,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let records = |records: &[usize]| records.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+        match self.kind {
+            MismatchKind::OnlyInFile1 => write!(f, "Only in file1, record {}", records(&self.file1_records)),
+            MismatchKind::OnlyInFile2 => write!(f, "Only in file2, record {}", records(&self.file2_records)),
+            MismatchKind::DuplicateKey => {
+                let (file, entries) = match self.file1_records.is_empty() {
+                    true => ("file2", &self.file2_records),
+                    false => ("file1", &self.file1_records),
+                };
+                write!(f, "Duplicate key [{}] in {}, records {}", self.key.as_deref().unwrap_or_default(), file, records(entries))
+            }
+            MismatchKind::Changed => {
+                write!(f, "Changed record {} (file2 record {}):", records(&self.file1_records), records(&self.file2_records))?;
+                for (index, field) in self.fields.iter().enumerate() {
+                    let separator = if index == 0 { " " } else { "; " };
+                    write!(f, "{}{}: {} -> {}", separator, field.field, shown(&field.file1), shown(&field.file2))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Соответствие, найденное нечёткой сверкой.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Match {
+    /// Номера записей учёта (первого файла), начиная с 1.
+    pub file1_records: Vec<usize>,
+    /// Номер строки выписки (второго файла), начиная с 1.
+    pub file2_records: Vec<usize>,
+    /// Уверенность совпадения, от 0 до 1.
+    pub confidence: f64,
+    /// Сумма записей учёта минус сумма строки выписки.
+    pub difference: Amount,
+}
+
+impl Match {
+    /// Совпали ли суммы не точно, а в пределах допуска.
+    pub fn is_near(&self) -> bool {
+        self.difference.minor_units() != 0
+    }
+
+    /// Вид соответствия в отчёте: `match` или `near_match`.
+    pub fn kind(&self) -> &'static str {
+        if self.is_near() { "near_match" } else { "match" }
+    }
+}
+
+impl std::fmt::Display for Match {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let records = |records: &[usize]| records.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+        let noun = if self.file1_records.len() == 1 { "record" } else { "records" };
+        write!(
+            f,
+            "Matched file1 {} {} -> file2 record {} (confidence {:.2}, difference {})",
+            noun, records(&self.file1_records), records(&self.file2_records), self.confidence, self.difference
+        )
+    }
+}
+
+/// Итоговые счётчики сверки.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    /// Совпавшие пары записей; для нечёткой сверки — соответствия
+    /// с точным совпадением сумм.
+    pub matched: usize,
+    /// Соответствия нечёткой сверки с расхождением сумм в пределах допуска.
+    pub near_matches: usize,
+    /// Сопоставленные пары с отличиями.
+    pub changed: usize,
+    /// Записи только в первом файле.
+    pub only_in_file1: usize,
+    /// Записи только во втором файле.
+    pub only_in_file2: usize,
+    /// Повторяющиеся ключи.
+    pub duplicate_keys: usize,
+}
+
+/// Отчёт о сверке двух файлов.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    /// Совпали ли файлы полностью.
+    pub identical: bool,
+    /// Итоговые счётчики.
+    pub summary: Summary,
+    /// Расхождения: повторяющиеся ключи, записи без пары, изменённые записи.
+    pub mismatches: Vec<Mismatch>,
+    /// Соответствия нечёткой сверки в порядке строк выписки.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<Match>,
+}
+
+impl Report {
+    /// Строит отчёт по результату сравнения по позиции или по ключу.
+    pub fn from_reconciliation(reconciliation: &Reconciliation) -> Self {
+        let diff = &reconciliation.diff;
+        let mut mismatches = Vec::new();
+
+        for duplicate in &reconciliation.duplicates {
+            let records = duplicate.entries.iter().map(|entry| entry.index).collect();
+            let (file1_records, file2_records) = match duplicate.side {
+                Side::Left => (records, Vec::new()),
+                Side::Right => (Vec::new(), records),
+            };
+            mismatches.push(Mismatch {
+                kind: MismatchKind::DuplicateKey,
+                file1_records,
+                file2_records,
+                key: Some(duplicate.key.clone()),
+                fields: Vec::new(),
+            });
+        }
+        mismatches.extend(diff.removed.iter().map(|entry| only(MismatchKind::OnlyInFile1, entry.index)));
+        mismatches.extend(diff.added.iter().map(|entry| only(MismatchKind::OnlyInFile2, entry.index)));
+        mismatches.extend(diff.changed.iter().map(|change| Mismatch {
+            kind: MismatchKind::Changed,
+            file1_records: vec![change.left.index],
+            file2_records: vec![change.right.index],
+            key: None,
+            fields: change.fields.iter().map(FieldMismatch::from).collect(),
+        }));
+
+        Self {
+            identical: reconciliation.is_clean(),
+            summary: Summary {
+                matched: diff.unchanged,
+                changed: diff.changed.len(),
+                only_in_file1: diff.removed.len(),
+                only_in_file2: diff.added.len(),
+                duplicate_keys: reconciliation.duplicates.len(),
+                ..Summary::default()
+            },
+            mismatches,
+            matches: Vec::new(),
+        }
+    }
+
+    /// Строит отчёт по результату нечёткой сверки.
+    ///
+    /// Соответствия в пределах допусков не считаются расхождениями:
+    /// расхождения — записи без пары, а соответствия с номерами записей,
+    /// уверенностью и расхождением сумм попадают в [`Report::matches`].
+    pub fn from_fuzzy(reconciliation: &FuzzyReconciliation) -> Self {
+        let mut mismatches = Vec::new();
+        mismatches.extend(reconciliation.unmatched_ledger.iter().map(|entry| only(MismatchKind::OnlyInFile1, entry.index)));
+        mismatches.extend(reconciliation.unmatched_statement.iter().map(|entry| only(MismatchKind::OnlyInFile2, entry.index)));
+
+        let matches: Vec<Match> = reconciliation.matches.iter().map(|found| Match {
+            file1_records: found.ledger.iter().map(|entry| entry.index).collect(),
+            file2_records: vec![found.statement.index],
+            confidence: found.confidence,
+            difference: found.difference,
+        }).collect();
+        let near_matches = matches.iter().filter(|found| found.is_near()).count();
+
+        Self {
+            identical: reconciliation.is_clean(),
+            summary: Summary {
+                matched: matches.len() - near_matches,
+                near_matches,
+                only_in_file1: reconciliation.unmatched_ledger.len(),
+                only_in_file2: reconciliation.unmatched_statement.len(),
+                ..Summary::default()
+            },
+            mismatches,
+            matches,
+        }
+    }
+
+    /// Пишет отчёт в формате `format`.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Io`] при ошибке записи и
+    /// [`ParserError::Csv`] при ошибке записи CSV.
+    pub fn write<W: Write>(&self, format: ReportFormat, mut writer: W) -> Result<(), ParserError> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, self).map_err(|err| ParserError::Io(err.into()))?;
+                writeln!(writer).map_err(ParserError::Io)
+            }
+            ReportFormat::Csv => self.write_csv(writer),
+            ReportFormat::Junit => self.write_junit(writer).map_err(ParserError::Io),
+        }
+    }
+
+    fn write_csv<W: Write>(&self, writer: W) -> Result<(), ParserError> {
+        let join = |records: &[usize]| records.iter().map(usize::to_string).collect::<Vec<_>>().join(";");
+
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record(CSV_HEADER).map_err(ParserError::Csv)?;
+        for mismatch in &self.mismatches {
+            let file1 = join(&mismatch.file1_records);
+            let file2 = join(&mismatch.file2_records);
+            let key = mismatch.key.as_deref().unwrap_or_default();
+
+            if mismatch.fields.is_empty() {
+                csv.write_record([mismatch.kind.as_str(), &file1, &file2, key, "", "", "", "", ""]).map_err(ParserError::Csv)?;
+            }
+            for field in &mismatch.fields {
+                let left = field.file1.as_deref().unwrap_or_default();
+                let right = field.file2.as_deref().unwrap_or_default();
+                csv.write_record([mismatch.kind.as_str(), &file1, &file2, key, field.field, left, right, "", ""])
+                    .map_err(ParserError::Csv)?;
+            }
+        }
+        for found in &self.matches {
+            let confidence = format!("{:.4}", found.confidence);
+            let difference = found.difference.to_string();
+            let (file1, file2) = (join(&found.file1_records), join(&found.file2_records));
+            csv.write_record([found.kind(), &file1, &file2, "", "", "", "", &confidence, &difference])
+                .map_err(ParserError::Csv)?;
+        }
+        csv.flush().map_err(ParserError::Io)
+    }
+
+    fn write_junit<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        // Соответствия нечёткой сверки — отдельные тесты, иначе один общий.
+        let passed = self.matches.is_empty() && self.summary.matched > 0;
+        let tests = self.mismatches.len() + self.matches.len() + usize::from(passed);
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(writer, "<testsuites name=\"comparer\" tests=\"{}\" failures=\"{}\">", tests, self.mismatches.len())?;
+        writeln!(writer, "  <testsuite name=\"comparer\" tests=\"{}\" failures=\"{}\">", tests, self.mismatches.len())?;
+        if passed {
+            writeln!(writer, "    <testcase classname=\"comparer\" name=\"{} matching records\"/>", self.summary.matched)?;
+        }
+        for found in &self.matches {
+            let name = format!("file2 record {}", found.file2_records[0]);
+            let message = found.to_string();
+            writeln!(writer, "    <testcase classname=\"comparer.{}\" name=\"{}\">", found.kind(), escape(name.as_str()))?;
+            writeln!(writer, "      <system-out>{}</system-out>", escape(message.as_str()))?;
+            writeln!(writer, "    </testcase>")?;
+        }
+        for mismatch in &self.mismatches {
+            let name = match mismatch.kind {
+                MismatchKind::OnlyInFile2 => format!("file2 record {}", mismatch.file2_records[0]),
+                MismatchKind::DuplicateKey => format!("duplicate key [{}]", mismatch.key.as_deref().unwrap_or_default()),
+                MismatchKind::OnlyInFile1 | MismatchKind::Changed => format!("file1 record {}", mismatch.file1_records[0]),
+            };
+            let message = mismatch.to_string();
+            writeln!(writer, "    <testcase classname=\"comparer.{}\" name=\"{}\">", mismatch.kind.as_str(), escape(name.as_str()))?;
+            writeln!(writer, "      <failure type=\"{}\" message=\"{}\"/>", mismatch.kind.as_str(), escape(message.as_str()))?;
+            writeln!(writer, "    </testcase>")?;
+        }
+        writeln!(writer, "  </testsuite>")?;
+        writeln!(writer, "</testsuites>")
+    }
+}
+
+fn only(kind: MismatchKind, index: usize) -> Mismatch {
+    let (file1_records, file2_records) = match kind {
+        MismatchKind::OnlyInFile2 => (Vec::new(), vec![index]),
+        _ => (vec![index], Vec::new()),
+    };
+    Mismatch { kind, file1_records, file2_records, key: None, fields: Vec::new() }
+}
+
+fn shown(value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!("{:?}", value),
+        None => "<none>".to_string(),
+    }
+}
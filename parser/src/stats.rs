@@ -0,0 +1,77 @@
+//! Сводная статистика по списку транзакций.
+//!
+//! [`Stats`] накапливает количество записей, счета, период и обороты
+//! по каждой валюте; транзакции добавляются по одной ([`Stats::add`]),
+//! поэтому статистику можно собрать по потоку, не загружая файл в память.
+use std::collections::{BTreeMap, BTreeSet};
+use chrono::NaiveDate;
+use crate::{Amount, Currency, ParserError, Transaction};
+
+/// Обороты в одной валюте.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrencyStats {
+    /// Количество транзакций.
+    pub count: u64,
+    /// Сумма поступлений (положительных сумм).
+    pub credits: Amount,
+    /// Сумма списаний (отрицательных сумм).
+    pub debits: Amount,
+}
+
+impl CurrencyStats {
+    fn new(currency: Currency) -> Self {
+        let zero = Amount::from_minor(0, currency.exponent());
+        Self { count: 0, credits: zero, debits: zero }
+    }
+
+    /// Итоговое изменение: поступления плюс списания.
+    pub fn net(&self) -> Option<Amount> {
+        self.credits.checked_add(&self.debits)
+    }
+}
+
+/// Статистика по транзакциям.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Количество транзакций.
+    pub count: u64,
+    /// Счета в алфавитном порядке.
+    pub accounts: BTreeSet<String>,
+    /// Самая ранняя дата проводки (или валютирования).
+    pub first_date: Option<NaiveDate>,
+    /// Самая поздняя дата проводки (или валютирования).
+    pub last_date: Option<NaiveDate>,
+    /// Обороты по валютам в порядке кодов.
+    pub currencies: BTreeMap<Currency, CurrencyStats>,
+}
+
+impl Stats {
+    /// Создаёт пустую статистику.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Учитывает транзакцию.
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParserError::Invalid`] при переполнении оборота.
+    pub fn add(&mut self, tx: &Transaction) -> Result<(), ParserError> {
+        let totals = self.currencies.entry(tx.currency).or_insert_with(|| CurrencyStats::new(tx.currency));
+        let total = if tx.amount.minor_units() < 0 { &mut totals.debits } else { &mut totals.credits };
+        *total = total
+            .checked_add(&tx.amount)
+            .ok_or_else(|| ParserError::Invalid(format!("Overflow in {} total for transaction {}", tx.currency, tx.id)))?;
+        totals.count += 1;
+
+        self.count += 1;
+        if !self.accounts.contains(&tx.account) {
+            self.accounts.insert(tx.account.clone());
+        }
+        if let Some(date) = tx.booking_date.or(tx.value_date) {
+            self.first_date = Some(self.first_date.map_or(date, |first| first.min(date)));
+            self.last_date = Some(self.last_date.map_or(date, |last| last.max(date)));
+        }
+        Ok(())
+    }
+}
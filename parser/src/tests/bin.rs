@@ -5,12 +5,7 @@ mod tests {
 
     #[test]
     fn csv_round() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -25,12 +20,7 @@ mod tests {
 
     #[test]
     fn csv_round_ne() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -39,13 +29,116 @@ mod tests {
         buf.set_position(0);
 
         let mut parsed = binary::BinParser::read(&mut buf).expect("csv read failed");
-        parsed.push(Transaction{
-            id: 0,
-            account: "".to_string(),
-            amount: 0,
-            currency: "".to_string(),
-        });
+        parsed.push(Transaction::new(0, "", Amount::from_minor(0, 2), "EUR".parse().unwrap()));
 
         assert_ne!(data, parsed);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn stream_round() {
+        let data = vec![
+            Transaction::new(1, "ACC1", Amount::from_minor(100, 2), "USD".parse().unwrap()),
+            Transaction::new(2, "ACC2", Amount::from_minor(-50, 2), "EUR".parse().unwrap()),
+        ];
+
+        let mut buf = Vec::new();
+        let mut writer = binary::BinParser::writer(&mut buf);
+        for tx in &data {
+            writer.write(tx).expect("bin write failed");
+        }
+        writer.finish().expect("bin finish failed");
+
+        let mut reader = binary::BinParser::reader(buf.as_slice());
+        assert_eq!(reader.next().unwrap().expect("bin read failed"), data[0]);
+        assert_eq!(reader.next().unwrap().expect("bin read failed"), data[1]);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn legacy_string_currency() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&(-1250i64).to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"ACC");
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"EUR");
+
+        let parsed = binary::BinParser::read(data.as_slice()).expect("bin read failed");
+
+        assert_eq!(parsed, vec![Transaction::new(7, "ACC", Amount::from_minor(-1250, 2), "EUR".parse().unwrap())]);
+    }
+
+    fn encoded(count: usize) -> Vec<u8> {
+        let data: Vec<_> = (0..count as u64)
+            .map(|id| Transaction::new(id, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap()))
+            .collect();
+
+        let mut buf = Vec::new();
+        binary::BinParser::write(&mut buf, &data).expect("bin write failed");
+        buf
+    }
+
+    #[test]
+    fn many_blocks_stream() {
+        let mut buf = Vec::new();
+        let mut writer = binary::BinParser::writer(&mut buf);
+        for id in 0..3000 {
+            let tx = Transaction::new(id, "ACC", Amount::from_minor(1, 2), "USD".parse().unwrap());
+            writer.write(&tx).expect("bin write failed");
+        }
+        writer.finish().expect("bin finish failed");
+
+        let parsed = binary::BinParser::read(buf.as_slice()).expect("bin read failed");
+        assert_eq!(parsed.len(), 3000);
+        assert_eq!(parsed[2999].id, 2999);
+    }
+
+    #[test]
+    fn corrupted_block_is_rejected() {
+        let mut buf = encoded(3);
+        let last = buf.len() - 10;
+        buf[last] ^= 0xFF;
+
+        let err = binary::BinParser::read(buf.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn corrupted_block_is_skipped_in_lenient_mode() {
+        let mut buf = Vec::new();
+        let mut writer = binary::BinParser::writer(&mut buf);
+        for id in 0..3000 {
+            let tx = Transaction::new(id, "ACC", Amount::from_minor(1, 2), "USD".parse().unwrap());
+            writer.write(&tx).expect("bin write failed");
+        }
+        writer.finish().expect("bin finish failed");
+
+        // Заголовок — 12 байт, у блока перед данными 8 байт, после — CRC.
+        let first_len = u32::from_le_bytes(buf[16..20].try_into().unwrap()) as usize;
+        let second = 12 + 8 + first_len + 4;
+        buf[second + 8 + 100] ^= 0xFF;
+
+        let report = binary::BinParser::read_lenient(buf.as_slice(), Lenient::new()).expect("lenient read failed");
+        assert_eq!(report.transactions.len(), 3000 - 1024);
+        assert_eq!(report.transactions[1024].id, 2048);
+
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, 1025);
+        let err = report.rejected[0].error.to_string();
+        assert!(err.contains("Checksum mismatch in BIN block 2 (records 1025-2048)"), "{}", err);
+    }
+
+    #[test]
+    fn truncated_and_foreign_data_is_rejected() {
+        let buf = encoded(3);
+        let err = binary::BinParser::read(&buf[..buf.len() - 4]).unwrap_err();
+        assert!(err.to_string().contains("Truncated"), "{}", err);
+
+        let mut foreign = binary::MAGIC.to_vec();
+        foreign.extend_from_slice(&7u16.to_le_bytes());
+        let err = binary::BinParser::read(foreign.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("Unsupported BIN version"), "{}", err);
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<c:Document xmlns:c="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+  <c:BkToCstmrStmt>
+    <c:Stmt>
+      <c:Acct><c:Id><c:IBAN>DE89370400440532013000</c:IBAN></c:Id></c:Acct>
+      <c:Bal><c:Amt Ccy="EUR">10.00</c:Amt><c:CdtDbtInd>CRDT</c:CdtDbtInd></c:Bal>
+      <c:Ntry>
+        <c:NtryRef>1001</c:NtryRef>
+        <c:Amt Ccy="EUR">19.99</c:Amt>
+        <c:CdtDbtInd>DBIT</c:CdtDbtInd>
+        <c:Sts><c:Cd>BOOK</c:Cd></c:Sts>
+        <c:BookgDt><c:DtTm>2024-03-01T10:15:00</c:DtTm></c:BookgDt>
+        <c:ValDt><c:Dt>2024-03-02</c:Dt></c:ValDt>
+        <c:NtryDtls><c:TxDtls>
+          <c:RltdPties><c:CdtrAcct><c:Id><c:IBAN>GB33BUKB20201555555555</c:IBAN></c:Id></c:CdtrAcct></c:RltdPties>
+          <c:RmtInf><c:Ustrd>Invoice 17 &amp; 18</c:Ustrd></c:RmtInf>
+        </c:TxDtls></c:NtryDtls>
+      </c:Ntry>
+      <c:Ntry>
+        <c:NtryRef>1002</c:NtryRef>
+        <c:Amt Ccy="EUR">5</c:Amt>
+        <c:CdtDbtInd>CRDT</c:CdtDbtInd>
+        <c:Sts><c:Cd>PDNG</c:Cd></c:Sts>
+        <c:AddtlNtryInf>Refund</c:AddtlNtryInf>
+      </c:Ntry>
+    </c:Stmt>
+  </c:BkToCstmrStmt>
+</c:Document>
+"#;
+
+    #[test]
+    fn read_statement() {
+        let parsed = camt::CamtParser::read(STATEMENT.as_bytes()).expect("camt read failed");
+
+        let mut debit = Transaction::new(1001, "DE89370400440532013000", Amount::from_minor(-1999, 2), "EUR".parse().unwrap());
+        debit.booking_date = "2024-03-01".parse().ok();
+        debit.value_date = "2024-03-02".parse().ok();
+        debit.description = Some("Invoice 17 & 18".to_string());
+        debit.counterparty = Some("GB33BUKB20201555555555".to_string());
+        debit.direction = Some(Direction::Debit);
+        debit.status = Some(Status::Booked);
+
+        let mut credit = Transaction::new(1002, "DE89370400440532013000", Amount::from_minor(500, 2), "EUR".parse().unwrap());
+        credit.description = Some("Refund".to_string());
+        credit.direction = Some(Direction::Credit);
+        credit.status = Some(Status::Pending);
+
+        assert_eq!(parsed, [debit, credit]);
+        assert_eq!(FormatKind::sniff(STATEMENT.as_bytes()), Some(FormatKind::Camt));
+    }
+
+    #[test]
+    fn read_notification() {
+        let data = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.054.001.02"><BkToCstmrDbtCdtNtfctn>
+            <Ntfctn><Acct><Id><Othr><Id>40702810</Id></Othr></Id></Acct>
+              <Ntry><NtryRef>abc</NtryRef><Amt Ccy="RUB">1.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts></Ntry>
+              <Ntry><NtryRef>7</NtryRef><Amt Ccy="RUB">2.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts></Ntry>
+              <Ntry><NtryRef>8</NtryRef><Amt Ccy="RUB">x</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts></Ntry>
+            </Ntfctn></BkToCstmrDbtCdtNtfctn></Document>"#;
+
+        let report = camt::CamtParser::read_lenient(data.as_bytes(), Lenient::new()).expect("camt read failed");
+        assert_eq!(report.transactions.len(), 2);
+        assert_eq!(report.transactions[0].id, mt940::NONREF_ID + 1);
+        assert_eq!(report.transactions[1].id, 7);
+        assert_eq!(report.transactions[1].account, "40702810");
+
+        let error = report.rejected[0].error.record().expect("record error");
+        assert_eq!(error.field, Some("Amt"));
+        assert_eq!(error.value.as_deref(), Some("x"));
+        assert_eq!(error.position.record, Some(3));
+    }
+
+    #[test]
+    fn write_statement_with_balances() {
+        let data = [
+            Transaction::new(1, "ACC 1", Amount::from_minor(1000, 2), "USD".parse().unwrap()),
+            Transaction::new(2, "ACC 1", Amount::from_minor(-250, 2), "USD".parse().unwrap()),
+            Transaction::new(3, "ACC 2", Amount::from_minor(7, 0), "JPY".parse().unwrap()),
+        ];
+
+        let created = "2024-03-01T12:30:00".parse().unwrap();
+        let mut buf = Vec::new();
+        let mut writer = camt::CamtWriter::with_time(&mut buf, created);
+        for tx in &data {
+            writer.write(tx).expect("camt write failed");
+        }
+        writer.finish().expect("camt finish failed");
+        drop(writer);
+        let xml = String::from_utf8(buf).expect("camt is not UTF-8");
+
+        assert!(xml.contains("<CreDtTm>2024-03-01T12:30:00</CreDtTm>"));
+        assert!(xml.contains("<Dt><Dt>2024-03-01</Dt></Dt>"));
+        assert_eq!(xml.matches("<Stmt>").count(), 2);
+        assert!(xml.contains("<Id><Othr><Id>ACC 1</Id></Othr></Id>"));
+        assert!(xml.contains("<Cd>CLBD</Cd></CdOrPrtry></Tp>\n        <Amt Ccy=\"USD\">7.50</Amt>"));
+
+        let parsed = camt::CamtParser::read(xml.as_bytes()).expect("camt read failed");
+        assert_eq!(parsed.iter().map(|tx| tx.amount).collect::<Vec<_>>(), data.map(|tx| tx.amount));
+    }
+
+    #[test]
+    fn broken_xml_stops_reading() {
+        let data = "<Document><BkToCstmrStmt><Stmt><Ntry></Stmt>";
+
+        let mut reader = camt::CamtParser::reader(data.as_bytes());
+        assert!(matches!(reader.next(), Some(Err(ParserError::Record(_)))));
+        assert!(reader.next().is_none());
+    }
+}
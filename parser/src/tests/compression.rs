@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::io::Read;
+    use std::path::Path;
+
+    #[test]
+    fn compressed_round() {
+        let data = vec![
+            Transaction::new(1, "ACC1", Amount::from_minor(100, 2), "USD".parse().unwrap()),
+            Transaction::new(2, "ACC2", Amount::from_minor(-50, 2), "EUR".parse().unwrap()),
+        ];
+
+        for compression in Compression::ALL {
+            let mut encoder = compression.encoder(Vec::new()).expect("encoder failed");
+            csv::Csv::write(&mut encoder, &data).expect("csv write failed");
+            let buf = encoder.finish().expect("finish failed");
+            assert_eq!(Compression::sniff(&buf), Some(compression));
+
+            let (detected, input) = decompress(None, buf.as_slice()).expect("decompress failed");
+            assert_eq!(detected, Some(compression));
+
+            let (format, input) = detect(None, input).expect("detect failed");
+            assert_eq!(format, FormatKind::Csv);
+            assert_eq!(csv::Csv::read(input).expect("csv read failed"), data);
+        }
+    }
+
+    #[test]
+    fn plain_input_and_extensions() {
+        let (detected, mut input) = decompress(Some(Path::new("a.csv")), &b"id,account"[..]).expect("decompress failed");
+        assert_eq!(detected, None);
+        let mut text = String::new();
+        input.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "id,account");
+
+        assert_eq!(Compression::from_extension(Path::new("a.bin.ZST")), Some(Compression::Zstd));
+        assert_eq!(Compression::strip_extension(Path::new("dir/a.csv.gz")), Path::new("dir/a.csv"));
+        assert_eq!(Compression::strip_extension(Path::new("a.csv")), Path::new("a.csv"));
+        assert_eq!("gz".parse::<Compression>().unwrap(), Compression::Gzip);
+        assert!("lz4".parse::<Compression>().is_err());
+
+        // Не сжатые данные в файле `.gz` дают ошибку распаковки, а не мусор.
+        let (detected, mut input) = decompress(Some(Path::new("a.csv.gz")), &b"id,account"[..]).expect("decompress failed");
+        assert_eq!(detected, Some(Compression::Gzip));
+        assert!(input.read_to_string(&mut text).is_err());
+    }
+}
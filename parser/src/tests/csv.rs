@@ -5,12 +5,7 @@ mod tests {
 
     #[test]
     fn csv_round() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -25,12 +20,7 @@ mod tests {
 
     #[test]
     fn csv_round_ne() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -39,13 +29,89 @@ mod tests {
         buf.set_position(0);
 
         let mut parsed = csv::Csv::read(&mut buf).expect("csv read failed");
-        parsed.push(Transaction{
-            id: 0,
-            account: "".to_string(),
-            amount: 0,
-            currency: "".to_string(),
-        });
+        parsed.push(Transaction::new(0, "", Amount::from_minor(0, 2), "EUR".parse().unwrap()));
 
         assert_ne!(data, parsed);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn header_columns_are_mapped_by_name() {
+        let data = "Amt,Branch,CCY,acct,Tx-Id,Buchungstag\n-5.50,HQ,EUR,ACC7,7,2024-01-31\n";
+        let options = csv::CsvOptions::new().alias("Buchungstag", csv::Column::BookingDate);
+
+        let parsed: Vec<_> = options.reader(data.as_bytes()).collect::<Result<_, _>>().expect("csv read failed");
+
+        let mut expected = Transaction::new(7, "ACC7", Amount::from_minor(-550, 2), "EUR".parse().unwrap());
+        expected.booking_date = Some("2024-01-31".parse().unwrap());
+        assert_eq!(parsed, [expected]);
+    }
+
+    #[test]
+    fn header_modes() {
+        let headerless = "1,ACC1,1.00,USD\n2,ACC2,2.00,EUR\n";
+        assert_eq!(csv::Csv::read(headerless.as_bytes()).expect("csv read failed").len(), 2);
+
+        let absent = csv::CsvOptions::new().header(csv::HeaderMode::Absent);
+        let mut buf = Vec::new();
+        let data = [Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
+        let mut writer = absent.writer(&mut buf);
+        writer.write(&data[0]).expect("csv write failed");
+        writer.finish().expect("csv write failed");
+        drop(writer);
+        assert!(buf.starts_with(b"1,ACC,1.00,USD"));
+
+        let required = csv::CsvOptions::new().header(csv::HeaderMode::Required);
+        let err = required.reader("id,acct,amount\n1,ACC1,1.00\n".as_bytes()).next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("missing columns: currency"), "{}", err);
+    }
+
+    #[test]
+    fn european_dialect() {
+        let options = csv::CsvOptions::new()
+            .delimiter(b';')
+            .decimal_separator(',').unwrap()
+            .encoding("windows-1251").unwrap()
+            .comment(b'#')
+            .trim(true);
+
+        // "Оплата" в windows-1251.
+        let mut data = b"# export\nid;account;amount;currency;description\n1; ACC1 ;-12,50;EUR;".to_vec();
+        data.extend_from_slice(&[0xCE, 0xEF, 0xEB, 0xE0, 0xF2, 0xE0]);
+        data.extend_from_slice(b"\n");
+
+        let parsed: Vec<_> = options.reader(data.as_slice()).collect::<Result<_, _>>().expect("csv read failed");
+        let mut expected = Transaction::new(1, "ACC1", Amount::from_minor(-1250, 2), "EUR".parse().unwrap());
+        expected.description = Some("Оплата".to_string());
+        assert_eq!(parsed, [expected.clone()]);
+
+        let mut buf = Vec::new();
+        let mut writer = options.writer(&mut buf);
+        writer.write(&expected).expect("csv write failed");
+        writer.finish().expect("csv write failed");
+        drop(writer);
+        assert!(buf.windows(6).any(|window| window == b"-12,50"));
+        assert!(buf.windows(6).any(|window| window == [0xCE, 0xEF, 0xEB, 0xE0, 0xF2, 0xE0]));
+
+        let parsed: Vec<_> = options.reader(buf.as_slice()).collect::<Result<_, _>>().expect("csv read failed");
+        assert_eq!(parsed, [expected]);
+    }
+
+    #[test]
+    fn raw_record_keeps_dialect() {
+        let options = csv::CsvOptions::new().delimiter(b';').decimal_separator(',').unwrap();
+        let data = "id;account;amount;currency;description\n1;ACC1;12.x;EUR;a,b\n";
+
+        let err = options.reader(data.as_bytes()).next().unwrap().unwrap_err();
+        let record = err.record().expect("record error");
+        assert_eq!(record.raw.as_deref(), Some("1;ACC1;12.x;EUR;a,b"));
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        let options = csv::CsvOptions::new().quote(b'\'').escape(b'\\');
+        let data = "id,account,amount,currency,description\n1,ACC1,1.00,USD,'it\\'s, fine'\n";
+
+        let parsed: Vec<_> = options.reader(data.as_bytes()).collect::<Result<_, _>>().expect("csv read failed");
+        assert_eq!(parsed[0].description.as_deref(), Some("it's, fine"));
+    }
+}
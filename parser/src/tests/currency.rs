@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn strict_parse() {
+        let usd: Currency = "USD".parse().unwrap();
+        assert_eq!(usd.code(), "USD");
+        assert_eq!(usd.numeric(), 840);
+        assert_eq!(usd.exponent(), 2);
+
+        assert!("usd".parse::<Currency>().is_err());
+        assert!(" USD".parse::<Currency>().is_err());
+        assert!("USD1".parse::<Currency>().is_err());
+        assert!("".parse::<Currency>().is_err());
+        assert!("XYZ".parse::<Currency>().is_err());
+    }
+
+    #[test]
+    fn lenient_parse() {
+        let jpy = Currency::parse(" jpy ", ParseMode::Lenient).unwrap();
+        assert_eq!(jpy.code(), "JPY");
+        assert_eq!(jpy.exponent(), 0);
+
+        assert_eq!(Currency::parse("978", ParseMode::Lenient).unwrap().code(), "EUR");
+        assert!(Currency::parse("usd1", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn numeric_lookup() {
+        assert_eq!(Currency::from_numeric(414).map(|c| c.code()), Some("KWD"));
+        assert_eq!(Currency::from_numeric(3), None);
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::io::Read;
+    use std::path::Path;
+
+    #[test]
+    fn sniff_content() {
+        assert_eq!(FormatKind::sniff(b"id,account,amount,currency\n1,ACC,100,USD\n"), Some(FormatKind::Csv));
+        assert_eq!(FormatKind::sniff(b"1 ACC 100 USD\n"), Some(FormatKind::Txt));
+        assert_eq!(FormatKind::sniff(&[1, 0, 0, 0, 1, 0, 0, 0]), Some(FormatKind::Bin));
+        assert_eq!(FormatKind::sniff(b""), None);
+    }
+
+    #[test]
+    fn commas_alone_do_not_make_csv() {
+        let txt = b"1 ACC 1.00 USD description=\"a, b, c, d\"\n";
+        assert_eq!(FormatKind::sniff(txt), Some(FormatKind::Txt));
+        let (kind, _) = detect(Some(Path::new("t.txt")), &txt[..]).expect("detect failed");
+        assert_eq!(kind, FormatKind::Txt);
+
+        assert_eq!(FormatKind::sniff(b"1,ACC,1.00,USD\n"), Some(FormatKind::Csv));
+        assert_eq!(FormatKind::sniff(b"a, b, c, d\n"), None);
+        let (kind, _) = detect(Some(Path::new("notes.txt")), &b"a, b, c, d\n"[..]).expect("detect failed");
+        assert_eq!(kind, FormatKind::Txt);
+    }
+
+    #[test]
+    fn detect_falls_back_to_extension() {
+        let (kind, _) = detect(Some(Path::new("empty.TXT")), &b""[..]).expect("detect failed");
+        assert_eq!(kind, FormatKind::Txt);
+
+        assert!(detect(None, &b""[..]).is_err());
+    }
+
+    #[test]
+    fn detect_keeps_stream_intact() {
+        let data = b"1 ACC 100 USD\n";
+        let (kind, mut stream) = detect(Some(Path::new("data.csv")), &data[..]).expect("detect failed");
+        assert_eq!(kind, FormatKind::Txt);
+
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).expect("read failed");
+        assert_eq!(rest, data);
+    }
+}
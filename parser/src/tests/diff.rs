@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::csv::Column;
+
+    fn tx(id: u64, minor_units: i64) -> Transaction {
+        Transaction::new(id, "ACC", Amount::from_minor(minor_units, 2), "USD".parse().unwrap())
+    }
+
+    #[test]
+    fn identical_lists() {
+        let data = vec![tx(1, 100), tx(2, 200)];
+
+        let diff = diff(&data, &data);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn changed_fields_and_trailing_records() {
+        let left = vec![tx(1, 100), tx(2, 200), tx(3, 300)];
+        let mut changed = tx(2, 250);
+        changed.description = Some("Fee".to_string());
+        let right = vec![tx(1, 100), changed];
+
+        let diff = diff(&left, &right);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.unchanged, 1);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].index, 3);
+        assert_eq!(diff.removed[0].transaction, &left[2]);
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].left.index, 2);
+        assert_eq!(diff.changed[0].fields, [
+            FieldDiff { field: Column::Amount, left: Some("2.00".to_string()), right: Some("2.50".to_string()) },
+            FieldDiff { field: Column::Description, left: None, right: Some("Fee".to_string()) },
+        ]);
+
+        let diff = crate::diff(&right, &left);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].index, 3);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn record_error(result: Result<Vec<Transaction>, ParserError>) -> RecordError {
+        let err = result.unwrap_err();
+        err.record().cloned().unwrap_or_else(|| panic!("not a record error: {}", err))
+    }
+
+    #[test]
+    fn csv_error_points_at_field() {
+        let data = "id,account,amount,currency\n1,ACC1,1.00,USD\n2,ACC2,12.x,USD\n";
+
+        let err = record_error(csv::Csv::read(data.as_bytes()));
+        assert_eq!(err.format, "csv");
+        assert_eq!(err.position, Position { record: Some(2), line: Some(3), column: Some(3), byte: Some(43) });
+        assert_eq!(err.field, Some("amount"));
+        assert_eq!(err.value.as_deref(), Some("12.x"));
+        assert_eq!(
+            err.to_string(),
+            "CSV error at record 2, line 3, column 3, byte 43, field amount, value \"12.x\": Invalid amount: 12.x"
+        );
+    }
+
+    #[test]
+    fn txt_error_points_at_column() {
+        let data = "1 ACC1 1.00 USD\n2 ACC2 5.00 USD direction=up\n";
+
+        let err = record_error(txt::TxtParser::read(data.as_bytes()));
+        assert_eq!(err.position, Position { record: Some(2), line: Some(2), column: Some(27), byte: Some(42) });
+        assert_eq!(err.field, Some("direction"));
+        assert_eq!(err.value.as_deref(), Some("up"));
+
+        let err = record_error(txt::TxtParser::read("3 ACC3".as_bytes()));
+        assert_eq!(err.field, Some("currency"));
+        assert_eq!(err.position.column, Some(7));
+    }
+
+    #[test]
+    fn bin_error_points_at_byte_offset() {
+        let mut data = binary::MAGIC.to_vec();
+        data.extend_from_slice(&binary::VERSION.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&100i64.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"ACC");
+        data.extend_from_slice(&999u16.to_le_bytes());
+
+        let err = record_error(binary::BinParser::read(data.as_slice()));
+        assert_eq!(err.format, "bin");
+        assert_eq!(err.position, Position { record: Some(1), byte: Some(35), ..Position::default() });
+        assert_eq!(err.field, Some("currency"));
+        assert_eq!(err.value.as_deref(), Some("999"));
+    }
+}
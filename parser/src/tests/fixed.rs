@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::fixed::Layout;
+
+    const LAYOUT: &str = r#"{
+        "fields": [
+            { "name": "id", "offset": 0, "width": 6, "type": "integer" },
+            { "name": "acct", "offset": 6, "width": 8, "type": "text" },
+            { "name": "amount", "offset": 14, "width": 9, "type": "decimal", "decimals": 2 },
+            { "name": "currency", "offset": 23, "width": 3, "type": "text" },
+            { "name": "booking_date", "offset": 27, "width": 6, "type": "date", "format": "DDMMYY" },
+            { "name": "description", "offset": 33, "width": 10, "type": "text" },
+            { "name": "direction", "offset": 43, "width": 6, "type": "text", "align": "right" }
+        ]
+    }"#;
+
+    #[test]
+    fn read_and_write() {
+        let layout = Layout::from_json(LAYOUT.as_bytes()).expect("layout failed");
+        let data = "000001ACC1    000001999USD 310124Rent       debit\n\
+                    \n\
+                    000002ACC2    -00000500EUR 000000                  \n";
+
+        let parsed: Vec<Transaction> = layout.reader(data.as_bytes()).collect::<Result<_, _>>().expect("fixed read failed");
+
+        let mut first = Transaction::new(1, "ACC1", Amount::from_minor(1999, 2), "USD".parse().unwrap());
+        first.booking_date = "2024-01-31".parse().ok();
+        first.description = Some("Rent".to_string());
+        first.direction = Some(Direction::Debit);
+        let second = Transaction::new(2, "ACC2", Amount::from_minor(-500, 2), "EUR".parse().unwrap());
+        assert_eq!(parsed, [first.clone(), second.clone()]);
+
+        let mut buf = Vec::new();
+        let mut writer = layout.writer(&mut buf);
+        writer.write(&first).expect("fixed write failed");
+        writer.write(&second).expect("fixed write failed");
+        writer.finish().expect("fixed finish failed");
+
+        // Пустые поля и промежутки заполняются пробелами до полной ширины записи.
+        let expected = format!("000001ACC1    000001999USD 310124Rent       debit\n000002ACC2    -00000500EUR{}\n", " ".repeat(23));
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn record_errors() {
+        let layout = Layout::from_json(LAYOUT.as_bytes()).expect("layout failed");
+        let data = "000001ACC1    0000019x9USD\n000002ACC2    000000100EUR 311324\n000003\n";
+
+        let errors: Vec<ParserError> = layout.reader(data.as_bytes()).filter_map(Result::err).collect();
+        assert_eq!(errors.len(), 3);
+
+        let record = errors[0].record().expect("record error");
+        assert_eq!(record.field, Some("amount"));
+        assert_eq!(record.value.as_deref(), Some("0000019x9"));
+        assert_eq!(record.position.line, Some(1));
+        assert_eq!(record.position.column, Some(15));
+
+        let record = errors[1].record().expect("record error");
+        assert_eq!(record.field, Some("booking_date"));
+        assert_eq!(record.position.byte, Some(27 + 27));
+
+        let record = errors[2].record().expect("record error");
+        assert_eq!(record.field, Some("account"));
+        assert_eq!(record.reason, "missing field");
+    }
+
+    #[test]
+    fn invalid_layouts_and_values() {
+        let layout = |fields: &str| Layout::from_json(format!(r#"{{ "fields": [{}] }}"#, fields).as_bytes());
+        let required = r#"{ "name": "id", "offset": 0, "width": 4, "type": "integer" },
+            { "name": "account", "offset": 4, "width": 4, "type": "text" },
+            { "name": "currency", "offset": 8, "width": 3, "type": "text" }"#;
+
+        let err = layout(required).unwrap_err();
+        assert!(err.to_string().contains("missing required field amount"), "{}", err);
+
+        let err = layout(&format!(r#"{}, {{ "name": "amount", "offset": 10, "width": 5, "type": "decimal" }}"#, required)).unwrap_err();
+        assert!(err.to_string().contains("overlaps"), "{}", err);
+
+        let err = layout(&format!(r#"{}, {{ "name": "amount", "offset": 11, "width": 5, "type": "text" }}"#, required)).unwrap_err();
+        assert!(err.to_string().contains("must have type decimal"), "{}", err);
+
+        let err = layout(&format!(r#"{}, {{ "name": "iban2", "offset": 11, "width": 5, "type": "text" }}"#, required)).unwrap_err();
+        assert!(err.to_string().contains("unknown field name: iban2"), "{}", err);
+
+        let layout = layout(&format!(r#"{}, {{ "name": "amount", "offset": 11, "width": 5, "type": "decimal", "padding": "space" }}"#, required))
+            .expect("layout failed");
+        let mut buf = Vec::new();
+        let mut writer = layout.writer(&mut buf);
+        writer.write(&Transaction::new(1, "ACC", Amount::from_minor(-150, 2), "USD".parse().unwrap())).expect("fixed write failed");
+        assert_eq!(buf, b"0001ACC USD-1.50\n");
+
+        let mut writer = layout.writer(Vec::new());
+        let err = writer.write(&Transaction::new(1, "ACC", Amount::from_minor(100000, 2), "USD".parse().unwrap())).unwrap_err();
+        assert!(err.to_string().contains("does not fit field amount"), "{}", err);
+    }
+
+    #[test]
+    fn registered_with_layout() {
+        use crate::fixed::FixedFormat;
+
+        let mut registry = Registry::default();
+        assert!(registry.by_name(FixedFormat::NAME).is_none());
+        registry.register(Box::new(FixedFormat::new(Layout::from_json(LAYOUT.as_bytes()).expect("layout failed"))));
+
+        let format = registry.get(FixedFormat::NAME).expect("fixed is not registered");
+        let data = "000001ACC1    000001999USD 310124Rent       debit\n";
+        let parsed: Vec<Transaction> = format.reader(Box::new(data.as_bytes())).collect::<Result<_, _>>().expect("fixed read failed");
+        assert_eq!(parsed[0].description.as_deref(), Some("Rent"));
+
+        let mut buf = Vec::new();
+        let mut writer = format.writer(Box::new(&mut buf));
+        writer.write(&parsed[0]).expect("fixed write failed");
+        writer.finish().expect("fixed finish failed");
+        drop(writer);
+        assert_eq!(String::from_utf8(buf).unwrap(), data);
+        assert!(registry.detect(Some(std::path::Path::new("t.dat")), data.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use chrono::NaiveDate;
+
+    fn tx(id: u64, account: &str, minor_units: i64, day: u32) -> Transaction {
+        let mut tx = Transaction::new(id, account, Amount::from_minor(minor_units, 2), "USD".parse().unwrap());
+        tx.booking_date = NaiveDate::from_ymd_opt(2024, 3, day);
+        tx
+    }
+
+    #[test]
+    fn tolerance_and_date_window() {
+        let ledger = vec![tx(1, "A", 10_000, 1), tx(2, "A", 5_000, 10), tx(3, "B", 700, 1)];
+        let bank = vec![tx(10, "A", 5_000, 11), tx(11, "A", 10_001, 3), tx(12, "A", 700, 1)];
+
+        let exact = reconcile_fuzzy(&ledger, &bank, &FuzzyOptions::new());
+        assert!(exact.matches.is_empty());
+
+        let options = FuzzyOptions::new().amount_tolerance("0.01".parse().unwrap()).date_window(2);
+        let result = reconcile_fuzzy(&ledger, &bank, &options);
+        assert_eq!(result.matches.len(), 2);
+
+        let first = &result.matches[0];
+        assert_eq!((first.ledger[0].index, first.statement.index), (2, 1));
+        assert_eq!(first.difference, Amount::from_minor(0, 2));
+
+        let second = &result.matches[1];
+        assert_eq!((second.ledger[0].index, second.statement.index), (1, 2));
+        assert_eq!(second.difference, Amount::from_minor(-1, 2));
+        assert!(second.confidence < first.confidence);
+
+        // Другой счёт не сопоставляется даже при точном совпадении суммы.
+        assert_eq!(result.unmatched_ledger.len(), 1);
+        assert_eq!(result.unmatched_ledger[0].index, 3);
+        assert_eq!(result.unmatched_statement[0].index, 3);
+        assert!(!result.is_clean());
+    }
+
+    #[test]
+    fn groups_ledger_rows_into_one_statement_line() {
+        let ledger = vec![tx(1, "A", 1_000, 1), tx(2, "A", 2_500, 2), tx(3, "A", -400, 2), tx(4, "A", 1_500, 2)];
+        let bank = vec![tx(10, "A", 4_000, 2), tx(11, "A", -400, 2)];
+
+        let ungrouped = reconcile_fuzzy(&ledger, &bank, &FuzzyOptions::new());
+        assert_eq!(ungrouped.matches.len(), 1);
+
+        let result = reconcile_fuzzy(&ledger, &bank, &FuzzyOptions::new().max_group(3).date_window(1));
+        assert_eq!(result.matches.len(), 2);
+        assert_eq!(result.unmatched_ledger.len(), 1);
+        assert_eq!(result.unmatched_ledger[0].index, 1);
+
+        // Расход -4.00 в группу не попадает: знаки записей группы совпадают со строкой.
+        let grouped = &result.matches[0];
+        let indices: Vec<usize> = grouped.ledger.iter().map(|entry| entry.index).collect();
+        assert_eq!(indices, vec![2, 4]);
+        assert_eq!(grouped.difference, Amount::from_minor(0, 2));
+        assert_eq!(result.matches[1].ledger[0].index, 3);
+    }
+
+    #[test]
+    fn group_search_is_bounded() {
+        let ledger: Vec<Transaction> = (1..=40).map(|id| tx(id, "A", 500, 1)).collect();
+        let bank = vec![tx(100, "A", 100_000, 1), tx(101, "A", 300, 1), tx(102, "A", 1_500, 1), tx(103, "A", 15_000, 1)];
+
+        // Без отсечения и ограничения группы перебор занял бы 2^24 вариантов на строку.
+        let started = std::time::Instant::now();
+        let result = reconcile_fuzzy(&ledger, &bank, &FuzzyOptions::new().max_group(usize::MAX));
+        assert!(started.elapsed() < std::time::Duration::from_secs(2), "{:?}", started.elapsed());
+
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].statement.index, 3);
+        assert_eq!(result.matches[0].ledger.len(), 3);
+        // 150.00 — это 30 записей, больше FuzzyOptions::MAX_GROUP.
+        assert_eq!(result.unmatched_statement.len(), 3);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn json_round() {
+        let mut tx = Transaction::new(1, "ACC1", Amount::from_minor(10000, 2), "USD".parse().unwrap());
+        tx.description = Some("Rent \"May\"".to_string());
+        tx.status = Some(Status::Booked);
+        let data = vec![tx, Transaction::new(2, "ACC2", Amount::from_minor(-50, 0), "JPY".parse().unwrap())];
+
+        let mut buf = Vec::new();
+        json::JsonParser::write(&mut buf, &data).expect("json write failed");
+        assert!(buf.starts_with(b"[\n{\"id\":1,\"account\":\"ACC1\",\"amount\":\"100.00\",\"currency\":\"USD\""));
+        assert_eq!(json::JsonParser::read(buf.as_slice()).expect("json read failed"), data);
+
+        let mut buf = Vec::new();
+        json::NdjsonParser::write(&mut buf, &data).expect("ndjson write failed");
+        assert_eq!(buf.iter().filter(|&&b| b == b'\n').count(), 2);
+        assert_eq!(json::NdjsonParser::read(buf.as_slice()).expect("ndjson read failed"), data);
+
+        let mut buf = Vec::new();
+        json::JsonParser::write(&mut buf, &[]).expect("json write failed");
+        assert_eq!(buf, b"[]\n");
+        assert!(json::JsonParser::read(buf.as_slice()).expect("json read failed").is_empty());
+    }
+
+    #[test]
+    fn numbers_nulls_and_unknown_fields() {
+        let data = r#" [ {"id": "7", "account": "A", "amount": 1.5, "currency": "EUR", "status": null, "memo": 1} ] "#;
+
+        let parsed = json::JsonParser::read(data.as_bytes()).expect("json read failed");
+        assert_eq!(parsed, [Transaction::new(7, "A", Amount::from_minor(150, 2), "EUR".parse().unwrap())]);
+        assert_eq!(FormatKind::sniff(data.as_bytes()), Some(FormatKind::Json));
+    }
+
+    #[test]
+    fn numeric_amount_keeps_precision() {
+        let data = r#"[{"id": 1, "account": "A", "amount": 90071992547409.93, "currency": "USD"}]"#;
+
+        let parsed = json::JsonParser::read(data.as_bytes()).expect("json read failed");
+        assert_eq!(parsed[0].amount, Amount::from_minor(9007199254740993, 2));
+
+        let line = r#"{"id": 1, "account": "A", "amount": 1234567890123456.789, "currency": "KWD"}"#;
+        let parsed = json::NdjsonParser::read(line.as_bytes()).expect("ndjson read failed");
+        assert_eq!(parsed[0].amount, Amount::from_minor(1234567890123456789, 3));
+    }
+
+    #[test]
+    fn field_errors_are_positioned() {
+        let data = "[{\"id\":1,\"account\":\"A\",\"amount\":\"x\",\"currency\":\"USD\"},\n\
+                    {\"id\":2,\"account\":\"B\",\"amount\":\"2.00\",\"currency\":\"USD\"}]";
+
+        let report = json::JsonParser::read_lenient(data.as_bytes(), Lenient::new()).expect("json read failed");
+        assert_eq!(report.transactions.len(), 1);
+        let error = report.rejected[0].error.record().expect("record error");
+        assert_eq!(error.field, Some("amount"));
+        assert_eq!(error.position.record, Some(1));
+        assert_eq!(error.position.byte, Some(1));
+
+        let data = "{\"id\":1,\"account\":\"A\",\"amount\":\"1.00\",\"currency\":\"USD\"}\n\n{\"id\":2,\n";
+        let report = json::NdjsonParser::read_lenient(data.as_bytes(), Lenient::new()).expect("ndjson read failed");
+        assert_eq!(report.transactions.len(), 1);
+        let error = report.rejected[0].error.record().expect("record error");
+        assert_eq!(error.position.line, Some(3));
+        assert_eq!(error.raw.as_deref(), Some("{\"id\":2,"));
+    }
+
+    #[test]
+    fn broken_array_stops_reading() {
+        let data = "[{\"id\":1,\"account\":\"A\",\"amount\":\"1.00\",\"currency\":\"USD\"} {\"id\":2}]";
+
+        let mut reader = json::JsonParser::reader(data.as_bytes());
+        assert!(reader.next().expect("first item").is_ok());
+        assert!(matches!(reader.next(), Some(Err(ParserError::Record(_)))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn kv_round() {
+        let mut tx = Transaction::new(1, "DE89 3704 0044", Amount::from_minor(100, 2), "USD".parse().unwrap());
+        tx.description = Some(" Rent \"May\"\n".to_string());
+        tx.counterparty = Some(String::new());
+        tx.status = Some(Status::Booked);
+        let data = vec![tx, Transaction::new(2, "ACC2", Amount::from_minor(-50, 2), "EUR".parse().unwrap())];
+
+        let mut buf = Vec::new();
+        kv::KvParser::write(&mut buf, &data).expect("kv write failed");
+        assert!(buf.starts_with(b"TX_ID: 1\nACCOUNT: DE89 3704 0044\nAMOUNT: 1.00\nCURRENCY: USD\n"));
+
+        let parsed = kv::KvParser::read(buf.as_slice()).expect("kv read failed");
+        assert_eq!(data, parsed);
+    }
+
+    #[test]
+    fn arbitrary_order_and_comments() {
+        let data = "# partner export\n\n\ncurrency: EUR\nAMOUNT: -5.5\nTX_ID: 7\nACCOUNT: ACC 7\n\n\n";
+
+        let parsed = kv::KvParser::read(data.as_bytes()).expect("kv read failed");
+        assert_eq!(parsed, [Transaction::new(7, "ACC 7", Amount::from_minor(-550, 2), "EUR".parse().unwrap())]);
+        assert_eq!(FormatKind::sniff(data.as_bytes()), Some(FormatKind::Kv));
+    }
+
+    #[test]
+    fn duplicate_and_unknown_keys() {
+        let data = "TX_ID: 1\nACCOUNT: A\nAMOUNT: 1.00\nAMOUNT: 2.00\nCURRENCY: USD\n\n\
+                    TX_ID: 2\nACCOUNT: B\nAMOUNT: 2.00\nCURRENCY: USD\nBRANCH: HQ\n\n\
+                    TX_ID: 3\nACCOUNT: C\nAMOUNT: 3.00\nCURRENCY: USD\n";
+
+        let report = kv::KvParser::read_lenient(data.as_bytes(), Lenient::new()).expect("kv read failed");
+        assert_eq!(report.transactions.len(), 1);
+        assert_eq!(report.transactions[0].id, 3);
+
+        let duplicate = report.rejected[0].error.record().expect("record error");
+        assert_eq!(duplicate.position.record, Some(1));
+        assert_eq!(duplicate.position.line, Some(4));
+        assert_eq!(duplicate.field, Some("amount"));
+        assert!(duplicate.reason.contains("duplicate key"), "{}", duplicate.reason);
+
+        let unknown = report.rejected[1].error.record().expect("record error");
+        assert_eq!(unknown.position.line, Some(11));
+        assert_eq!(unknown.value.as_deref(), Some("BRANCH"));
+
+        let lenient_keys = kv::KvOptions::new().unknown_keys(kv::UnknownKeys::Ignore);
+        let parsed: Result<Vec<_>, _> = lenient_keys.reader(data.as_bytes()).skip(1).collect();
+        assert_eq!(parsed.expect("kv read failed").len(), 2);
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const TXT: &str = "1 ACC1 1.00 USD\nbroken\n2 ACC2 2.x USD\n3 ACC3 3.00 EUR\n";
+
+    #[test]
+    fn keeps_valid_records() {
+        let report = txt::TxtParser::read_lenient(TXT.as_bytes(), Lenient::new()).expect("lenient read failed");
+
+        let ids: Vec<u64> = report.transactions.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, [1, 3]);
+
+        assert_eq!(report.rejected.len(), 2);
+        assert_eq!(report.rejected[0].index, 2);
+        assert_eq!(report.rejected[0].raw.as_deref(), Some("broken"));
+        assert_eq!(report.rejected[1].index, 3);
+        assert_eq!(report.rejected[1].raw.as_deref(), Some("2 ACC2 2.x USD"));
+    }
+
+    #[test]
+    fn csv_records_with_wrong_field_count_are_rejected() {
+        let data = "id,account,amount,currency\n1,ACC1,1.00,USD\n2,ACC2\n3,ACC3,3.00,XXX\n4,ACC4,4.00,EUR\n";
+
+        let report = csv::Csv::read_lenient(data.as_bytes(), Lenient::new()).expect("lenient read failed");
+
+        let ids: Vec<u64> = report.transactions.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, [1, 4]);
+
+        let rejected: Vec<u64> = report.rejected.iter().map(|rejected| rejected.index).collect();
+        assert_eq!(rejected, [2, 3]);
+        assert_eq!(report.rejected[1].raw.as_deref(), Some("3,ACC3,3.00,XXX"));
+    }
+
+    #[test]
+    fn stops_after_max_errors() {
+        let result = txt::TxtParser::read_lenient(TXT.as_bytes(), Lenient::new().max_errors(1));
+        assert!(result.unwrap_err().to_string().contains("Too many invalid records"));
+
+        let report = txt::TxtParser::read_lenient(TXT.as_bytes(), Lenient::new().max_errors(2)).expect("lenient read failed");
+        assert_eq!(report.rejected.len(), 2);
+    }
+}
@@ -1,3 +1,22 @@
+mod camt;
+mod compression;
 mod csv;
+mod currency;
+mod detect;
+mod diff;
+mod errors;
+mod fixed;
+mod fuzzy;
+mod json;
+mod kv;
+mod lenient;
+mod money;
+mod mt940;
+mod ofx;
+mod parquet;
+mod reconcile;
+mod registry;
+mod report;
+mod stats;
 mod text;
-mod bin;
\ No newline at end of file
+mod bin;
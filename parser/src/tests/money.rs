@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(Amount::parse("12.34", 2).unwrap(), Amount::from_minor(1234, 2));
+        assert_eq!(Amount::parse("-0.5", 2).unwrap(), Amount::from_minor(-50, 2));
+        assert_eq!(Amount::parse("+100", 2).unwrap(), Amount::from_minor(10000, 2));
+        assert_eq!(Amount::parse("1.230", 2).unwrap(), Amount::from_minor(123, 2));
+        assert_eq!(Amount::parse("500", 0).unwrap(), Amount::from_minor(500, 0));
+
+        assert_eq!(Amount::from_minor(-5, 2).to_string(), "-0.05");
+        assert_eq!(Amount::from_minor(1234, 3).to_string(), "1.234");
+        assert_eq!(Amount::from_minor(500, 0).to_string(), "500");
+    }
+
+    #[test]
+    fn parse_rejects_lossy_and_garbage() {
+        assert!(Amount::parse("12.345", 2).is_err());
+        assert!(Amount::parse("1.5", 0).is_err());
+        assert!(Amount::parse("", 2).is_err());
+        assert!(Amount::parse("-", 2).is_err());
+        assert!(Amount::parse("1e5", 2).is_err());
+        assert!(Amount::parse("99999999999999999999", 2).is_err());
+    }
+
+    #[test]
+    fn currency_scale() {
+        assert_eq!(Money::parse("100", "JPY".parse().unwrap()).unwrap().amount(), Amount::from_minor(100, 0));
+        assert_eq!(Money::parse("1.5", "KWD".parse().unwrap()).unwrap().amount(), Amount::from_minor(1500, 3));
+        assert!(Money::parse("1.5", "JPY".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn arithmetic_requires_same_currency() {
+        let usd = Money::parse("10.50", "USD".parse().unwrap()).unwrap();
+        let eur = Money::parse("1.00", "EUR".parse().unwrap()).unwrap();
+
+        assert_eq!(usd.checked_add(&usd).unwrap(), Money::from_minor(2100, usd.currency()));
+        assert_eq!(usd.checked_sub(&Money::from_minor(50, usd.currency())).unwrap(), Money::from_minor(1000, usd.currency()));
+        assert!(matches!(usd.checked_add(&eur), Err(ParserError::CurrencyMismatch(_, _))));
+    }
+
+    #[test]
+    fn equality_is_numeric() {
+        use std::collections::HashSet;
+
+        let short = Amount::parse("1.0", 1).unwrap();
+        let long = Amount::parse("1.00", 2).unwrap();
+        assert_eq!(short, long);
+        assert_ne!(short.to_string(), long.to_string());
+        assert_ne!(Amount::from_minor(10, 1), Amount::from_minor(10, 2));
+        assert_eq!(HashSet::from([short, long]).len(), 1);
+
+        let usd = Money::parse("1.5", "USD".parse().unwrap()).unwrap();
+        assert_eq!(usd.amount().exponent(), 2);
+        assert_eq!(usd.amount().to_string(), "1.50");
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const STATEMENT: &str = "{1:F01BANKDEFFAXXX0000000000}{2:I940BANKDEFFXXXXN}{4:\r\n\
+:20:STARTUMS\r\n\
+:25:10020030/1234567\r\n\
+:28C:12/1\r\n\
+:60F:C240229EUR1000,00\r\n\
+:61:2403010301D19,99NTRF1001//BANK-1\r\n\
+/OCMT/EUR19,99/\r\n\
+:86:Invoice 17, payment for \r\n\
+March\r\n\
+:61:2412311231CR5,NTRFNONREF\r\n\
+:62F:C241231EUR985,01\r\n\
+-}";
+
+    #[test]
+    fn read_statement() {
+        let mut reader = mt940::Mt940Parser::reader(STATEMENT.as_bytes());
+        let parsed = reader.by_ref().collect::<Result<Vec<_>, _>>().expect("mt940 read failed");
+
+        let mut debit = Transaction::new(1001, "10020030/1234567", Amount::from_minor(-1999, 2), "EUR".parse().unwrap());
+        debit.booking_date = "2024-03-01".parse().ok();
+        debit.value_date = "2024-03-01".parse().ok();
+        debit.description = Some("Invoice 17, payment for March".to_string());
+        debit.direction = Some(Direction::Debit);
+
+        // Нечисловая ссылка: идентификатор — номер записи после NONREF_ID; `R` — код средств.
+        let mut credit = Transaction::new(mt940::NONREF_ID + 2, "10020030/1234567", Amount::from_minor(500, 2), "EUR".parse().unwrap());
+        credit.booking_date = "2024-12-31".parse().ok();
+        credit.value_date = "2024-12-31".parse().ok();
+        credit.direction = Some(Direction::Credit);
+
+        assert_eq!(parsed, [debit, credit]);
+
+        let statement = &reader.statements()[0];
+        assert_eq!(statement.reference, "STARTUMS");
+        assert_eq!(statement.number.as_deref(), Some("12/1"));
+        assert_eq!(statement.opening.map(|balance| balance.amount), Some(Amount::from_minor(100000, 2)));
+        let closing = statement.closing.expect("closing balance");
+        assert_eq!((closing.date, closing.amount), ("2024-12-31".parse().unwrap(), Amount::from_minor(98501, 2)));
+
+        assert_eq!(FormatKind::sniff(STATEMENT.as_bytes()), Some(FormatKind::Mt940));
+    }
+
+    #[test]
+    fn entry_errors_are_positioned() {
+        let data = ":20:S\n:25:ACC\n:60F:C240301USD0,\n:61:240301X1,00NTRF1\n:61:240301C2,00NTRF2\n:62F:C240301USD2,00\n-\n";
+
+        let report = mt940::Mt940Parser::read_lenient(data.as_bytes(), Lenient::new()).expect("mt940 read failed");
+        assert_eq!(report.transactions.len(), 1);
+        assert_eq!(report.transactions[0].id, 2);
+
+        let error = report.rejected[0].error.record().expect("record error");
+        assert_eq!(error.field, Some("direction"));
+        assert_eq!(error.position.line, Some(4));
+        assert_eq!(error.raw.as_deref(), Some("240301X1,00NTRF1"));
+    }
+
+    #[test]
+    fn write_statement() {
+        let mut tx = Transaction::new(7, "DE89370400440532013000", Amount::from_minor(-1999, 2), "EUR".parse().unwrap());
+        tx.value_date = "2024-03-02".parse().ok();
+        tx.booking_date = "2024-03-01".parse().ok();
+        tx.description = Some("x".repeat(70));
+        let mut refund = Transaction::new(8, "DE89370400440532013000", Amount::from_minor(5, 0), "EUR".parse().unwrap());
+        refund.value_date = "2024-03-05".parse().ok();
+        let data = [tx, refund];
+
+        let mut buf = Vec::new();
+        mt940::Mt940Parser::write(&mut buf, &data).expect("mt940 write failed");
+        let text = String::from_utf8(buf).expect("mt940 is not UTF-8");
+
+        assert!(text.contains(":60F:C240302EUR0,00\n:61:2403020301D19,99NTRF7\n"));
+        assert!(text.contains(&format!(":86:{}\n{}\n", "x".repeat(65), "x".repeat(5))));
+        assert!(text.ends_with(":62F:D240305EUR14,99\n-\n"));
+
+        let parsed = mt940::Mt940Parser::read(text.as_bytes()).expect("mt940 read failed");
+        assert_eq!(parsed[0].description, data[0].description);
+        assert_eq!(parsed[1].amount, Amount::from_minor(500, 2));
+
+        let too_long = Transaction::new(u64::MAX, "ACC", Amount::from_minor(1, 2), "USD".parse().unwrap());
+        assert!(mt940::Mt940Parser::write(Vec::new(), &[too_long]).is_err());
+    }
+
+    #[test]
+    fn description_lines() {
+        let mut tx = Transaction::new(1, "ACC", Amount::from_minor(100, 2), "EUR".parse().unwrap());
+        tx.value_date = "2024-03-01".parse().ok();
+
+        // Перенос сдвигается так, чтобы строка продолжения не начиналась с `-`, `:` или пробела.
+        let description = format!("{}-:  x{}", "a".repeat(63), "b".repeat(70));
+        tx.description = Some(description.clone());
+        let mut buf = Vec::new();
+        mt940::Mt940Parser::write(&mut buf, std::slice::from_ref(&tx)).expect("mt940 write failed");
+        let text = String::from_utf8(buf).expect("mt940 is not UTF-8");
+
+        let lines: Vec<&str> = text.lines().skip_while(|line| !line.starts_with(":86:")).take(4).collect();
+        assert_eq!(lines[0], format!(":86:{}", "a".repeat(62)));
+        assert_eq!(lines[1], format!("a-:  x{}", "b".repeat(59)));
+        assert_eq!(lines[2], "b".repeat(11));
+        assert!(lines[3].starts_with(":62F:"));
+
+        let parsed = mt940::Mt940Parser::read(text.as_bytes()).expect("mt940 read failed");
+        assert_eq!(parsed[0].description.as_deref(), Some(description.as_str()));
+
+        tx.description = Some("x".repeat(6 * 65 + 1));
+        let err = mt940::Mt940Parser::write(Vec::new(), std::slice::from_ref(&tx)).unwrap_err();
+        assert!(err.to_string().contains("does not fit into 6 lines"), "{}", err);
+
+        tx.description = Some("Оплата".to_string());
+        let err = mt940::Mt940Parser::write(Vec::new(), &[tx]).unwrap_err();
+        assert!(err.to_string().contains("not allowed in MT940"), "{}", err);
+    }
+
+    #[test]
+    fn nonref_ids_do_not_collide() {
+        let data = ":20:S\n:25:ACC\n:60F:C240301USD0,\n:61:240301C1,00NTRF2\n:61:240301C2,00NTRFNONREF\n:62F:C240301USD3,00\n-\n";
+
+        let parsed = mt940::Mt940Parser::read(data.as_bytes()).expect("mt940 read failed");
+        let ids: Vec<u64> = parsed.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, [2, mt940::NONREF_ID + 2]);
+
+        let mut buf = Vec::new();
+        mt940::Mt940Parser::write(&mut buf, &parsed).expect("mt940 write failed");
+        let text = String::from_utf8(buf).expect("mt940 is not UTF-8");
+        assert!(text.contains("C2,00NTRFNONREF\n"), "{}", text);
+        assert_eq!(mt940::Mt940Parser::read(text.as_bytes()).expect("mt940 read failed"), parsed);
+    }
+
+    #[test]
+    fn undated_statement_uses_writer_date() {
+        let tx = Transaction::new(1, "ACC", Amount::from_minor(100, 2), "EUR".parse().unwrap());
+
+        let mut buf = Vec::new();
+        let mut writer = mt940::Mt940Writer::with_date(&mut buf, "2024-03-01".parse().unwrap());
+        writer.write(&tx).expect("mt940 write failed");
+        writer.finish().expect("mt940 finish failed");
+        drop(writer);
+        let text = String::from_utf8(buf).expect("mt940 is not UTF-8");
+
+        assert!(text.contains(":60F:C240301EUR0,00\n:61:240301C1,00NTRF1\n"), "{}", text);
+        assert!(text.ends_with(":62F:C240301EUR1,00\n-\n"), "{}", text);
+    }
+
+    #[test]
+    fn multibyte_tag_name_is_not_a_tag() {
+        let data = ":20:STMT1\n:25:ACC\n:1\u{e9}:x\n:60F:C240301USD0,\n:61:240301C1,00NTRF1\n-\n";
+
+        let parsed = mt940::Mt940Parser::read(data.as_bytes()).expect("mt940 read failed");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, 1);
+    }
+}
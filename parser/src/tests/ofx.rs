@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const SGML: &[u8] = b"OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nENCODING:USASCII\r\nCHARSET:1252\r\n\r\n\
+<OFX>\r\n<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20240301</SONRS></SIGNONMSGSRSV1>\r\n\
+<BANKMSGSRSV1><STMTTRNRS><TRNUID>1<STMTRS><CURDEF>EUR\r\n\
+<BANKACCTFROM><BANKID>123<ACCTID>DE89370400440532013000<ACCTTYPE>CHECKING</BANKACCTFROM>\r\n\
+<BANKTRANLIST><DTSTART>20240301<DTEND>20240331\r\n\
+<STMTTRN><TRNTYPE>POS<DTPOSTED>20240301120000.000[-5:EST]<TRNAMT>-19.99<FITID>1001\r\n\
+<NAME>Caf\xe9 &amp; Bar<MEMO>Lunch</STMTTRN>\r\n\
+<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20240302<TRNAMT>5.00<FITID>1002<NAME>Refund\r\n\
+<BANKACCTTO><BANKID>1<ACCTID>GB33BUKB20201555555555<ACCTTYPE>SAVINGS</BANKACCTTO></STMTTRN>\r\n\
+</BANKTRANLIST><LEDGERBAL><BALAMT>-14.99<DTASOF>20240331</LEDGERBAL></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>\r\n";
+
+    #[test]
+    fn read_sgml() {
+        let parsed = ofx::OfxParser::read(SGML).expect("ofx read failed");
+
+        let mut debit = Transaction::new(1001, "DE89370400440532013000", Amount::from_minor(-1999, 2), "EUR".parse().unwrap());
+        debit.booking_date = "2024-03-01".parse().ok();
+        debit.description = Some("Lunch".to_string());
+        debit.direction = Some(Direction::Debit);
+
+        let mut credit = Transaction::new(1002, "DE89370400440532013000", Amount::from_minor(500, 2), "EUR".parse().unwrap());
+        credit.booking_date = "2024-03-02".parse().ok();
+        credit.description = Some("Refund".to_string());
+        credit.counterparty = Some("GB33BUKB20201555555555".to_string());
+        credit.direction = Some(Direction::Credit);
+
+        assert_eq!(parsed, [debit, credit]);
+        assert_eq!(FormatKind::sniff(SGML), Some(FormatKind::Ofx));
+    }
+
+    #[test]
+    fn sgml_charset_and_errors() {
+        let replace = |data: &[u8], from: &str, to: &str| {
+            let at = data.windows(from.len()).position(|w| w == from.as_bytes()).expect("pattern not found");
+            [&data[..at], to.as_bytes(), &data[at + from.len()..]].concat()
+        };
+        let data = replace(&replace(&replace(SGML, "<MEMO>Lunch", ""), "1001", "R-1"), "5.00", "x");
+
+        let report = ofx::OfxParser::read_lenient(data.as_slice(), Lenient::new()).expect("ofx read failed");
+        assert_eq!(report.transactions.len(), 1);
+        assert_eq!(report.transactions[0].id, mt940::NONREF_ID + 1);
+        assert_eq!(report.transactions[0].description.as_deref(), Some("Café & Bar"));
+
+        let error = report.rejected[0].error.record().expect("record error");
+        assert_eq!(error.field, Some("TRNAMT"));
+        assert_eq!(error.value.as_deref(), Some("x"));
+        assert_eq!(error.position.record, Some(2));
+    }
+
+    #[test]
+    fn write_ofx2() {
+        let mut tx = Transaction::new(7, "ACC <1>", Amount::from_minor(-250, 2), "USD".parse().unwrap());
+        tx.booking_date = "2024-03-01".parse().ok();
+        tx.value_date = "2024-03-03".parse().ok();
+        tx.description = Some("Fee & tax".to_string());
+        let data = [tx, Transaction::new(8, "ACC <1>", Amount::from_minor(1000, 2), "USD".parse().unwrap())];
+
+        let mut buf = Vec::new();
+        ofx::OfxParser::write(&mut buf, &data).expect("ofx write failed");
+        let xml = String::from_utf8(buf).expect("ofx is not UTF-8");
+
+        assert!(xml.contains("<?OFX OFXHEADER=\"200\" VERSION=\"220\""));
+        assert!(xml.contains("<ACCTID>ACC &lt;1&gt;</ACCTID>"));
+        assert!(xml.contains("<LEDGERBAL><BALAMT>7.50</BALAMT>"));
+        assert_eq!(FormatKind::sniff(xml.as_bytes()), Some(FormatKind::Ofx));
+
+        let parsed = ofx::OfxParser::read(xml.as_bytes()).expect("ofx read failed");
+        assert_eq!(parsed[0].account, "ACC <1>");
+        assert_eq!(parsed[0].description.as_deref(), Some("Fee & tax"));
+        assert_eq!(parsed[0].value_date, data[0].value_date);
+        assert_eq!(parsed[1].amount, data[1].amount);
+    }
+
+    #[test]
+    fn undated_statement_uses_writer_time() {
+        let tx = Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap());
+        let created = "2024-03-01T12:30:05".parse().unwrap();
+
+        let mut buf = Vec::new();
+        let mut writer = ofx::OfxWriter::with_time(&mut buf, created);
+        writer.write(&tx).expect("ofx write failed");
+        writer.finish().expect("ofx finish failed");
+        drop(writer);
+        let xml = String::from_utf8(buf).expect("ofx is not UTF-8");
+
+        assert!(xml.contains("<DTSERVER>20240301123005</DTSERVER>"), "{}", xml);
+        assert!(xml.contains("<DTSTART>20240301</DTSTART>"), "{}", xml);
+
+        let parsed = ofx::OfxParser::read(xml.as_bytes()).expect("ofx read failed");
+        assert_eq!(parsed[0].booking_date, "2024-03-01".parse().ok());
+    }
+}
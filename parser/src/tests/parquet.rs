@@ -0,0 +1,99 @@
+#[cfg(all(test, feature = "parquet"))]
+mod tests {
+    use crate::*;
+    use std::sync::Arc;
+    use ::arrow::array::{ArrayRef, Decimal128Array, Int64Array, RecordBatch, StringArray};
+
+    fn sample() -> Vec<Transaction> {
+        let mut rent = Transaction::new(1, "DE89370400440532013000", Amount::from_minor(-125050, 2), "EUR".parse().unwrap());
+        rent.booking_date = "2024-01-31".parse().ok();
+        rent.value_date = "2024-02-01".parse().ok();
+        rent.description = Some("Rent, May".to_string());
+        rent.counterparty = Some("GB33BUKB20201555555555".to_string());
+        rent.direction = Some(Direction::Debit);
+        rent.status = Some(Status::Booked);
+
+        vec![
+            rent,
+            Transaction::new(2, "JP1", Amount::from_minor(5000, 0), "JPY".parse().unwrap()),
+            Transaction::new(3, "KW1", Amount::from_minor(1234, 3), "KWD".parse().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn record_batch_round() {
+        let data = sample();
+
+        let batch = arrow::to_record_batch(&data).expect("arrow conversion failed");
+        assert_eq!(batch.schema(), arrow::schema());
+        assert_eq!(batch.num_rows(), 3);
+
+        assert_eq!(arrow::from_record_batch(&batch).expect("arrow read failed"), data);
+    }
+
+    #[test]
+    fn record_batch_casts_and_errors() {
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            ("id", Arc::new(Int64Array::from(vec![7, 8]))),
+            ("account", Arc::new(StringArray::from(vec!["A", "B"]))),
+            ("amount", Arc::new(Decimal128Array::from(vec![1990, 1]).with_precision_and_scale(10, 3).unwrap())),
+            ("currency", Arc::new(StringArray::from(vec!["USD", "USD"]))),
+            ("extra", Arc::new(StringArray::from(vec!["x", "y"]))),
+        ];
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+
+        let rows: Vec<_> = arrow::from_record_batch(&batch.slice(0, 1)).expect("arrow read failed");
+        assert_eq!(rows, [Transaction::new(7, "A", Amount::from_minor(199, 2), "USD".parse().unwrap())]);
+
+        // 0.001 USD нельзя представить в центах.
+        let err = arrow::from_record_batch(&batch).unwrap_err();
+        let record = err.record().expect("record error");
+        assert_eq!(record.position.record, Some(2));
+        assert_eq!(record.field, Some("amount"));
+        assert_eq!(record.value.as_deref(), Some("0.001"));
+
+        let batch = batch.project(&[0, 1, 3]).unwrap();
+        let err = arrow::from_record_batch(&batch).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid: Missing column: amount");
+    }
+
+    #[test]
+    fn parquet_round() {
+        let mut data = sample();
+        data.extend((4..20_000).map(|id| Transaction::new(id, "ACC", Amount::from_minor(id as i64, 2), "USD".parse().unwrap())));
+
+        let mut buf = Vec::new();
+        parquet::Parquet::write(&mut buf, &data).expect("parquet write failed");
+        assert!(buf.starts_with(&parquet::MAGIC) && buf.ends_with(&parquet::MAGIC));
+
+        let registry = Registry::default();
+        let (format, stream) = registry.detect(None, buf.as_slice()).expect("detect failed");
+        assert_eq!(format.name(), "parquet");
+
+        let parsed = format.reader(Box::new(stream)).collect::<Result<Vec<_>, _>>().expect("parquet read failed");
+        assert_eq!(parsed, data);
+
+        let err = parquet::Parquet::read(&b"PAR1 broken"[..]).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid: Parquet error"), "{}", err);
+    }
+
+    #[test]
+    fn writer_rejects_amount_immediately() {
+        let mut buf = Vec::new();
+        let mut writer = <parquet::Parquet as Format>::writer(&mut buf);
+
+        let err = writer.write(&Transaction::new(1, "A", Amount::from_minor(1, 6), "USD".parse().unwrap())).unwrap_err();
+        assert!(err.to_string().contains("cannot be stored with 4 decimal places"), "{}", err);
+
+        let valid = Transaction::new(2, "A", Amount::from_minor(100, 2), "USD".parse().unwrap());
+        writer.write(&valid).expect("parquet write failed");
+        writer.finish().expect("parquet finish failed");
+        writer.finish().expect("second parquet finish failed");
+        assert!(writer.write(&valid).is_err());
+        drop(writer);
+
+        assert_eq!(buf.windows(parquet::MAGIC.len()).filter(|w| *w == parquet::MAGIC).count(), 2);
+
+        assert_eq!(parquet::Parquet::read(buf.as_slice()).expect("parquet read failed"), [valid]);
+    }
+}
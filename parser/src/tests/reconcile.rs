@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::csv::Column;
+
+    fn tx(id: u64, account: &str, minor_units: i64) -> Transaction {
+        Transaction::new(id, account, Amount::from_minor(minor_units, 2), "USD".parse().unwrap())
+    }
+
+    #[test]
+    fn matches_by_id_in_any_order() {
+        let ledger = vec![tx(1, "A", 100), tx(2, "A", 200), tx(3, "B", 300)];
+        let bank = vec![tx(3, "B", 300), tx(4, "C", 400), tx(2, "A", 250), tx(1, "A", 100)];
+
+        let result = reconcile(&ledger, &bank, &MatchKey::default());
+        assert!(!result.is_clean());
+        assert!(result.duplicates.is_empty());
+        assert_eq!(result.diff.unchanged, 2);
+        assert!(result.diff.removed.is_empty());
+        assert_eq!(result.diff.added.len(), 1);
+        assert_eq!(result.diff.added[0].index, 2);
+
+        let change = &result.diff.changed[0];
+        assert_eq!((change.left.index, change.right.index), (2, 3));
+        assert_eq!(change.fields.len(), 1);
+        assert_eq!(change.fields[0].field, Column::Amount);
+
+        let reordered: Vec<Transaction> = ledger.iter().rev().cloned().collect();
+        assert!(reconcile(&ledger, &reordered, &MatchKey::id()).is_clean());
+    }
+
+    #[test]
+    fn composite_key_and_duplicates() {
+        let key: MatchKey = "acct+amount+ccy".parse().expect("key failed");
+        assert_eq!(key.columns(), [Column::Account, Column::Amount, Column::Currency]);
+        assert_eq!(key.to_string(), "account+amount+currency");
+        assert!("account+bogus".parse::<MatchKey>().is_err());
+        assert!("id+id".parse::<MatchKey>().is_err());
+
+        let ledger = vec![tx(1, "A", 100), tx(2, "A", 100), tx(3, "B", 500)];
+        let bank = vec![tx(90, "A", 100), tx(91, "B", 500)];
+
+        let result = reconcile(&ledger, &bank, &key);
+        assert_eq!(result.duplicates.len(), 1);
+        let duplicate = &result.duplicates[0];
+        assert_eq!(duplicate.side, Side::Left);
+        assert_eq!(duplicate.key, "A|1.00|USD");
+        assert_eq!(duplicate.entries.iter().map(|entry| entry.index).collect::<Vec<_>>(), [1, 2]);
+
+        // Повторы сопоставляются по порядку: вторая запись ledger остаётся без пары.
+        assert_eq!(result.diff.removed.len(), 1);
+        assert_eq!(result.diff.removed[0].index, 2);
+        assert!(result.diff.added.is_empty());
+        assert_eq!(result.diff.changed.len(), 2);
+        assert!(result.diff.changed.iter().all(|change| change.fields.len() == 1 && change.fields[0].field == Column::Id));
+    }
+
+    #[test]
+    fn amounts_compare_by_value() {
+        let ledger = vec![Transaction::new(1, "A", Amount::parse("1.0", 1).unwrap(), "USD".parse().unwrap())];
+        let bank = vec![Transaction::new(2, "A", Amount::parse("1.00", 2).unwrap(), "USD".parse().unwrap())];
+
+        assert!(diff(&ledger, &bank).changed[0].fields.iter().all(|field| field.field == Column::Id));
+        let key: MatchKey = "account+amount".parse().expect("key failed");
+        assert!(reconcile(&ledger, &bank, &key).diff.removed.is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::path::Path;
+
+    #[test]
+    fn lookup() {
+        let registry = Registry::default();
+
+        assert_eq!(registry.by_name("csv").map(|f| f.name()), Some("csv"));
+        assert_eq!(registry.by_extension(".TXT").map(|f| f.name()), Some("txt"));
+        assert_eq!(registry.by_mime("text/csv; charset=utf-8").map(|f| f.name()), Some("csv"));
+        assert_eq!(registry.for_path(Path::new("a/b.bin")).map(|f| f.name()), Some("bin"));
+        assert!(registry.get("xml").is_err());
+
+        // Имя, расширения и разбор имени берутся из одной таблицы форматов.
+        for kind in FormatKind::ALL {
+            assert_eq!(kind.as_str().parse::<FormatKind>().unwrap(), kind);
+            for extension in kind.extensions() {
+                assert_eq!(FormatKind::from_extension(Path::new(&format!("a.{}", extension))), Some(kind));
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_round() {
+        let registry = Registry::default();
+        let mut tx = Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap());
+        tx.booking_date = "2024-03-01".parse().ok();
+        let data = vec![tx];
+
+        for name in registry.names() {
+            let format = registry.get(name).expect("format not registered");
+
+            let mut buf = Vec::new();
+            let mut writer = format.writer(Box::new(&mut buf));
+            writer.write(&data[0]).expect("write failed");
+            writer.finish().expect("finish failed");
+            drop(writer);
+
+            let (detected, stream) = registry.detect(None, buf.as_slice()).expect("detect failed");
+            assert_eq!(detected.name(), name);
+
+            let parsed = detected.reader(Box::new(stream)).collect::<Result<Vec<_>, _>>().expect("read failed");
+            assert_eq!(expected(name, &data), parsed);
+        }
+    }
+
+    #[test]
+    fn extended_fields_round() {
+        let registry = Registry::default();
+
+        let mut tx = Transaction::new(42, "DE89370400440532013000", Amount::from_minor(-1999, 2), "EUR".parse().unwrap());
+        tx.booking_date = "2024-03-01".parse().ok();
+        tx.value_date = "2024-03-02".parse().ok();
+        tx.description = Some("Invoice-17".to_string());
+        tx.counterparty = Some("GB33BUKB20201555555555".to_string());
+        tx.direction = Some(Direction::Debit);
+        tx.status = Some(Status::Booked);
+        let mut plain = Transaction::new(43, "ACC", Amount::from_minor(5, 2), "USD".parse().unwrap());
+        plain.booking_date = "2024-03-04".parse().ok();
+        let data = vec![tx, plain];
+
+        for name in registry.names() {
+            let format = registry.get(name).expect("format not registered");
+
+            let mut buf = Vec::new();
+            let mut writer = format.writer(Box::new(&mut buf));
+            for tx in &data {
+                writer.write(tx).expect("write failed");
+            }
+            writer.finish().expect("finish failed");
+            drop(writer);
+
+            let parsed = format.reader(Box::new(buf.as_slice())).collect::<Result<Vec<_>, _>>().expect("read failed");
+            assert_eq!(expected(name, &data), parsed, "format {}", name);
+        }
+    }
+
+    /// Банковские выписки всегда передают направление записи; camt — ещё
+    /// и статус, а MT940 не хранит статус и контрагента, но требует дату
+    /// валютирования (по умолчанию — дата проводки). OFX не хранит статус
+    /// и требует дату проводки. Записи тестов датированы, чтобы результат
+    /// не зависел от текущей даты.
+    fn expected(name: &str, data: &[Transaction]) -> Vec<Transaction> {
+        let mut data = data.to_vec();
+        for tx in &mut data {
+            let debit = tx.amount.minor_units() < 0;
+            match name {
+                "camt" => {
+                    tx.direction.get_or_insert(if debit { Direction::Debit } else { Direction::Credit });
+                    tx.status.get_or_insert(Status::Booked);
+                }
+                "mt940" => {
+                    tx.direction.get_or_insert(if debit { Direction::Debit } else { Direction::Credit });
+                    tx.status = None;
+                    tx.counterparty = None;
+                    tx.value_date = tx.value_date.or(tx.booking_date);
+                }
+                "ofx" => {
+                    tx.direction.get_or_insert(if debit { Direction::Debit } else { Direction::Credit });
+                    tx.status = None;
+                    tx.booking_date = tx.booking_date.or(tx.value_date);
+                }
+                _ => {}
+            }
+        }
+
+        data
+    }
+}
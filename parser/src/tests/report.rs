@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn tx(id: u64, account: &str, minor_units: i64) -> Transaction {
+        Transaction::new(id, account, Amount::from_minor(minor_units, 2), "USD".parse().unwrap())
+    }
+
+    fn render(report: &Report, format: ReportFormat) -> String {
+        let mut out = Vec::new();
+        report.write(format, &mut out).expect("report write failed");
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn structured_mismatches() {
+        let left = vec![tx(1, "A", 100), tx(2, "A", 200), tx(3, "B", 300)];
+        let right = vec![tx(2, "A", 250), tx(1, "A", 100), tx(4, "C", 400), tx(4, "C", 400)];
+
+        let report = Report::from_reconciliation(&reconcile(&left, &right, &MatchKey::id()));
+        assert!(!report.identical);
+        assert_eq!(report.summary, Summary { matched: 1, near_matches: 0, changed: 1, only_in_file1: 1, only_in_file2: 2, duplicate_keys: 1 });
+
+        let kinds: Vec<MismatchKind> = report.mismatches.iter().map(|mismatch| mismatch.kind).collect();
+        assert_eq!(
+            kinds,
+            [MismatchKind::DuplicateKey, MismatchKind::OnlyInFile1, MismatchKind::OnlyInFile2, MismatchKind::OnlyInFile2, MismatchKind::Changed]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&render(&report, ReportFormat::Json)).unwrap();
+        assert_eq!(json["summary"]["changed"], 1);
+        assert_eq!(json["mismatches"][0]["key"], "4");
+        assert_eq!(json["mismatches"][4]["fields"][0]["field"], "amount");
+        assert_eq!(json["mismatches"][4]["fields"][0]["file2"], "2.50");
+
+        let csv = render(&report, ReportFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "kind,file1_records,file2_records,key,field,file1_value,file2_value,confidence,difference");
+        assert_eq!(lines[1], "duplicate_key,,3;4,4,,,,,");
+        assert_eq!(lines[5], "changed,2,1,,amount,2.00,2.50,,");
+    }
+
+    #[test]
+    fn junit_and_identical_files() {
+        let left = vec![tx(1, "A", 100), tx(2, "A", 200)];
+        let right = vec![tx(1, "A", 100)];
+
+        let junit = render(&Report::from_reconciliation(&reconcile(&left, &right, &MatchKey::id())), ReportFormat::Junit);
+        assert!(junit.starts_with("<?xml"));
+        assert!(junit.contains("<testsuite name=\"comparer\" tests=\"2\" failures=\"1\">"));
+        assert!(junit.contains("<testcase classname=\"comparer\" name=\"1 matching records\"/>"));
+        assert!(junit.contains("<failure type=\"only_in_file1\" message=\"Only in file1, record 2\"/>"));
+
+        let report = Report::from_reconciliation(&Reconciliation { diff: diff(&right, &right), duplicates: Vec::new() });
+        assert!(report.identical);
+        assert!(report.mismatches.is_empty());
+        assert!(render(&report, ReportFormat::Junit).contains("failures=\"0\""));
+
+        assert_eq!("junit".parse::<ReportFormat>().unwrap(), ReportFormat::Junit);
+        assert!("html".parse::<ReportFormat>().is_err());
+    }
+
+    #[test]
+    fn fuzzy_matches() {
+        let ledger = vec![tx(1, "A", 100), tx(2, "A", 300), tx(3, "A", 200), tx(4, "B", 50)];
+        let bank = vec![tx(10, "A", 101), tx(11, "A", 500)];
+        let options = FuzzyOptions::new().amount_tolerance("0.01".parse().unwrap()).max_group(2);
+
+        let report = Report::from_fuzzy(&reconcile_fuzzy(&ledger, &bank, &options));
+        assert!(!report.identical);
+        assert_eq!((report.summary.matched, report.summary.near_matches, report.summary.only_in_file1), (1, 1, 1));
+        assert_eq!(report.matches.len(), 2);
+        assert_eq!(report.matches[1].file1_records, [2, 3]);
+        assert_eq!(report.matches[1].file2_records, [2]);
+
+        let json: serde_json::Value = serde_json::from_str(&render(&report, ReportFormat::Json)).unwrap();
+        assert_eq!(json["summary"]["near_matches"], 1);
+        assert_eq!(json["matches"][0]["difference"], "-0.01");
+        assert_eq!(json["matches"][1]["confidence"], 0.8);
+
+        let csv = render(&report, ReportFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[2], "near_match,1,1,,,,,0.5500,-0.01");
+        assert_eq!(lines[3], "match,2;3,2,,,,,0.8000,0.00");
+
+        let junit = render(&report, ReportFormat::Junit);
+        assert!(junit.contains("<testsuite name=\"comparer\" tests=\"3\" failures=\"1\">"), "{}", junit);
+        assert!(junit.contains("<testcase classname=\"comparer.match\" name=\"file2 record 2\">"), "{}", junit);
+        assert!(junit.contains("Matched file1 records 2, 3 -&gt; file2 record 2"), "{}", junit);
+    }
+}
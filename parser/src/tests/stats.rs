@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn totals_per_currency() {
+        let usd: Currency = "USD".parse().unwrap();
+        let mut first = Transaction::new(1, "B", Amount::from_minor(10_000, 2), usd);
+        first.booking_date = NaiveDate::from_ymd_opt(2024, 3, 5);
+        let mut second = Transaction::new(2, "A", Amount::from_minor(-2_550, 2), usd);
+        second.value_date = NaiveDate::from_ymd_opt(2024, 2, 1);
+        let third = Transaction::new(3, "A", Amount::from_minor(7, 0), "JPY".parse().unwrap());
+
+        let mut stats = Stats::new();
+        for tx in [&first, &second, &third] {
+            stats.add(tx).unwrap();
+        }
+
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.accounts.iter().collect::<Vec<_>>(), ["A", "B"]);
+        assert_eq!(stats.first_date, NaiveDate::from_ymd_opt(2024, 2, 1));
+        assert_eq!(stats.last_date, NaiveDate::from_ymd_opt(2024, 3, 5));
+
+        let codes: Vec<&str> = stats.currencies.keys().map(Currency::code).collect();
+        assert_eq!(codes, ["JPY", "USD"]);
+        let totals = stats.currencies[&usd];
+        assert_eq!(totals.count, 2);
+        assert_eq!(totals.credits.to_string(), "100.00");
+        assert_eq!(totals.debits.to_string(), "-25.50");
+        assert_eq!(totals.net().unwrap().to_string(), "74.50");
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let usd: Currency = "USD".parse().unwrap();
+        let mut stats = Stats::new();
+        stats.add(&Transaction::new(1, "A", Amount::from_minor(i64::MAX, 2), usd)).unwrap();
+        assert!(stats.add(&Transaction::new(2, "A", Amount::from_minor(1, 2), usd)).is_err());
+        assert_eq!(stats.count, 1);
+    }
+}
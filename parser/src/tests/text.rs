@@ -5,12 +5,7 @@ mod tests {
 
     #[test]
     fn csv_round() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -25,12 +20,7 @@ mod tests {
 
     #[test]
     fn csv_round_ne() {
-        let data = vec![Transaction {
-            id: 1,
-            account: "ACC".to_string(),
-            amount: 100,
-            currency: "USD".to_string(),
-        }];
+        let data = vec![Transaction::new(1, "ACC", Amount::from_minor(100, 2), "USD".parse().unwrap())];
 
         let mut buf = Cursor::new(Vec::new());
 
@@ -39,13 +29,75 @@ mod tests {
         buf.set_position(0);
 
         let mut parsed = txt::TxtParser::read(&mut buf).expect("csv read failed");
-        parsed.push(Transaction{
-            id: 0,
-            account: "".to_string(),
-            amount: 0,
-            currency: "".to_string(),
-        });
+        parsed.push(Transaction::new(0, "", Amount::from_minor(0, 2), "EUR".parse().unwrap()));
 
         assert_ne!(data, parsed);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn stream_round() {
+        let data = vec![
+            Transaction::new(1, "ACC1", Amount::from_minor(100, 2), "USD".parse().unwrap()),
+            Transaction::new(2, "ACC2", Amount::from_minor(-50, 2), "EUR".parse().unwrap()),
+        ];
+
+        let mut buf = Vec::new();
+        let mut writer = txt::TxtParser::writer(&mut buf);
+        for tx in &data {
+            writer.write(tx).expect("txt write failed");
+        }
+        writer.finish().expect("txt finish failed");
+
+        let mut reader = txt::TxtParser::reader(buf.as_slice());
+        assert_eq!(reader.next().unwrap().expect("txt read failed"), data[0]);
+        assert_eq!(reader.next().unwrap().expect("txt read failed"), data[1]);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn quoted_values_round() {
+        let mut tx = Transaction::new(1, "DE89 3704 0044", Amount::from_minor(100, 2), "USD".parse().unwrap());
+        tx.description = Some("Rent \"May\"\n\tC:\\docs # not a comment".to_string());
+        tx.counterparty = Some(String::new());
+        let data = vec![tx, Transaction::new(2, "#ACC", Amount::from_minor(1, 2), "EUR".parse().unwrap())];
+
+        let mut buf = Vec::new();
+        txt::TxtParser::write(&mut buf, &data).expect("txt write failed");
+        assert!(buf.starts_with(b"1 \"DE89 3704 0044\" 1.00 USD"));
+
+        let parsed = txt::TxtParser::read(buf.as_slice()).expect("txt read failed");
+        assert_eq!(data, parsed);
+    }
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let data = "# export\n\n1 \"ACC 1\" 1.00 USD  # first\n   \n2 ACC2 2.00 EUR \"description=a b\"\n";
+
+        let parsed = txt::TxtParser::read(data.as_bytes()).expect("txt read failed");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].account, "ACC 1");
+        assert_eq!(parsed[1].description.as_deref(), Some("a b"));
+
+        let err = txt::TxtParser::read("\n3 \"ACC 1.00 USD\n".as_bytes()).unwrap_err();
+        let err = err.record().expect("record error");
+        assert_eq!(err.position.line, Some(2));
+        assert_eq!(err.position.record, Some(1));
+        assert_eq!(err.position.column, Some(3));
+        assert_eq!(err.reason, "unterminated quote");
+    }
+
+    #[test]
+    fn amounts_written_in_currency_scale() {
+        let data = vec![
+            Transaction::new(1, "ACC", Amount::parse("1.5", 1).unwrap(), "USD".parse().unwrap()),
+            Transaction::new(2, "ACC", Amount::parse("1.000", 3).unwrap(), "JPY".parse().unwrap()),
+        ];
+
+        let mut buf = Vec::new();
+        txt::TxtParser::write(&mut buf, &data).expect("txt write failed");
+        assert_eq!(String::from_utf8(buf).unwrap(), "1 ACC 1.50 USD\n2 ACC 1 JPY\n");
+
+        let lossy = vec![Transaction::new(1, "ACC", Amount::parse("1.005", 3).unwrap(), "USD".parse().unwrap())];
+        assert!(txt::TxtParser::write(&mut Vec::new(), &lossy).is_err());
+    }
+}